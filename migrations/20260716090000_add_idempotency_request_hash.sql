@@ -0,0 +1,4 @@
+-- Fingerprint of the request payload that claimed the key, used to reject
+-- reuse of a key with a different body.
+ALTER TABLE idempotency
+    ADD COLUMN request_hash TEXT;
@@ -0,0 +1,13 @@
+-- Swiss postal code (PLZ/NPA), derived from the address at insert time.
+-- Nullable: not every stored address carries a recognisable postal code.
+ALTER TABLE farms
+    ADD COLUMN postal_code SMALLINT
+        CHECK (postal_code BETWEEN 1000 AND 9999);
+
+-- Backfill existing rows with the last standalone four-digit number in the
+-- address, mirroring `PostalCode::from_address`.
+UPDATE farms
+SET postal_code = substring(address FROM '.*\m([1-9][0-9]{3})\M')::smallint
+WHERE postal_code IS NULL;
+
+CREATE INDEX farms_postal_code_idx ON farms (postal_code);
@@ -0,0 +1,8 @@
+-- Organic / production certifications held by a farm, stored as the codes of
+-- `Certification` (e.g. 'bio_suisse'). NULL when none were recorded.
+ALTER TABLE farms
+    ADD COLUMN certifications TEXT[]
+        CHECK (certifications <@ ARRAY['bio_suisse', 'demeter', 'ip_suisse']);
+
+-- `GET /farms?certification=` matches with the array overlap operator.
+CREATE INDEX farms_certifications_idx ON farms USING GIN (certifications);
@@ -0,0 +1,25 @@
+-- Append-only record of write operations: who did what to which farm, and in
+-- which request. No foreign keys, so entries outlive the rows they describe.
+CREATE TABLE audit_log (
+    id UUID PRIMARY KEY,
+    action TEXT NOT NULL,
+    farm_id UUID NOT NULL,
+    user_id UUID NOT NULL,
+    payload JSONB NOT NULL,
+    -- The `TracingLogger` request id; NULL for writes made outside a request.
+    request_id UUID,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX audit_log_farm_id_idx ON audit_log (farm_id);
+
+CREATE FUNCTION audit_log_reject_changes() RETURNS trigger
+LANGUAGE plpgsql AS $$
+BEGIN
+    RAISE EXCEPTION 'audit_log is append-only';
+END
+$$;
+
+CREATE TRIGGER audit_log_append_only
+    BEFORE UPDATE OR DELETE ON audit_log
+    FOR EACH ROW EXECUTE FUNCTION audit_log_reject_changes();
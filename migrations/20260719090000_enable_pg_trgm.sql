@@ -0,0 +1,4 @@
+-- Trigram similarity, used to warn about likely duplicate farm names.
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+CREATE INDEX farms_name_trgm_idx ON farms USING GIN (name gin_trgm_ops);
@@ -0,0 +1,5 @@
+-- A farm cannot have been updated before it was created, whatever the clock
+-- of the writer says.
+ALTER TABLE farms
+    ADD CONSTRAINT farms_updated_at_after_created_at
+        CHECK (updated_at IS NULL OR updated_at >= created_at);
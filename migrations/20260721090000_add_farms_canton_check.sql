@@ -0,0 +1,8 @@
+-- Only the 26 official canton codes, as accepted by `Canton::parse`, so a
+-- write that bypasses it cannot store anything else.
+ALTER TABLE farms
+    ADD CONSTRAINT farms_canton_valid
+        CHECK (canton IN (
+            'AG', 'AI', 'AR', 'BE', 'BL', 'BS', 'FR', 'GE', 'GL', 'GR', 'JU', 'LU', 'NE',
+            'NW', 'OW', 'SG', 'SH', 'SO', 'SZ', 'TG', 'TI', 'UR', 'VD', 'VS', 'ZG', 'ZH'
+        ));
@@ -0,0 +1,5 @@
+-- The stored key is at most `MAX_STORED_KEY_LENGTH` bytes once namespaced, the
+-- same limit the Redis and memory engines are held to.
+ALTER TABLE idempotency
+    ADD CONSTRAINT idempotency_key_length
+        CHECK (octet_length(key) <= 200);
@@ -0,0 +1,44 @@
+-- The 26 cantons with their official names, mirroring `CANTON_NAMES` in
+-- `domain::farm::canton`, so queries can fetch a localized name by joining.
+CREATE TABLE cantons (
+    code    TEXT PRIMARY KEY,
+    name_en TEXT NOT NULL,
+    name_de TEXT NOT NULL,
+    name_fr TEXT NOT NULL,
+    name_it TEXT NOT NULL,
+    name_rm TEXT NOT NULL
+);
+
+INSERT INTO cantons (code, name_en, name_de, name_fr, name_it, name_rm) VALUES
+    ('AG', 'Aargau', 'Aargau', 'Argovie', 'Argovia', 'Argovia'),
+    ('AI', 'Appenzell Innerrhoden', 'Appenzell Innerrhoden', 'Appenzell Rhodes-Intérieures', 'Appenzello Interno', 'Appenzell Dadens'),
+    ('AR', 'Appenzell Ausserrhoden', 'Appenzell Ausserrhoden', 'Appenzell Rhodes-Extérieures', 'Appenzello Esterno', 'Appenzell Dadora'),
+    ('BE', 'Bern', 'Bern', 'Berne', 'Berna', 'Berna'),
+    ('BL', 'Basel-Landschaft', 'Basel-Landschaft', 'Bâle-Campagne', 'Basilea Campagna', 'Basilea-Champagna'),
+    ('BS', 'Basel-Stadt', 'Basel-Stadt', 'Bâle-Ville', 'Basilea Città', 'Basilea-Citad'),
+    ('FR', 'Fribourg', 'Freiburg', 'Fribourg', 'Friburgo', 'Friburg'),
+    ('GE', 'Geneva', 'Genf', 'Genève', 'Ginevra', 'Genevra'),
+    ('GL', 'Glarus', 'Glarus', 'Glaris', 'Glarona', 'Glaruna'),
+    ('GR', 'Graubünden', 'Graubünden', 'Grisons', 'Grigioni', 'Grischun'),
+    ('JU', 'Jura', 'Jura', 'Jura', 'Giura', 'Giura'),
+    ('LU', 'Lucerne', 'Luzern', 'Lucerne', 'Lucerna', 'Lucerna'),
+    ('NE', 'Neuchâtel', 'Neuenburg', 'Neuchâtel', 'Neuchâtel', 'Neuchâtel'),
+    ('NW', 'Nidwalden', 'Nidwalden', 'Nidwald', 'Nidvaldo', 'Sutsilvania'),
+    ('OW', 'Obwalden', 'Obwalden', 'Obwald', 'Obvaldo', 'Sursilvania'),
+    ('SG', 'St. Gallen', 'St. Gallen', 'Saint-Gall', 'San Gallo', 'Son Gagl'),
+    ('SH', 'Schaffhausen', 'Schaffhausen', 'Schaffhouse', 'Sciaffusa', 'Schaffusa'),
+    ('SO', 'Solothurn', 'Solothurn', 'Soleure', 'Soletta', 'Soloturn'),
+    ('SZ', 'Schwyz', 'Schwyz', 'Schwytz', 'Svitto', 'Sviz'),
+    ('TG', 'Thurgau', 'Thurgau', 'Thurgovie', 'Turgovia', 'Turgovia'),
+    ('TI', 'Ticino', 'Tessin', 'Tessin', 'Ticino', 'Tessin'),
+    ('UR', 'Uri', 'Uri', 'Uri', 'Uri', 'Uri'),
+    ('VD', 'Vaud', 'Waadt', 'Vaud', 'Vaud', 'Vad'),
+    ('VS', 'Valais', 'Wallis', 'Valais', 'Vallese', 'Vallais'),
+    ('ZG', 'Zug', 'Zug', 'Zoug', 'Zugo', 'Zug'),
+    ('ZH', 'Zurich', 'Zürich', 'Zurich', 'Zurigo', 'Turitg');
+
+-- The reference table now guards `farms.canton`. The foreign key keeps the
+-- check's name, which the API maps to a validation error.
+ALTER TABLE farms DROP CONSTRAINT farms_canton_valid;
+ALTER TABLE farms
+    ADD CONSTRAINT farms_canton_valid FOREIGN KEY (canton) REFERENCES cantons (code);
@@ -0,0 +1,61 @@
+-- Each canton's capital, mirroring `CANTON_CAPITALS` in `domain::farm::canton`,
+-- so queries can measure distances to capitals by joining.
+ALTER TABLE cantons
+    ADD COLUMN capital_lat FLOAT8,
+    ADD COLUMN capital_lon FLOAT8;
+
+UPDATE cantons c
+SET capital_lat = v.latitude, capital_lon = v.longitude
+FROM (VALUES
+    ('AG', 47.3925, 8.0457),
+    ('AI', 47.3316, 9.4094),
+    ('AR', 47.3859, 9.2792),
+    ('BE', 46.9481, 7.4474),
+    ('BL', 47.4814, 7.7343),
+    ('BS', 47.5596, 7.5886),
+    ('FR', 46.8063, 7.1608),
+    ('GE', 46.2044, 6.1432),
+    ('GL', 47.0404, 9.0679),
+    ('GR', 46.8499, 9.5331),
+    ('JU', 47.3653, 7.3453),
+    ('LU', 47.0502, 8.3093),
+    ('NE', 46.9896, 6.9294),
+    ('NW', 46.9579, 8.3659),
+    ('OW', 46.8960, 8.2461),
+    ('SG', 47.4245, 9.3767),
+    ('SH', 47.6979, 8.6344),
+    ('SO', 47.2084, 7.5371),
+    ('SZ', 47.0207, 8.6532),
+    ('TG', 47.5536, 8.8988),
+    ('TI', 46.1930, 9.0208),
+    ('UR', 46.8805, 8.6444),
+    ('VD', 46.5197, 6.6323),
+    ('VS', 46.2310, 7.3603),
+    ('ZG', 47.1724, 8.5153),
+    ('ZH', 47.3769, 8.5417)
+) AS v (code, latitude, longitude)
+WHERE c.code = v.code;
+
+ALTER TABLE cantons
+    ALTER COLUMN capital_lat SET NOT NULL,
+    ALTER COLUMN capital_lon SET NOT NULL;
+
+-- The canton whose capital lies closest to the farm's coordinates, as
+-- `Canton::nearest_capital` computes it at insert. An approximation of the
+-- containing canton, kept to cross-check and query by location.
+ALTER TABLE farms
+    ADD COLUMN nearest_capital_canton TEXT REFERENCES cantons (code);
+
+-- Existing farms, with the same great-circle distance. `coordinates` is
+-- POINT(longitude, latitude).
+UPDATE farms f
+SET nearest_capital_canton = (
+    SELECT c.code
+    FROM cantons c
+    ORDER BY acos(least(1, greatest(-1,
+        sin(radians(c.capital_lat)) * sin(radians(f.coordinates[1]))
+      + cos(radians(c.capital_lat)) * cos(radians(f.coordinates[1]))
+        * cos(radians(f.coordinates[0] - c.capital_lon))
+    ))), c.code
+    LIMIT 1
+);
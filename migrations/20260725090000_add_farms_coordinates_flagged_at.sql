@@ -0,0 +1,4 @@
+-- Set by `POST /farms/reindex-coordinates` when a farm's stored coordinates no
+-- longer pass the configured boundary check; cleared once they pass again.
+ALTER TABLE farms
+    ADD COLUMN coordinates_flagged_at TIMESTAMPTZ;
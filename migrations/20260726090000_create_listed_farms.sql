@@ -0,0 +1,105 @@
+-- The farms `GET /farms` lists: every filter of the endpoint, ANDed together,
+-- with the great-circle distance from ($6, $7) when both are given. The page
+-- and the envelope total both select from it, so a filter is written once;
+-- a new one replaces this function in a migration of its own.
+CREATE FUNCTION listed_farms(
+    category_ids INT2[],
+    product_ids INT[],
+    match_all BOOL,
+    canton_codes TEXT[],
+    q_pattern TEXT,
+    lat FLOAT8,
+    lng FLOAT8,
+    radius_km FLOAT8,
+    wanted_postal_code INT2,
+    wanted_certifications TEXT[],
+    incomplete BOOL,
+    default_category_ids INT2[],
+    created_after TIMESTAMPTZ,
+    created_before TIMESTAMPTZ
+) RETURNS TABLE (farm_id UUID, distance_km FLOAT8)
+LANGUAGE sql STABLE AS $$
+    WITH base AS (
+        SELECT
+            f.id, f.name, f.address, f.postal_code, f.canton,
+            f.certifications, f.created_at,
+            CASE
+                WHEN $6 IS NULL OR $7 IS NULL THEN NULL
+                ELSE 6371.0 * acos(least(1, greatest(-1,
+                    sin(radians($6)) * sin(radians(f.coordinates[1]))
+                  + cos(radians($6)) * cos(radians(f.coordinates[1]))
+                    * cos(radians(f.coordinates[0] - $7))
+                )))
+            END AS distance_km
+        FROM farms f
+    )
+    SELECT f.id, f.distance_km
+    FROM base f
+    WHERE
+        (
+            cardinality($1) = 0
+            OR f.id IN (
+                SELECT fc.farm_id FROM farm_categories fc WHERE fc.category_id = ANY($1)
+                UNION
+                SELECT fp.farm_id FROM farm_products fp
+                    JOIN products p ON p.id = fp.product_id
+                    WHERE p.category_id = ANY($1)
+            )
+        )
+        AND (
+            $3 = false
+            OR cardinality($1) = 0
+            OR (
+                SELECT count(DISTINCT m.category_id)
+                FROM (
+                    SELECT fc.category_id FROM farm_categories fc
+                        WHERE fc.farm_id = f.id AND fc.category_id = ANY($1)
+                    UNION
+                    SELECT p.category_id FROM farm_products fp
+                        JOIN products p ON p.id = fp.product_id
+                        WHERE fp.farm_id = f.id AND p.category_id = ANY($1)
+                ) m
+            ) = cardinality($1)
+        )
+        AND (
+            cardinality($2) = 0
+            OR f.id IN (
+                SELECT fp.farm_id FROM farm_products fp
+                WHERE fp.product_id = ANY($2)
+                GROUP BY fp.farm_id
+                HAVING $3 = false OR count(DISTINCT fp.product_id) = cardinality($2)
+            )
+        )
+        AND (cardinality($4) = 0 OR f.canton = ANY($4))
+        AND (
+            $5 IS NULL
+            OR f.name ILIKE $5
+            OR f.address ILIKE $5
+            OR EXISTS (
+                SELECT 1 FROM farm_products fpq
+                JOIN products pq ON pq.id = fpq.product_id
+                WHERE fpq.farm_id = f.id
+                  AND (pq.key_de ILIKE $5 OR pq.name_en ILIKE $5)
+            )
+        )
+        AND ($8 IS NULL OR (f.distance_km IS NOT NULL AND f.distance_km <= $8))
+        AND ($9 IS NULL OR f.postal_code = $9)
+        AND (cardinality($10) = 0 OR f.certifications && $10)
+        AND (
+            $11 IS NULL
+            OR $11 = (
+                f.postal_code IS NULL
+                OR NOT EXISTS (SELECT 1 FROM farm_products fpi WHERE fpi.farm_id = f.id)
+                OR NOT EXISTS (
+                    SELECT 1 FROM farm_categories fci
+                    WHERE fci.farm_id = f.id AND fci.category_id <> ALL($12)
+                    UNION ALL
+                    SELECT 1 FROM farm_products fpi
+                        JOIN products pi ON pi.id = fpi.product_id
+                        WHERE fpi.farm_id = f.id AND pi.category_id <> ALL($12)
+                )
+            )
+        )
+        AND ($13 IS NULL OR f.created_at >= $13)
+        AND ($14 IS NULL OR f.created_at < $14)
+$$;
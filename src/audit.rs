@@ -0,0 +1,61 @@
+//! Append-only audit trail of write operations, stored in `audit_log`.
+
+use anyhow::Context;
+use sqlx::{Executor, Postgres};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditAction {
+    Create,
+    /// A farm created by `POST /farms/import`.
+    Import,
+}
+
+impl AuditAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Create => "create",
+            Self::Import => "import",
+        }
+    }
+}
+
+/// One audit entry: `user_id` did `action` to `farm_id`, with `payload` the
+/// request body that asked for it.
+#[derive(Debug)]
+pub struct AuditEntry<'a> {
+    pub action: AuditAction,
+    pub farm_id: Uuid,
+    pub user_id: Uuid,
+    pub request_id: Option<Uuid>,
+    pub payload: &'a serde_json::Value,
+}
+
+/// Appends `entry` to the audit log.
+///
+/// Pass the connection of the transaction making the change, so the entry is
+/// committed (or rolled back) together with it.
+#[tracing::instrument(name = "Recording audit entry", skip(executor, entry), fields(action = entry.action.as_str()))]
+pub async fn record(
+    executor: impl Executor<'_, Database = Postgres>,
+    entry: AuditEntry<'_>,
+) -> Result<(), anyhow::Error> {
+    let query = sqlx::query!(
+        r#"
+        INSERT INTO audit_log (id, action, farm_id, user_id, payload, request_id)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        "#,
+        Uuid::new_v4(),
+        entry.action.as_str(),
+        entry.farm_id,
+        entry.user_id,
+        entry.payload,
+        entry.request_id,
+    );
+    executor
+        .execute(query)
+        .await
+        .context("Failed to record an audit entry.")?;
+
+    Ok(())
+}
@@ -1,8 +1,13 @@
+use crate::domain::farm::{CategoriesParseOptions, SwissBoundary};
 use crate::domain::user::{Email, EmailError};
+use crate::i18n::Language;
+use crate::taxonomy::TaxonomySnapshot;
+use arc_swap::ArcSwap;
 use secrecy::{ExposeSecret, SecretString};
 use serde::Deserializer;
 use serde_aux::field_attributes::deserialize_number_from_string;
 use sqlx::postgres::{PgConnectOptions, PgSslMode};
+use std::path::{Path, PathBuf};
 
 #[derive(serde::Deserialize, Clone)]
 pub struct Settings {
@@ -15,6 +20,144 @@ pub struct Settings {
     pub telemetry: TelemetrySettings,
     pub email_client: EmailClientSettings,
     pub registration: RegistrationSettings,
+    #[serde(default)]
+    pub farms: FarmsSettings,
+    #[serde(default)]
+    pub languages: LanguageSettings,
+}
+
+/// The languages responses may be localized into (see `i18n`).
+#[derive(serde::Deserialize, Clone, Debug)]
+pub struct LanguageSettings {
+    /// Accepted for `?lang=` and picked from `Accept-Language`.
+    #[serde(default = "default_supported_languages")]
+    pub supported: Vec<Language>,
+    /// Used when the request names no supported language. Must be one of
+    /// `supported`.
+    #[serde(default)]
+    pub default: Language,
+}
+
+impl Default for LanguageSettings {
+    fn default() -> Self {
+        Self {
+            supported: default_supported_languages(),
+            default: Language::default(),
+        }
+    }
+}
+
+fn default_supported_languages() -> Vec<Language> {
+    Language::ALL.to_vec()
+}
+
+#[derive(serde::Deserialize, Clone, Default)]
+pub struct FarmsSettings {
+    /// Terms farm names may not contain (case-insensitive), on top of the
+    /// fixed forbidden characters.
+    #[serde(default)]
+    pub name_blocklist: Vec<String>,
+    /// Category slugs given to a farm created without any categories or
+    /// products. Empty (the default) rejects such farms instead.
+    #[serde(default)]
+    pub default_categories: Vec<String>,
+    #[serde(default)]
+    pub list_cache: ListCacheSettings,
+    /// How submitted coordinates are checked to lie in Switzerland.
+    #[serde(default)]
+    pub swiss_boundary: SwissBoundary,
+    /// How the submitted canton is checked against the coordinates.
+    #[serde(default)]
+    pub canton_check: CantonCheckSettings,
+    /// Tell the categories of a new farm differing only in case ("CO2",
+    /// "Co2") apart instead of rejecting them as duplicates.
+    #[serde(default)]
+    pub categories_case_sensitive: bool,
+}
+
+impl FarmsSettings {
+    /// The options categories are parsed and compared with.
+    pub fn categories_parse_options(&self) -> CategoriesParseOptions {
+        CategoriesParseOptions {
+            case_sensitive: self.categories_case_sensitive,
+            ..CategoriesParseOptions::default()
+        }
+    }
+
+    /// Rejects `default_categories` naming a category `taxonomy` doesn't
+    /// have, which would otherwise only surface as a `422` for every
+    /// unclassified farm.
+    pub fn validate_against(&self, taxonomy: &TaxonomySnapshot) -> Result<(), String> {
+        let unknown: Vec<_> = self
+            .default_categories
+            .iter()
+            .filter(|slug| taxonomy.category_id_for_slug(slug).is_none())
+            .map(|slug| format!("'{slug}'"))
+            .collect();
+        if unknown.is_empty() {
+            Ok(())
+        } else {
+            Err(format!(
+                "farms.default_categories names unknown categories: {}.",
+                unknown.join(", ")
+            ))
+        }
+    }
+}
+
+/// Cross-checks a new farm's canton against `Canton::nearest_capital` of its
+/// coordinates (see `Canton::mismatch_at`).
+#[derive(serde::Deserialize, Clone)]
+pub struct CantonCheckSettings {
+    #[serde(default)]
+    pub on_mismatch: CantonMismatchAction,
+    /// How much closer, in km, the coordinates may lie to another canton's
+    /// capital than to the submitted canton's before they disagree.
+    #[serde(default = "default_canton_check_tolerance_km")]
+    pub tolerance_km: f64,
+}
+
+impl Default for CantonCheckSettings {
+    fn default() -> Self {
+        Self {
+            on_mismatch: CantonMismatchAction::default(),
+            tolerance_km: default_canton_check_tolerance_km(),
+        }
+    }
+}
+
+/// What a canton that disagrees with the coordinates leads to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CantonMismatchAction {
+    /// Create the farm, with a `canton_mismatch` warning.
+    #[default]
+    Warn,
+    /// Refuse the farm with a `422`.
+    Reject,
+}
+
+/// Caches `GET /farms` response bodies in Redis, keyed by the normalized query.
+#[derive(serde::Deserialize, Clone)]
+pub struct ListCacheSettings {
+    /// Off by default: `POST /farms` invalidates the cache, but farms written
+    /// any other way only show up once cached pages expire.
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_list_cache_ttl_seconds")]
+    pub ttl_seconds: u64,
+    #[serde(default = "default_list_cache_key_prefix")]
+    pub key_prefix: String,
+}
+
+impl Default for ListCacheSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            ttl_seconds: default_list_cache_ttl_seconds(),
+            key_prefix: default_list_cache_key_prefix(),
+        }
+    }
 }
 
 #[derive(serde::Deserialize, Clone)]
@@ -22,6 +165,38 @@ pub struct ApplicationSettings {
     #[serde(deserialize_with = "deserialize_number_from_string")]
     pub port: u16,
     pub host: String,
+    /// HTTP worker threads; one per logical CPU when unset.
+    #[serde(default)]
+    pub workers: Option<usize>,
+    /// Answer `500`s with a generic message and the request id instead of
+    /// the underlying error. The full error is still logged.
+    #[serde(default)]
+    pub hide_internal_errors: bool,
+    /// Writes (any method but `GET`, `HEAD` and `OPTIONS`) one client IP may
+    /// have in flight at once; more are answered with `429`. Uncapped when
+    /// unset.
+    #[serde(default)]
+    pub max_concurrent_writes_per_ip: Option<usize>,
+    /// Base of the `Retry-After` sent with a `503`; each response adds up to
+    /// as much again at random. `RETRY_AFTER_SECONDS` when unset.
+    #[serde(default)]
+    pub retry_after_seconds: Option<u64>,
+    /// How much a farm error body says (see `FarmError::error_body`).
+    #[serde(default)]
+    pub error_verbosity: ErrorVerbosity,
+}
+
+/// The keys of a farm error body.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorVerbosity {
+    /// `error_code` alone.
+    Minimal,
+    /// `error_code` and `message`.
+    Standard,
+    /// `error_code`, `message`, the offending `field` and the `request_id`.
+    #[default]
+    Verbose,
 }
 
 #[derive(serde::Deserialize, Clone)]
@@ -39,10 +214,23 @@ pub struct DatabaseSettings {
 
 #[derive(serde::Deserialize, Clone)]
 pub struct RedisSettings {
+    /// `redis://` for plaintext, `rediss://` for TLS with a verified server
+    /// certificate.
     pub uri: SecretString,
+    /// PEM file with the CA to verify a `rediss://` server against, instead
+    /// of the system roots (e.g. a managed Redis with a private CA).
+    #[serde(default)]
+    pub ca_certificate_path: Option<PathBuf>,
     pub pool_max_size: Option<usize>,
     pub timeout_seconds: Option<u64>,
     pub session_key_prefix: String,
+    /// Whether the application still starts when Redis can't be reached at
+    /// boot, leaving its first requests to connect. Off, startup fails.
+    #[serde(default)]
+    pub fail_open: bool,
+    /// How many times startup tries to reach Redis before giving up.
+    #[serde(default)]
+    pub warmup_attempts: Option<u32>,
 }
 
 #[derive(serde::Deserialize, Clone)]
@@ -72,6 +260,85 @@ pub struct IdempotencySettings {
     pub redis_key_prefix: String,
     #[serde(default = "default_idempotency_settings_cleanup_worker_run_interval")]
     pub cleanup_worker_run_interval: u64,
+    /// Namespaces every stored key, so bumping it stops responses saved by a
+    /// previous API version from being replayed.
+    #[serde(default = "default_idempotency_settings_api_version")]
+    pub api_version: String,
+    /// Endpoints that skip idempotency even with an engine configured: they
+    /// take no `idempotency_key` and never replay a response.
+    #[serde(default)]
+    pub exempt_endpoints: Vec<IdempotentEndpoint>,
+    /// Most seconds the `Retry-After` of a `409` for a key still in flight
+    /// asks for; less when the claim on the key expires sooner.
+    /// `IN_FLIGHT_RETRY_AFTER_SECONDS` when unset.
+    #[serde(default)]
+    pub in_flight_retry_after_seconds: Option<u64>,
+}
+
+impl IdempotencySettings {
+    /// Whether `endpoint` requires an `idempotency_key` and goes through the
+    /// engine.
+    pub fn enforced_for(&self, endpoint: IdempotentEndpoint) -> bool {
+        !self.exempt_endpoints.contains(&endpoint)
+    }
+
+    /// The engine has what it needs: a key lifetime for every engine that
+    /// stores keys, a Redis URI and key prefix for `redis`, and a cleanup
+    /// interval for `postgres`.
+    fn validate(&self, redis: &RedisSettings) -> Result<(), String> {
+        let engine = self.engine.as_str();
+        if self.in_flight_retry_after_seconds == Some(0) {
+            return Err(
+                "idempotency.in_flight_retry_after_seconds must be at least 1.".to_string(),
+            );
+        }
+        if !matches!(self.engine, IdempotencyEngine::None) && self.ttl_seconds == 0 {
+            return Err(format!(
+                "idempotency.ttl_seconds must be at least 1 with the {engine} engine."
+            ));
+        }
+        if matches!(self.engine, IdempotencyEngine::Redis) {
+            if redis.uri.expose_secret().trim().is_empty() {
+                return Err("idempotency.engine is redis but redis.uri is empty.".to_string());
+            }
+            if self.redis_key_prefix.trim().is_empty() {
+                return Err(
+                    "idempotency.redis_key_prefix must not be empty with the redis engine."
+                        .to_string(),
+                );
+            }
+        }
+        if matches!(self.engine, IdempotencyEngine::Postgres)
+            && self.cleanup_worker_run_interval == 0
+        {
+            return Err(
+                "idempotency.cleanup_worker_run_interval must be at least 1 with the postgres \
+                engine."
+                    .to_string(),
+            );
+        }
+        Ok(())
+    }
+
+    /// Options set away from their defaults that the engine ignores.
+    fn warnings(&self) -> Vec<String> {
+        let engine = self.engine.as_str();
+        let mut warnings = Vec::new();
+        if !matches!(self.engine, IdempotencyEngine::Redis)
+            && self.redis_key_prefix != default_idempotency_settings_redis_key_prefix()
+        {
+            warnings.push(format!(
+                "idempotency.redis_key_prefix is set but unused by the {engine} engine."
+            ));
+        }
+        if matches!(self.engine, IdempotencyEngine::None)
+            && self.ttl_seconds != default_idempotency_settings_ttl_seconds()
+        {
+            warnings
+                .push("idempotency.ttl_seconds is set but unused by the none engine.".to_string());
+        }
+        warnings
+    }
 }
 
 #[derive(serde::Deserialize, Clone)]
@@ -180,6 +447,14 @@ pub struct RateLimitSettings {
     pub key_prefix: String,
 }
 
+fn default_list_cache_ttl_seconds() -> u64 {
+    30
+}
+
+fn default_list_cache_key_prefix() -> String {
+    "farms-list".to_string()
+}
+
 fn default_verification_token_ttl_seconds() -> i64 {
     86_400 // 24h
 }
@@ -200,6 +475,14 @@ fn default_idempotency_settings_redis_key_prefix() -> String {
     "idem".to_string()
 }
 
+fn default_canton_check_tolerance_km() -> f64 {
+    25.0
+}
+
+fn default_idempotency_settings_api_version() -> String {
+    "v1".to_string()
+}
+
 /// The runtime environment for our application.
 pub enum Environment {
     Local,
@@ -234,6 +517,7 @@ pub enum IdempotencyEngine {
     None,
     Redis,
     Postgres,
+    Memory,
 }
 impl IdempotencyEngine {
     pub fn as_str(&self) -> &'static str {
@@ -241,6 +525,7 @@ impl IdempotencyEngine {
             Self::None => "none",
             Self::Redis => "redis",
             Self::Postgres => "postgres",
+            Self::Memory => "memory",
         }
     }
 }
@@ -252,9 +537,10 @@ impl TryFrom<String> for IdempotencyEngine {
             "none" => Ok(Self::None),
             "redis" => Ok(Self::Redis),
             "postgres" => Ok(Self::Postgres),
+            "memory" => Ok(Self::Memory),
             other => Err(format!(
                 "'{}' is not a supported Idempotency engine.\
-                Use 'redis', 'postgres' or 'none' to disable Idempotency\
+                Use 'redis', 'postgres', 'memory' or 'none' to disable Idempotency\
                 Warning: postgres engine is currently untested",
                 other
             )),
@@ -271,6 +557,15 @@ impl<'de> serde::Deserialize<'de> for IdempotencyEngine {
     }
 }
 
+/// An endpoint that enforces idempotency unless listed in
+/// `idempotency.exempt_endpoints`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IdempotentEndpoint {
+    /// `POST /farms`
+    CreateFarm,
+}
+
 impl DatabaseSettings {
     pub fn without_db(&self) -> PgConnectOptions {
         let ssl_mode = if self.require_ssl {
@@ -377,10 +672,23 @@ impl<'de> serde::Deserialize<'de> for LogFormat {
     }
 }
 
+/// Reads the settings from the configuration directory: `APP_CONFIG_DIR` when
+/// set, otherwise `configuration/` under the current directory.
 pub fn get_configuration() -> Result<Settings, config::ConfigError> {
-    let base_path = std::env::current_dir().expect("Failed to determine the current directory");
-    let configuration_directory = base_path.join("configuration");
-
+    let configuration_directory = match std::env::var_os("APP_CONFIG_DIR") {
+        Some(directory) => PathBuf::from(directory),
+        None => std::env::current_dir()
+            .expect("Failed to determine the current directory")
+            .join("configuration"),
+    };
+    get_configuration_from(&configuration_directory)
+}
+
+/// Reads the settings from `base.yaml` and the `APP_ENVIRONMENT` file in
+/// `configuration_directory`, then `APP_`-prefixed environment variables.
+pub fn get_configuration_from(
+    configuration_directory: &Path,
+) -> Result<Settings, config::ConfigError> {
     // Detect the running environment.
     // Default to `local` if unspecified.
     let environment: Environment = std::env::var("APP_ENVIRONMENT")
@@ -407,5 +715,65 @@ pub fn get_configuration() -> Result<Settings, config::ConfigError> {
 
     // Try to convert the configuration values it read into
     // our Settings type
-    settings.try_deserialize::<Settings>()
+    let settings = settings.try_deserialize::<Settings>()?;
+    settings
+        .validate(&environment)
+        .map_err(config::ConfigError::Message)?;
+    Ok(settings)
+}
+
+/// The settings as handlers see them. Reloadable options can be swapped in
+/// while the server runs (see [`reload_settings`]).
+pub type SharedSettings = ArcSwap<Settings>;
+
+impl Settings {
+    /// Rejects inconsistent settings, and combinations that are fine locally
+    /// but unsafe in `environment`: production must talk to the database over
+    /// SSL.
+    pub fn validate(&self, environment: &Environment) -> Result<(), String> {
+        if matches!(environment, Environment::Production) && !self.database.require_ssl {
+            return Err("database.require_ssl must be true in production. \
+                Set APP_DATABASE__REQUIRE_SSL=true."
+                .to_string());
+        }
+        if !self.languages.supported.contains(&self.languages.default) {
+            return Err(format!(
+                "languages.default ({}) must be one of languages.supported.",
+                self.languages.default.as_str()
+            ));
+        }
+        let tolerance_km = self.farms.canton_check.tolerance_km;
+        if tolerance_km.is_nan() || tolerance_km < 0.0 {
+            return Err(format!(
+                "farms.canton_check.tolerance_km must be a number of km, not negative \
+                ({tolerance_km})."
+            ));
+        }
+        self.idempotency.validate(&self.redis)
+    }
+
+    /// Settings that are accepted but probably not what was meant, one
+    /// message each, for the caller to log once logging is set up.
+    pub fn warnings(&self) -> Vec<String> {
+        self.idempotency.warnings()
+    }
+
+    /// These settings with the options that are safe to change at runtime
+    /// taken from `fresh`: `registration` (rate limits included), `farms`,
+    /// `application.hide_internal_errors` and `application.error_verbosity`.
+    /// Everything else (bind address, workers, database, Redis, sessions,
+    /// logging, ...) is fixed at startup.
+    pub fn with_reloadable_from(&self, fresh: &Settings) -> Settings {
+        let mut settings = self.clone();
+        settings.registration = fresh.registration.clone();
+        settings.farms = fresh.farms.clone();
+        settings.application.hide_internal_errors = fresh.application.hide_internal_errors;
+        settings.application.error_verbosity = fresh.application.error_verbosity;
+        settings
+    }
+}
+
+/// Swaps the reloadable options of `fresh` into `shared`.
+pub fn reload_settings(shared: &SharedSettings, fresh: &Settings) {
+    shared.rcu(|current| current.with_reloadable_from(fresh));
 }
@@ -0,0 +1,241 @@
+//! Transaction helpers shared by the write paths, and the per-request
+//! statement counter.
+
+use crate::errors::is_retryable_transaction_error;
+use actix_web::{FromRequest, HttpMessage, HttpRequest, dev::Payload};
+use anyhow::Context;
+use sqlx::{
+    Describe, Either, Error, Execute, Executor, PgPool, Postgres, Transaction,
+    postgres::{PgQueryResult, PgRow, PgStatement, PgTypeInfo},
+};
+use std::{
+    convert::Infallible,
+    future::{Future, Ready, ready},
+    pin::Pin,
+    sync::{
+        Arc,
+        atomic::{AtomicU32, Ordering},
+    },
+    time::Duration,
+};
+use tokio_stream::Stream;
+
+/// Attempts made by `retry_serialization_failures` before giving up.
+pub const MAX_TRANSACTION_ATTEMPTS: u32 = 3;
+
+/// Delay before the first retry; doubled for each one after.
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(20);
+
+/// Runs `transaction`, retrying it up to `max_attempts` times in total when
+/// it fails with a serialization failure or deadlock (see
+/// `is_retryable_transaction_error`).
+///
+/// Postgres only lets a transaction that lost such a race be run again from
+/// the start, with a new snapshot, so each attempt must be a whole
+/// transaction: `BEGIN` through `COMMIT`. A failed attempt's transaction is
+/// rolled back when it is dropped. Any other error is returned straight
+/// away. `E` must keep the database error in its source chain, as
+/// `.context(...)` does.
+pub async fn retry_serialization_failures<T, E, F>(
+    max_attempts: u32,
+    mut transaction: F,
+) -> Result<T, E>
+where
+    F: AsyncFnMut() -> Result<T, E>,
+    E: std::error::Error + 'static,
+{
+    let mut attempt = 1;
+    loop {
+        match transaction().await {
+            Err(e) if attempt < max_attempts && is_retryable_transaction_error(&e) => {
+                tracing::warn!(error = ?e, attempt, "Retrying a transaction that lost a race.");
+                tokio::time::sleep(RETRY_BASE_DELAY * 2u32.pow(attempt - 1)).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Runs `work` in a transaction begun and then committed, or rolled back if
+/// `work` fails, through `statements`.
+pub async fn in_transaction<T, E, F>(
+    pool: &PgPool,
+    statements: &StatementCounter,
+    work: F,
+) -> Result<T, E>
+where
+    F: AsyncFnOnce(&mut Transaction<'static, Postgres>) -> Result<T, E>,
+    E: From<anyhow::Error>,
+{
+    let mut transaction = statements.begin(pool).await?;
+    match work(&mut transaction).await {
+        Ok(value) => {
+            statements.commit(transaction).await?;
+            Ok(value)
+        }
+        Err(e) => {
+            // `e` is what the caller needs to see, even if the rollback fails
+            // too; the connection is then closed rather than reused.
+            if let Err(rollback) = statements.rollback(transaction).await {
+                tracing::warn!(error = ?rollback, "Failed to roll back a transaction.");
+            }
+            Err(e)
+        }
+    }
+}
+
+/// The SQL statements run on behalf of one request, to spot N+1 patterns and
+/// oversized transactions. The root span builder puts one in the request's
+/// extensions and logs the total as `db_statements` once the request
+/// completes (see `telemetry::FarmsRootSpanBuilder`).
+///
+/// Handlers take it as an extractor. Queries are counted as they are sent,
+/// by running them on `on(executor)`, and transactions by opening and
+/// closing them through `begin`, `commit` and `rollback`; clones share the
+/// tally.
+#[derive(Debug, Clone, Default)]
+pub struct StatementCounter(Arc<AtomicU32>);
+
+impl StatementCounter {
+    /// Counts one statement.
+    fn record(&self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// The statements counted so far.
+    pub fn count(&self) -> u32 {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// `executor`, counting every query run on it here.
+    pub fn on<E>(&self, executor: E) -> Counted<'_, E> {
+        Counted {
+            executor,
+            statements: self,
+        }
+    }
+
+    /// Sends `BEGIN`.
+    pub async fn begin(
+        &self,
+        pool: &PgPool,
+    ) -> Result<Transaction<'static, Postgres>, anyhow::Error> {
+        self.record();
+        pool.begin()
+            .await
+            .context("Failed to begin a SQL transaction.")
+    }
+
+    /// Sends `COMMIT`.
+    pub async fn commit(
+        &self,
+        transaction: Transaction<'static, Postgres>,
+    ) -> Result<(), anyhow::Error> {
+        self.record();
+        transaction
+            .commit()
+            .await
+            .context("Failed to commit a SQL transaction.")
+    }
+
+    /// Sends `ROLLBACK`.
+    pub async fn rollback(
+        &self,
+        transaction: Transaction<'static, Postgres>,
+    ) -> Result<(), anyhow::Error> {
+        self.record();
+        transaction
+            .rollback()
+            .await
+            .context("Failed to roll back a SQL transaction.")
+    }
+}
+
+/// An executor whose queries a `StatementCounter` counts, one per query
+/// sent (see `StatementCounter::on`).
+#[derive(Debug)]
+pub struct Counted<'s, E> {
+    executor: E,
+    statements: &'s StatementCounter,
+}
+
+type BoxFuture<'e, T> = Pin<Box<dyn Future<Output = T> + Send + 'e>>;
+type BoxStream<'e, T> = Pin<Box<dyn Stream<Item = T> + Send + 'e>>;
+
+impl<'c, E> Executor<'c> for Counted<'_, E>
+where
+    E: Executor<'c, Database = Postgres>,
+{
+    type Database = Postgres;
+
+    fn fetch_many<'e, 'q: 'e, Q>(
+        self,
+        query: Q,
+    ) -> BoxStream<'e, Result<Either<PgQueryResult, PgRow>, Error>>
+    where
+        'c: 'e,
+        Q: 'q + Execute<'q, Postgres>,
+    {
+        self.statements.record();
+        self.executor.fetch_many(query)
+    }
+
+    fn fetch_optional<'e, 'q: 'e, Q>(self, query: Q) -> BoxFuture<'e, Result<Option<PgRow>, Error>>
+    where
+        'c: 'e,
+        Q: 'q + Execute<'q, Postgres>,
+    {
+        self.statements.record();
+        self.executor.fetch_optional(query)
+    }
+
+    fn prepare_with<'e>(
+        self,
+        sql: sqlx::SqlStr,
+        parameters: &'e [PgTypeInfo],
+    ) -> BoxFuture<'e, Result<PgStatement, Error>>
+    where
+        'c: 'e,
+    {
+        self.executor.prepare_with(sql, parameters)
+    }
+
+    fn describe<'e>(self, sql: sqlx::SqlStr) -> BoxFuture<'e, Result<Describe<Postgres>, Error>>
+    where
+        'c: 'e,
+    {
+        self.executor.describe(sql)
+    }
+}
+
+/// The request's counter; a detached one, counted but never logged, when the
+/// request went through no `FarmsRootSpanBuilder`.
+impl FromRequest for StatementCounter {
+    type Error = Infallible;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        ready(Ok(req
+            .extensions()
+            .get::<Self>()
+            .cloned()
+            .unwrap_or_default()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StatementCounter;
+
+    #[test]
+    fn clones_share_one_tally() {
+        let counter = StatementCounter::default();
+        let handle = counter.clone();
+
+        counter.record();
+        handle.record();
+
+        assert_eq!(2, counter.count());
+    }
+}
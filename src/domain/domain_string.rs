@@ -0,0 +1,116 @@
+//! Shared validation for domain types that wrap a single trimmed string.
+//!
+//! Implement `DomainString` for the limits and `impl_domain_string!` for the
+//! accessors, serde and sqlx impls; the type's own `parse` calls
+//! `DomainString::validate`.
+
+use unicode_segmentation::UnicodeSegmentation;
+
+/// The rule a string broke, converted into the type's own error.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DomainStringViolation {
+    Empty,
+    TooShort(usize),
+    TooLong(usize),
+    ForbiddenCharacter(char),
+}
+
+pub trait DomainString {
+    type Error: From<DomainStringViolation>;
+
+    /// Fewest grapheme clusters allowed.
+    const MIN_LENGTH: usize = 1;
+    /// Most grapheme clusters allowed.
+    const MAX_LENGTH: usize;
+    const FORBIDDEN_CHARACTERS: &'static [char] = &[];
+    /// Whether the length limits count surrounding whitespace, which parsing
+    /// trims anyway.
+    const LENGTH_INCLUDES_SURROUNDING_WHITESPACE: bool = false;
+
+    /// `s` trimmed, if it is non-blank, within the length limits and free of
+    /// forbidden characters, checked in that order.
+    fn validate(s: &str) -> Result<&str, Self::Error> {
+        let trimmed = s.trim();
+        if trimmed.is_empty() {
+            return Err(DomainStringViolation::Empty.into());
+        }
+
+        let measured = if Self::LENGTH_INCLUDES_SURROUNDING_WHITESPACE {
+            s
+        } else {
+            trimmed
+        };
+        let length = measured.graphemes(true).count();
+        if length < Self::MIN_LENGTH {
+            return Err(DomainStringViolation::TooShort(length).into());
+        }
+        if length > Self::MAX_LENGTH {
+            return Err(DomainStringViolation::TooLong(length).into());
+        }
+
+        if let Some(forbidden) = trimmed
+            .chars()
+            .find(|c| Self::FORBIDDEN_CHARACTERS.contains(c))
+        {
+            return Err(DomainStringViolation::ForbiddenCharacter(forbidden).into());
+        }
+
+        Ok(trimmed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DomainString, DomainStringViolation};
+    use claims::{assert_matches, assert_ok_eq};
+
+    struct Code;
+
+    impl DomainString for Code {
+        type Error = DomainStringViolation;
+        const MIN_LENGTH: usize = 2;
+        const MAX_LENGTH: usize = 4;
+        const FORBIDDEN_CHARACTERS: &'static [char] = &['#'];
+    }
+
+    struct Padded;
+
+    impl DomainString for Padded {
+        type Error = DomainStringViolation;
+        const MAX_LENGTH: usize = 4;
+        const LENGTH_INCLUDES_SURROUNDING_WHITESPACE: bool = true;
+    }
+
+    #[test]
+    fn validate_trims_a_valid_string() {
+        assert_ok_eq!(Code::validate("  ab  "), "ab");
+    }
+
+    #[test]
+    fn validate_reports_the_first_rule_broken() {
+        assert_matches!(Code::validate(" \n "), Err(DomainStringViolation::Empty));
+        assert_matches!(Code::validate("a"), Err(DomainStringViolation::TooShort(1)));
+        assert_matches!(
+            Code::validate("abcde"),
+            Err(DomainStringViolation::TooLong(5))
+        );
+        assert_matches!(
+            Code::validate("a#"),
+            Err(DomainStringViolation::ForbiddenCharacter('#'))
+        );
+    }
+
+    #[test]
+    fn lengths_are_counted_in_grapheme_clusters() {
+        assert_ok_eq!(Code::validate("äöüé"), "äöüé");
+    }
+
+    #[test]
+    fn surrounding_whitespace_counts_only_when_configured() {
+        assert_ok_eq!(Code::validate("  abcd  "), "abcd");
+        assert_matches!(
+            Padded::validate("  abcd  "),
+            Err(DomainStringViolation::TooLong(8))
+        );
+    }
+}
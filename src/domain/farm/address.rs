@@ -4,10 +4,10 @@
 //! are within Switzerland's boundaries and properly formatted for storage
 //! in PostgreSQL's POINT datatype.
 
-use crate::impl_sqlx_for_string_domain_type;
-use std::fmt::Display;
+use crate::domain::domain_string::{DomainString, DomainStringViolation};
+use crate::domain::farm::PostalCode;
+use crate::impl_domain_string;
 use thiserror::Error;
-use unicode_segmentation::UnicodeSegmentation;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Address(String);
@@ -22,13 +22,30 @@ pub enum AddressError {
 
     #[error("Address is too short (min 5 characters, got {0}).")]
     TooShort(usize),
+
+    #[error("Address contains a forbidden character: '{0}'.")]
+    ForbiddenCharacter(char),
 }
 
-impl Address {
+impl From<DomainStringViolation> for AddressError {
+    fn from(violation: DomainStringViolation) -> Self {
+        match violation {
+            DomainStringViolation::Empty => Self::EmptyAddress,
+            DomainStringViolation::TooShort(length) => Self::TooShort(length),
+            DomainStringViolation::TooLong(length) => Self::TooLong(length),
+            DomainStringViolation::ForbiddenCharacter(c) => Self::ForbiddenCharacter(c),
+        }
+    }
+}
+
+impl DomainString for Address {
+    type Error = AddressError;
     // Typical format: "Street Number, Postal Code City"
     const MIN_LENGTH: usize = 5;
     const MAX_LENGTH: usize = 200;
+}
 
+impl Address {
     /// Parse an address string into a validated Address
     ///
     /// Rules:
@@ -41,71 +58,31 @@ impl Address {
     ///   * PO Box addresses
     ///   * Addresses with apartment/building details
     pub fn parse(s: String) -> Result<Address, AddressError> {
-        let trimmed = s.trim();
-
-        if trimmed.is_empty() {
-            return Err(AddressError::EmptyAddress);
-        }
-
-        let char_count = trimmed.graphemes(true).count();
-
-        if char_count < Self::MIN_LENGTH {
-            return Err(AddressError::TooShort(char_count));
-        }
-
-        if char_count > Self::MAX_LENGTH {
-            return Err(AddressError::TooLong(char_count));
-        }
-
-        Ok(Self(trimmed.to_string()))
-    }
-
-    /// Returns the address as a string slice. Useful for logging and display.
-    pub fn as_str(&self) -> &str {
-        &self.0
-    }
-}
-
-impl AsRef<str> for Address {
-    fn as_ref(&self) -> &str {
-        &self.0
-    }
-}
-
-impl Display for Address {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        self.0.fmt(f)
+        Self::validate(&s).map(|trimmed| Self(trimmed.to_string()))
     }
-}
-
-// Serialize for JSON API responses
-impl serde::Serialize for Address {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: serde::Serializer,
-    {
-        serializer.serialize_str(&self.0)
-    }
-}
 
-// Deserialize from JSON API requests
-impl<'de> serde::Deserialize<'de> for Address {
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-    where
-        D: serde::Deserializer<'de>,
-    {
-        let s = String::deserialize(deserializer)?;
-        Address::parse(s).map_err(serde::de::Error::custom)
+    /// The postal code embedded in the address, if one can be found.
+    pub fn postal_code(&self) -> Option<PostalCode> {
+        PostalCode::from_address(&self.0)
     }
 }
 
-impl_sqlx_for_string_domain_type!(Address);
+impl_domain_string!(Address);
 
 #[cfg(test)]
 mod tests {
-    use super::Address;
+    use super::{Address, AddressError};
+    use crate::domain::domain_string::{DomainString, DomainStringViolation};
     use crate::domain::test_data::VALID_SWISS_ADDRESSES;
-    use claims::{assert_err, assert_ok};
+    use claims::{assert_err, assert_matches, assert_ok, assert_some};
+
+    #[test]
+    fn a_forbidden_character_violation_maps_to_an_address_error() {
+        assert_matches!(
+            AddressError::from(DomainStringViolation::ForbiddenCharacter('{')),
+            AddressError::ForbiddenCharacter('{')
+        );
+    }
 
     #[test]
     fn address_with_min_length_is_valid() {
@@ -161,6 +138,18 @@ mod tests {
         }
     }
 
+    #[test]
+    fn all_valid_swiss_addresses_from_test_data_have_a_postal_code() {
+        for address in VALID_SWISS_ADDRESSES {
+            let parsed = Address::parse(address.to_string()).unwrap();
+            assert_some!(
+                parsed.postal_code(),
+                "Failed to extract postal code: {}",
+                address
+            );
+        }
+    }
+
     #[test]
     fn multiline_address_format_is_valid() {
         let address = "Bahnhofstrasse 1\n8001 Zürich".to_string();
@@ -174,4 +163,44 @@ mod tests {
         assert_eq!(parsed.to_string(), original);
         assert_eq!(parsed.as_str(), original);
     }
+
+    #[test]
+    fn each_rule_reports_its_own_error() {
+        assert_matches!(
+            Address::parse(" \n ".to_string()),
+            Err(AddressError::EmptyAddress)
+        );
+        assert_matches!(
+            Address::parse("A 1".to_string()),
+            Err(AddressError::TooShort(3))
+        );
+        assert_matches!(
+            Address::parse("a".repeat(201)),
+            Err(AddressError::TooLong(201))
+        );
+    }
+
+    #[test]
+    fn the_length_limits_ignore_surrounding_whitespace() {
+        let padded = format!("  {}  ", "a".repeat(Address::MAX_LENGTH));
+
+        assert_ok!(Address::parse(padded));
+        assert_matches!(
+            Address::parse("   A 1   ".to_string()),
+            Err(AddressError::TooShort(3))
+        );
+    }
+
+    #[test]
+    fn serde_round_trips_through_parse() {
+        let address: Address =
+            serde_json::from_value(serde_json::json!(" Dorfstrasse 5, 3000 Bern ")).unwrap();
+
+        assert_eq!(address.as_str(), "Dorfstrasse 5, 3000 Bern");
+        assert_eq!(
+            serde_json::to_value(&address).unwrap(),
+            serde_json::json!("Dorfstrasse 5, 3000 Bern")
+        );
+        assert_err!(serde_json::from_value::<Address>(serde_json::json!("A 1")));
+    }
 }
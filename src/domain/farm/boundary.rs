@@ -0,0 +1,155 @@
+//! How precisely coordinates are checked to lie in Switzerland.
+
+use crate::domain::farm::Point;
+
+/// The check `Point::parse_within` applies on top of `Point::parse`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SwissBoundary {
+    /// The rectangle `Point::parse` checks. Cheap, but it takes in parts of
+    /// Lake Constance and the French, German, Italian and Austrian border
+    /// areas.
+    #[default]
+    BoundingBox,
+    /// `SWISS_BORDER`, within a few kilometres of the real border. Across
+    /// the lakes it follows a rough median line.
+    Polygon,
+}
+
+/// A simplified Swiss border, clockwise from Basel: (latitude, longitude).
+const SWISS_BORDER: [(f64, f64); 69] = [
+    (47.59, 7.59), // Basel
+    (47.60, 7.68),
+    (47.54, 7.80),
+    (47.56, 7.95),
+    (47.61, 8.22),
+    (47.58, 8.44),
+    (47.69, 8.40),
+    (47.81, 8.62), // Schaffhausen
+    (47.70, 8.80),
+    (47.66, 8.88),
+    (47.66, 9.18), // Kreuzlingen
+    (47.57, 9.39),
+    (47.49, 9.56),
+    (47.40, 9.65),
+    (47.27, 9.52), // Liechtenstein
+    (47.06, 9.49),
+    (47.06, 9.61),
+    (46.96, 9.87),
+    (46.91, 10.10),
+    (46.99, 10.40),
+    (46.85, 10.49),
+    (46.63, 10.49), // Val Müstair
+    (46.55, 10.40),
+    (46.50, 10.16),
+    (46.38, 10.10),
+    (46.23, 10.16),
+    (46.40, 9.90),
+    (46.33, 9.55),
+    (46.50, 9.33),
+    (46.24, 9.12),
+    (46.00, 9.10),
+    (45.82, 9.03), // Chiasso
+    (45.92, 8.90),
+    (46.00, 8.80),
+    (46.09, 8.71),
+    (46.16, 8.60),
+    (46.35, 8.45),
+    (46.45, 8.38),
+    (46.25, 8.12),
+    (46.10, 8.10),
+    (45.99, 7.98),
+    (45.93, 7.87), // Monte Rosa
+    (45.97, 7.66),
+    (45.87, 7.18),
+    (45.92, 7.04),
+    (46.06, 6.92),
+    (46.20, 6.82),
+    (46.39, 6.80), // Saint-Gingolph
+    (46.45, 6.60),
+    (46.38, 6.35),
+    (46.31, 6.25),
+    (46.22, 6.25),
+    (46.18, 6.20),
+    (46.13, 6.05),
+    (46.15, 5.96), // Chancy
+    (46.25, 5.97),
+    (46.25, 6.10),
+    (46.31, 6.15),
+    (46.40, 6.07),
+    (46.57, 6.12),
+    (46.71, 6.38), // Vallorbe
+    (46.91, 6.46),
+    (47.06, 6.72),
+    (47.21, 6.96),
+    (47.37, 6.88),
+    (47.50, 7.00), // Boncourt
+    (47.43, 7.15),
+    (47.48, 7.40),
+    (47.54, 7.53),
+];
+
+impl SwissBoundary {
+    /// Whether `point` lies inside this boundary.
+    pub fn contains(&self, point: &Point) -> bool {
+        match self {
+            Self::BoundingBox => Point::is_within_switzerland(point.latitude, point.longitude),
+            Self::Polygon => in_polygon(point, &SWISS_BORDER),
+        }
+    }
+}
+
+/// Even-odd ray casting: counts the polygon edges a ray from `point` due
+/// north crosses.
+fn in_polygon(point: &Point, polygon: &[(f64, f64)]) -> bool {
+    let mut inside = false;
+    let mut previous = polygon[polygon.len() - 1];
+    for &current in polygon {
+        let ((lat_a, lon_a), (lat_b, lon_b)) = (previous, current);
+        if (lon_a > point.longitude) != (lon_b > point.longitude) {
+            let crossing = lat_a + (point.longitude - lon_a) / (lon_b - lon_a) * (lat_b - lat_a);
+            if crossing > point.latitude {
+                inside = !inside;
+            }
+        }
+        previous = current;
+    }
+    inside
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SwissBoundary;
+    use crate::domain::farm::{Point, canton::CANTON_CAPITALS};
+
+    /// In the bounding box, but not in Switzerland.
+    const ABROAD: [(&str, f64, f64); 5] = [
+        ("Friedrichshafen", 47.65, 9.48),
+        ("Bregenz", 47.50, 9.75),
+        ("Mulhouse", 47.75, 7.34),
+        ("Annecy", 45.90, 6.13),
+        ("Como", 45.81, 9.08),
+    ];
+
+    #[test]
+    fn every_canton_capital_is_inside_the_polygon() {
+        for (code, city, latitude, longitude) in CANTON_CAPITALS {
+            let capital = Point::new(latitude, longitude);
+            assert!(SwissBoundary::Polygon.contains(&capital), "{city} ({code})");
+        }
+    }
+
+    #[test]
+    fn places_abroad_pass_the_bounding_box_but_not_the_polygon() {
+        for (place, latitude, longitude) in ABROAD {
+            let point = Point::new(latitude, longitude);
+            assert!(SwissBoundary::BoundingBox.contains(&point), "{place}");
+            assert!(!SwissBoundary::Polygon.contains(&point), "{place}");
+        }
+    }
+
+    #[test]
+    fn the_bounding_box_is_the_default() {
+        assert_eq!(SwissBoundary::default(), SwissBoundary::BoundingBox);
+    }
+}
@@ -3,23 +3,214 @@
 //! Provides a validated `Canton` type that ensures only official Swiss canton
 //! abbreviations are accepted.
 
+use crate::domain::farm::Point;
+use crate::i18n::Language;
 use crate::impl_sqlx_for_string_domain_type;
+use sqlx::PgPool;
+use std::collections::HashMap;
 use std::fmt::Display;
+use std::sync::LazyLock;
+use unicode_normalization::{UnicodeNormalization, char::is_combining_mark};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Canton(String);
 
 #[derive(Debug, thiserror::Error)]
 pub enum CantonError {
+    /// `input` is the trimmed input, `normalized` the uppercased code that
+    /// was looked up.
     #[error(
-        "Invalid canton code: {0}. Must be a valid Swiss canton abbreviation (e.g., 'ZH', 'BE', 'LU')."
+        "Invalid canton code: '{input}' (read as '{normalized}'). Must be a valid Swiss canton abbreviation (e.g., 'ZH', 'BE', 'LU')."
     )]
-    InvalidCanton(String),
+    InvalidCanton { input: String, normalized: String },
 
     #[error("Canton code cannot be empty.")]
     EmptyCanton,
 }
 
+/// Official canton names, in the order en, de, fr, it, rm.
+const CANTON_NAMES: [(&str, [&str; 5]); 26] = [
+    ("AG", ["Aargau", "Aargau", "Argovie", "Argovia", "Argovia"]),
+    (
+        "AI",
+        [
+            "Appenzell Innerrhoden",
+            "Appenzell Innerrhoden",
+            "Appenzell Rhodes-Intérieures",
+            "Appenzello Interno",
+            "Appenzell Dadens",
+        ],
+    ),
+    (
+        "AR",
+        [
+            "Appenzell Ausserrhoden",
+            "Appenzell Ausserrhoden",
+            "Appenzell Rhodes-Extérieures",
+            "Appenzello Esterno",
+            "Appenzell Dadora",
+        ],
+    ),
+    ("BE", ["Bern", "Bern", "Berne", "Berna", "Berna"]),
+    (
+        "BL",
+        [
+            "Basel-Landschaft",
+            "Basel-Landschaft",
+            "Bâle-Campagne",
+            "Basilea Campagna",
+            "Basilea-Champagna",
+        ],
+    ),
+    (
+        "BS",
+        [
+            "Basel-Stadt",
+            "Basel-Stadt",
+            "Bâle-Ville",
+            "Basilea Città",
+            "Basilea-Citad",
+        ],
+    ),
+    (
+        "FR",
+        ["Fribourg", "Freiburg", "Fribourg", "Friburgo", "Friburg"],
+    ),
+    ("GE", ["Geneva", "Genf", "Genève", "Ginevra", "Genevra"]),
+    ("GL", ["Glarus", "Glarus", "Glaris", "Glarona", "Glaruna"]),
+    (
+        "GR",
+        [
+            "Graubünden",
+            "Graubünden",
+            "Grisons",
+            "Grigioni",
+            "Grischun",
+        ],
+    ),
+    ("JU", ["Jura", "Jura", "Jura", "Giura", "Giura"]),
+    ("LU", ["Lucerne", "Luzern", "Lucerne", "Lucerna", "Lucerna"]),
+    (
+        "NE",
+        [
+            "Neuchâtel",
+            "Neuenburg",
+            "Neuchâtel",
+            "Neuchâtel",
+            "Neuchâtel",
+        ],
+    ),
+    (
+        "NW",
+        [
+            "Nidwalden",
+            "Nidwalden",
+            "Nidwald",
+            "Nidvaldo",
+            "Sutsilvania",
+        ],
+    ),
+    (
+        "OW",
+        ["Obwalden", "Obwalden", "Obwald", "Obvaldo", "Sursilvania"],
+    ),
+    (
+        "SG",
+        [
+            "St. Gallen",
+            "St. Gallen",
+            "Saint-Gall",
+            "San Gallo",
+            "Son Gagl",
+        ],
+    ),
+    (
+        "SH",
+        [
+            "Schaffhausen",
+            "Schaffhausen",
+            "Schaffhouse",
+            "Sciaffusa",
+            "Schaffusa",
+        ],
+    ),
+    (
+        "SO",
+        ["Solothurn", "Solothurn", "Soleure", "Soletta", "Soloturn"],
+    ),
+    ("SZ", ["Schwyz", "Schwyz", "Schwytz", "Svitto", "Sviz"]),
+    (
+        "TG",
+        ["Thurgau", "Thurgau", "Thurgovie", "Turgovia", "Turgovia"],
+    ),
+    ("TI", ["Ticino", "Tessin", "Tessin", "Ticino", "Tessin"]),
+    ("UR", ["Uri", "Uri", "Uri", "Uri", "Uri"]),
+    ("VD", ["Vaud", "Waadt", "Vaud", "Vaud", "Vad"]),
+    ("VS", ["Valais", "Wallis", "Valais", "Vallese", "Vallais"]),
+    ("ZG", ["Zug", "Zug", "Zoug", "Zugo", "Zug"]),
+    ("ZH", ["Zurich", "Zürich", "Zurich", "Zurigo", "Turitg"]),
+];
+
+/// Every name in `CANTON_NAMES`, normalized by `normalize_name`, mapped to its
+/// canton code.
+static CANTON_CODES_BY_NAME: LazyLock<HashMap<String, &'static str>> = LazyLock::new(|| {
+    CANTON_NAMES
+        .iter()
+        .flat_map(|(code, names)| names.iter().map(move |name| (normalize_name(name), *code)))
+        .collect()
+});
+
+/// Where a `language`'s name sits in a `CANTON_NAMES` entry.
+fn name_index(language: Language) -> usize {
+    match language {
+        Language::En => 0,
+        Language::De => 1,
+        Language::Fr => 2,
+        Language::It => 3,
+        Language::Rm => 4,
+    }
+}
+
+/// Lowercases `name` and strips its accents, so "Genève" and "GENEVE" match.
+fn normalize_name(name: &str) -> String {
+    name.trim()
+        .nfd()
+        .filter(|c| !is_combining_mark(*c))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Each canton's capital with its coordinates: (code, city, latitude,
+/// longitude).
+pub(crate) const CANTON_CAPITALS: [(&str, &str, f64, f64); 26] = [
+    ("AG", "Aarau", 47.3925, 8.0457),
+    ("AI", "Appenzell", 47.3316, 9.4094),
+    ("AR", "Herisau", 47.3859, 9.2792),
+    ("BE", "Bern", 46.9481, 7.4474),
+    ("BL", "Liestal", 47.4814, 7.7343),
+    ("BS", "Basel", 47.5596, 7.5886),
+    ("FR", "Fribourg", 46.8063, 7.1608),
+    ("GE", "Geneva", 46.2044, 6.1432),
+    ("GL", "Glarus", 47.0404, 9.0679),
+    ("GR", "Chur", 46.8499, 9.5331),
+    ("JU", "Delémont", 47.3653, 7.3453),
+    ("LU", "Lucerne", 47.0502, 8.3093),
+    ("NE", "Neuchâtel", 46.9896, 6.9294),
+    ("NW", "Stans", 46.9579, 8.3659),
+    ("OW", "Sarnen", 46.8960, 8.2461),
+    ("SG", "St. Gallen", 47.4245, 9.3767),
+    ("SH", "Schaffhausen", 47.6979, 8.6344),
+    ("SO", "Solothurn", 47.2084, 7.5371),
+    ("SZ", "Schwyz", 47.0207, 8.6532),
+    ("TG", "Frauenfeld", 47.5536, 8.8988),
+    ("TI", "Bellinzona", 46.1930, 9.0208),
+    ("UR", "Altdorf", 46.8805, 8.6444),
+    ("VD", "Lausanne", 46.5197, 6.6323),
+    ("VS", "Sion", 46.2310, 7.3603),
+    ("ZG", "Zug", 47.1724, 8.5153),
+    ("ZH", "Zürich", 47.3769, 8.5417),
+];
+
 impl Canton {
     const VALID_CANTONS: [&'static str; 26] = [
         "AG", "AI", "AR", "BE", "BL", "BS", "FR", "GE", "GL", "GR", "JU", "LU", "NE", "NW", "OW",
@@ -38,14 +229,136 @@ impl Canton {
         if Self::VALID_CANTONS.contains(&uppercased.as_str()) {
             Ok(Canton(uppercased))
         } else {
-            Err(CantonError::InvalidCanton(s))
+            Err(CantonError::InvalidCanton {
+                input: trimmed.to_string(),
+                normalized: uppercased,
+            })
         }
     }
 
+    /// The canton named `name` in any language of `CANTON_NAMES`, ignoring
+    /// case and accents: "zurigo", "Zürich" and "ZURICH" all give ZH. For a
+    /// search box; codes are not names, use `parse` for those.
+    pub fn from_name(name: &str) -> Option<Canton> {
+        CANTON_CODES_BY_NAME
+            .get(&normalize_name(name))
+            .map(|code| Canton(code.to_string()))
+    }
+
     /// Returns the address as a string slice. Useful for logging and display.
     pub fn as_str(&self) -> &str {
         &self.0
     }
+
+    /// The canton's official name in `language`, e.g. "Genève" for GE in French.
+    pub fn name(&self, language: Language) -> &'static str {
+        CANTON_NAMES
+            .iter()
+            .find(|(code, _)| *code == self.0)
+            .map(|(_, names)| names[name_index(language)])
+            .expect("A parsed canton always has a name.")
+    }
+
+    /// Like `name`, but as `names` (the `cantons` table) has it. Falls back
+    /// to `name` for a canton missing there.
+    pub fn name_in<'a>(&self, names: &'a CantonNames, language: Language) -> &'a str {
+        names
+            .0
+            .get(&self.0)
+            .map_or_else(|| self.name(language), |names| &names[name_index(language)])
+    }
+
+    /// Where the canton's capital lies, e.g. Bern for BE. Handy for centering
+    /// a map on the canton.
+    pub fn capital_point(&self) -> Point {
+        CANTON_CAPITALS
+            .iter()
+            .find(|(code, ..)| *code == self.0)
+            .map(|&(_, _, latitude, longitude)| Point::new(latitude, longitude))
+            .expect("A parsed canton always has a capital.")
+    }
+
+    /// Reverse geocoding for auto-filling the canton from a map click: the
+    /// canton whose capital lies closest to `point`. There are no canton
+    /// boundaries in this crate, so this is approximate: near a border (or in
+    /// a large canton like GR) the nearest capital can belong to a neighbour.
+    pub fn nearest_capital(point: &Point) -> Canton {
+        CANTON_CAPITALS
+            .iter()
+            .map(|&(code, _, latitude, longitude)| {
+                (code, point.distance_to(&Point::new(latitude, longitude)))
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(code, _)| Canton(code.to_string()))
+            .expect("There is always a capital.")
+    }
+
+    /// The canton `nearest_capital` gives for `point`, when `point` lies
+    /// more than `tolerance_km` closer to that canton's capital than to this
+    /// one's: a coarse sign that `point` is not in this canton. `None` when
+    /// they agree or are within the tolerance, which absorbs large cantons
+    /// and border areas.
+    pub fn mismatch_at(&self, point: &Point, tolerance_km: f64) -> Option<Canton> {
+        let nearest = Self::nearest_capital(point);
+        let detour_km =
+            point.distance_to(&self.capital_point()) - point.distance_to(&nearest.capital_point());
+        (nearest != *self && detour_km > tolerance_km).then_some(nearest)
+    }
+
+    /// The canton's code together with its name in `language`.
+    pub fn localized(&self, language: Language) -> LocalizedCanton<'_> {
+        LocalizedCanton {
+            code: self.as_str(),
+            name: self.name(language),
+        }
+    }
+
+    /// `serialize_with` helper emitting `{"code":"ZH","name":"Zürich"}` (name in
+    /// the default language) instead of the bare code.
+    pub fn serialize_with_name<S>(canton: &Canton, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serde::Serialize::serialize(&canton.localized(Language::default()), serializer)
+    }
+}
+
+/// The canton names in the `cantons` table, by code, in the order of
+/// `CANTON_NAMES`. Load once and share; see `Canton::name_in`.
+#[derive(Debug, Clone, Default)]
+pub struct CantonNames(HashMap<String, [String; 5]>);
+
+impl CantonNames {
+    /// Load every row of `cantons`. Cheap: 26 rows.
+    #[tracing::instrument(name = "Load canton names", skip(pool))]
+    pub async fn load(pool: &PgPool) -> Result<Self, sqlx::Error> {
+        let rows =
+            sqlx::query!("SELECT code, name_en, name_de, name_fr, name_it, name_rm FROM cantons")
+                .fetch_all(pool)
+                .await?;
+
+        Ok(Self(
+            rows.into_iter()
+                .map(|row| {
+                    let names = [
+                        row.name_en,
+                        row.name_de,
+                        row.name_fr,
+                        row.name_it,
+                        row.name_rm,
+                    ];
+                    (row.code, names)
+                })
+                .collect(),
+        ))
+    }
+}
+
+/// A canton serialized as `{"code": .., "name": ..}`.
+#[derive(Debug, serde::Serialize)]
+pub struct LocalizedCanton<'a> {
+    pub code: &'a str,
+    pub name: &'static str,
 }
 
 impl AsRef<str> for Canton {
@@ -85,8 +398,11 @@ impl_sqlx_for_string_domain_type!(Canton);
 
 #[cfg(test)]
 mod tests {
-    use super::Canton;
+    use super::{CANTON_CAPITALS, CANTON_NAMES, Canton, CantonError, CantonNames};
+    use crate::domain::farm::Point;
+    use crate::i18n::Language;
     use claims::{assert_err, assert_ok};
+    use serde_json::json;
 
     #[test]
     fn valid_canton_uppercase() {
@@ -100,15 +416,204 @@ mod tests {
         assert_ok!(Canton::parse(canton.to_string()));
     }
 
+    #[test]
+    fn every_canton_has_a_capital_within_switzerland() {
+        for code in Canton::VALID_CANTONS {
+            let point = Canton::parse(code.to_string()).unwrap().capital_point();
+            assert_ok!(
+                Point::parse(&format!("{},{}", point.latitude, point.longitude)),
+                "{code}"
+            );
+        }
+        assert_eq!(CANTON_CAPITALS.len(), Canton::VALID_CANTONS.len());
+    }
+
+    #[test]
+    fn capital_point_is_the_capital_city() {
+        let bern = Canton::parse("BE".to_string()).unwrap().capital_point();
+        assert_eq!(Point::new(46.9481, 7.4474), bern);
+    }
+
+    #[test]
+    fn every_capital_is_its_own_nearest_capital() {
+        for code in Canton::VALID_CANTONS {
+            let canton = Canton::parse(code.to_string()).unwrap();
+            assert_eq!(canton, Canton::nearest_capital(&canton.capital_point()));
+        }
+    }
+
+    #[test]
+    fn nearest_capital_of_oerlikon_is_zurich() {
+        let oerlikon = Point::new(47.4111, 8.5441);
+        assert_eq!("ZH", Canton::nearest_capital(&oerlikon).as_str());
+    }
+
+    #[test]
+    fn nearest_capital_of_carouge_is_geneva() {
+        let carouge = Point::new(46.1839, 6.1394);
+        assert_eq!("GE", Canton::nearest_capital(&carouge).as_str());
+    }
+
+    #[test]
+    fn nearest_capital_is_only_a_hint_near_borders() {
+        // Winterthur is in ZH, but Frauenfeld (TG) is closer than Zürich.
+        let winterthur = Point::new(47.4988, 8.7237);
+        assert_eq!("TG", Canton::nearest_capital(&winterthur).as_str());
+    }
+
     #[test]
     fn invalid_canton_rejected() {
         let canton = "DE";
         assert_err!(Canton::parse(canton.to_string()));
     }
 
+    #[test]
+    fn invalid_canton_error_carries_the_trimmed_and_normalized_input() {
+        let error = Canton::parse("  de  ".to_string()).unwrap_err();
+
+        assert!(matches!(
+            &error,
+            CantonError::InvalidCanton { input, normalized } if input == "de" && normalized == "DE"
+        ));
+        let message = error.to_string();
+        assert!(message.contains("'DE'"), "{message}");
+        assert!(!message.contains("  de  "), "{message}");
+    }
+
+    #[test]
+    fn every_valid_canton_has_a_name() {
+        for code in Canton::VALID_CANTONS {
+            let canton = Canton::parse(code.to_string()).unwrap();
+            assert!(!canton.name(Language::De).is_empty());
+        }
+    }
+
+    #[test]
+    fn canton_name_is_localized() {
+        let canton = Canton::parse("GE".to_string()).unwrap();
+        assert_eq!(canton.name(Language::En), "Geneva");
+        assert_eq!(canton.name(Language::De), "Genf");
+        assert_eq!(canton.name(Language::Fr), "Genève");
+        assert_eq!(canton.name(Language::It), "Ginevra");
+        assert_eq!(canton.name(Language::Rm), "Genevra");
+    }
+
+    #[test]
+    fn from_name_resolves_an_italian_name() {
+        assert_eq!(Canton::from_name("zurigo").unwrap().as_str(), "ZH");
+    }
+
+    #[test]
+    fn from_name_ignores_case_and_accents() {
+        for name in ["genève", "Geneve", "GENÈVE", " Genf "] {
+            assert_eq!(Canton::from_name(name).unwrap().as_str(), "GE", "{name}");
+        }
+    }
+
+    #[test]
+    fn from_name_resolves_every_name_to_its_own_canton() {
+        for (code, names) in CANTON_NAMES {
+            for name in names {
+                assert_eq!(Canton::from_name(name).unwrap().as_str(), code, "{name}");
+            }
+        }
+    }
+
+    #[test]
+    fn from_name_rejects_codes_and_unknown_names() {
+        assert!(Canton::from_name("ZH").is_none());
+        assert!(Canton::from_name("Atlantis").is_none());
+        assert!(Canton::from_name("").is_none());
+    }
+
     #[test]
     fn invalid_canton_empty_string() {
         let canton = "";
         assert_err!(Canton::parse(canton.to_string()));
     }
+
+    #[test]
+    fn serializes_to_the_code() {
+        let canton = Canton::parse("zh".to_string()).unwrap();
+        assert_eq!(serde_json::to_value(&canton).unwrap(), json!("ZH"));
+    }
+
+    #[test]
+    fn serialize_with_name_emits_code_and_name() {
+        #[derive(serde::Serialize)]
+        struct Wrapper {
+            #[serde(serialize_with = "Canton::serialize_with_name")]
+            canton: Canton,
+        }
+
+        let wrapper = Wrapper {
+            canton: Canton::parse("ZH".to_string()).unwrap(),
+        };
+
+        assert_eq!(
+            serde_json::to_value(&wrapper).unwrap(),
+            json!({"canton": {"code": "ZH", "name": "Zürich"}})
+        );
+    }
+
+    #[test]
+    fn localized_uses_the_requested_language() {
+        let canton = Canton::parse("GE".to_string()).unwrap();
+        assert_eq!(
+            serde_json::to_value(canton.localized(Language::Fr)).unwrap(),
+            json!({"code": "GE", "name": "Genève"})
+        );
+    }
+
+    #[test]
+    fn deserializes_and_normalizes_a_valid_code() {
+        let canton: Canton = serde_json::from_value(json!("be")).unwrap();
+        assert_eq!(canton.as_str(), "BE");
+    }
+
+    #[test]
+    fn deserialize_rejects_an_invalid_code() {
+        assert_err!(serde_json::from_value::<Canton>(json!("XX")));
+        assert_err!(serde_json::from_value::<Canton>(json!("")));
+    }
+
+    #[test]
+    fn name_in_prefers_the_loaded_names_and_falls_back_to_the_built_in_ones() {
+        let ge = Canton::parse("GE".to_string()).unwrap();
+        let zh = Canton::parse("ZH".to_string()).unwrap();
+        let names = CantonNames(
+            [(
+                "GE".to_string(),
+                ["Geneva", "Genf", "Genève", "Ginevra", "Genevra"],
+            )]
+            .into_iter()
+            .map(|(code, names)| (code, names.map(String::from)))
+            .collect(),
+        );
+
+        assert_eq!("Ginevra", ge.name_in(&names, Language::It));
+        assert_eq!("Zürich", zh.name_in(&names, Language::De));
+        assert_eq!("Genève", ge.name_in(&CantonNames::default(), Language::Fr));
+    }
+
+    #[test]
+    fn mismatch_at_flags_coordinates_far_from_the_canton() {
+        let zurich = Point::new(47.3769, 8.5417);
+        let zh = Canton::parse("ZH".to_string()).unwrap();
+        let ge = Canton::parse("GE".to_string()).unwrap();
+
+        assert_eq!(None, zh.mismatch_at(&zurich, 25.0));
+        assert_eq!(Some(zh), ge.mismatch_at(&zurich, 25.0));
+    }
+
+    #[test]
+    fn mismatch_at_tolerates_a_neighbouring_capital_within_the_tolerance() {
+        // Interlaken (BE) is closer to Sarnen (OW) than to Bern.
+        let interlaken = Point::new(46.6863, 7.8632);
+        let be = Canton::parse("BE".to_string()).unwrap();
+
+        assert_eq!("OW", Canton::nearest_capital(&interlaken).as_str());
+        assert_eq!(None, be.mismatch_at(&interlaken, 25.0));
+        assert!(be.mismatch_at(&interlaken, 0.0).is_some());
+    }
 }
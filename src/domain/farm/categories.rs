@@ -32,11 +32,87 @@ pub enum CategoriesError {
 
     #[error("Duplicate category: '{0}'.")]
     DuplicateCategory(String),
+
+    #[error("Categories take up {actual} bytes. Maximum allowed is {max}.")]
+    TotalSizeTooLarge { actual: usize, max: usize },
+
+    #[error("Category '{0}' contains characters that are not allowed.")]
+    InvalidCharacters(String),
+}
+
+/// Optional checks for `Categories::parse_with_options`.
+///
+/// The default enables no character-set check, keeps internal whitespace as
+/// given and applies the generous `DEFAULT_MAX_TOTAL_BYTES` cap, matching
+/// `Categories::parse`.
+#[derive(Debug, Clone, Copy)]
+pub struct CategoriesParseOptions {
+    /// Reject categories containing control characters (e.g. `\u{0007}`).
+    pub reject_control_characters: bool,
+    /// Only allow letters (including diacritics), spaces and hyphens.
+    pub letters_spaces_and_hyphens_only: bool,
+    /// Cap on the trimmed categories' combined length in bytes, i.e. what
+    /// ends up in the `TEXT[]` column.
+    pub max_total_bytes: usize,
+    /// Collapse runs of whitespace inside a category to a single space, so
+    /// "Organic   Dairy" and "Organic Dairy" are the same category.
+    pub collapse_internal_whitespace: bool,
+    /// Tell categories differing only in case apart, so "CO2" and "Co2" are
+    /// both kept, for duplicates and the `Categories::*_with_options` lookups.
+    pub case_sensitive: bool,
+}
+
+impl Default for CategoriesParseOptions {
+    fn default() -> Self {
+        Self {
+            reject_control_characters: false,
+            letters_spaces_and_hyphens_only: false,
+            max_total_bytes: Self::DEFAULT_MAX_TOTAL_BYTES,
+            collapse_internal_whitespace: false,
+            case_sensitive: false,
+        }
+    }
+}
+
+impl CategoriesParseOptions {
+    /// Above what the count and length limits allow (50 x 50 bytes), so it
+    /// only bites when those are loosened.
+    pub const DEFAULT_MAX_TOTAL_BYTES: usize = 4096;
+
+    /// What two categories are compared by.
+    fn comparison_key(&self, category: &str) -> String {
+        if self.case_sensitive {
+            category.to_string()
+        } else {
+            category.to_lowercase()
+        }
+    }
+
+    /// `category` trimmed, with its inner whitespace collapsed if asked.
+    fn normalize(&self, category: &str) -> String {
+        if self.collapse_internal_whitespace {
+            category.split_whitespace().collect::<Vec<_>>().join(" ")
+        } else {
+            category.trim().to_string()
+        }
+    }
+
+    fn allows(&self, category: &str) -> bool {
+        category.chars().all(|c| {
+            if self.letters_spaces_and_hyphens_only {
+                c.is_alphabetic() || c == ' ' || c == '-'
+            } else {
+                !(self.reject_control_characters && c.is_control())
+            }
+        })
+    }
 }
 
 impl Categories {
-    const MAX_CATEGORIES: usize = 50;
-    const MAX_CATEGORY_NAME_LENGTH: usize = 50;
+    /// Most categories a farm can have.
+    pub const MAX_CATEGORIES: usize = 50;
+    /// Longest category name, in bytes once trimmed.
+    pub const MAX_CATEGORY_NAME_LENGTH: usize = 50;
 
     /// Parse and validate a list of farm categories
     ///
@@ -47,6 +123,46 @@ impl Categories {
     /// .No duplicates (case-insensitive)
     /// .Trims whitespace from each category
     pub fn parse(categories: Vec<String>) -> Result<Self, CategoriesError> {
+        Self::parse_with_options(categories, CategoriesParseOptions::default())
+    }
+
+    /// Same as `parse`, but collapses case-insensitive duplicates (compared
+    /// after trimming) into their first occurrence instead of rejecting them.
+    /// The count limit applies to the categories left after collapsing. A
+    /// list of nothing but blank entries is `EmptyCategories`, as if none
+    /// had been sent.
+    pub fn parse_dedup(categories: Vec<String>) -> Result<Self, CategoriesError> {
+        Self::parse_dedup_with_options(categories, CategoriesParseOptions::default())
+    }
+
+    /// Like `parse_dedup`, validating as `parse_with_options` does; entries
+    /// are duplicates when they compare equal under `options`.
+    pub fn parse_dedup_with_options(
+        categories: Vec<String>,
+        options: CategoriesParseOptions,
+    ) -> Result<Self, CategoriesError> {
+        if categories.iter().all(|category| category.trim().is_empty()) {
+            return Err(CategoriesError::EmptyCategories);
+        }
+
+        let mut already_seen: HashSet<String> = HashSet::new();
+        let unique = categories
+            .into_iter()
+            .filter(|category| {
+                already_seen.insert(options.comparison_key(&options.normalize(category)))
+            })
+            .collect();
+
+        Self::parse_with_options(unique, options)
+    }
+
+    /// Same as `parse`, additionally applying the character-set checks and
+    /// whitespace normalization enabled in `options`. Categories are
+    /// normalized before the length, character and duplicate checks.
+    pub fn parse_with_options(
+        categories: Vec<String>,
+        options: CategoriesParseOptions,
+    ) -> Result<Self, CategoriesError> {
         if categories.is_empty() {
             return Err(CategoriesError::EmptyCategories);
         }
@@ -59,16 +175,16 @@ impl Categories {
         }
 
         let mut validated: Vec<String> = Vec::new();
-        let mut already_seen_lowercase: HashSet<String> = HashSet::new();
+        let mut already_seen: HashSet<String> = HashSet::new();
 
         for category in categories {
-            let trimmed = category.trim().to_string();
+            let trimmed = options.normalize(&category);
 
             if trimmed.is_empty() {
                 return Err(CategoriesError::EmptyCategoryValue(category));
             }
 
-            if trimmed.len() > Self::MAX_CATEGORIES {
+            if trimmed.len() > Self::MAX_CATEGORY_NAME_LENGTH {
                 return Err(CategoriesError::CategoryLengthTooLong {
                     category: trimmed.clone(),
                     max: Self::MAX_CATEGORY_NAME_LENGTH,
@@ -76,16 +192,26 @@ impl Categories {
                 });
             }
 
-            let lowercase = trimmed.to_lowercase();
+            if !options.allows(&trimmed) {
+                return Err(CategoriesError::InvalidCharacters(trimmed));
+            }
 
             // Tries to insert. If the category already exists, it returns false, otherwise, it returns an Error.
-            if !(already_seen_lowercase).insert(lowercase) {
+            if !already_seen.insert(options.comparison_key(&trimmed)) {
                 return Err(CategoriesError::DuplicateCategory(trimmed));
             }
 
             validated.push(trimmed);
         }
 
+        let total_bytes = validated.iter().map(String::len).sum();
+        if total_bytes > options.max_total_bytes {
+            return Err(CategoriesError::TotalSizeTooLarge {
+                actual: total_bytes,
+                max: options.max_total_bytes,
+            });
+        }
+
         Ok(Self(validated))
     }
 
@@ -114,10 +240,67 @@ impl Categories {
         self.0.is_empty()
     }
 
+    /// The maximum number of categories a farm can have.
+    pub fn max_categories() -> usize {
+        Self::MAX_CATEGORIES
+    }
+
+    /// How many more categories can be added before hitting the limit.
+    pub fn capacity_remaining(&self) -> usize {
+        Self::MAX_CATEGORIES.saturating_sub(self.len())
+    }
+
+    /// Returns `true` if `n` more categories would still be within the limit.
+    pub fn can_add(&self, n: usize) -> bool {
+        n <= self.capacity_remaining()
+    }
+
     /// Checks if a category exists in the list (case-insensitive).
     pub fn contains(&self, category: &str) -> bool {
-        let lowercased = category.to_lowercase();
-        self.0.iter().any(|c| c.to_lowercase() == lowercased)
+        self.contains_with_options(category, &CategoriesParseOptions::default())
+    }
+
+    /// Like `contains`, comparing as `options` does, i.e. case-sensitively
+    /// when `options.case_sensitive` is set.
+    pub fn contains_with_options(&self, category: &str, options: &CategoriesParseOptions) -> bool {
+        let needle = options.comparison_key(category);
+        self.0.iter().any(|c| options.comparison_key(c) == needle)
+    }
+
+    /// Checks that every one of `needles` is in the list (case-insensitive).
+    /// Vacuously `true` for no needles. `GET /farms?match=all` applies the
+    /// same rule in SQL, against the farm's taxonomy ids.
+    pub fn contains_all(&self, needles: &[&str]) -> bool {
+        self.contains_all_with_options(needles, &CategoriesParseOptions::default())
+    }
+
+    /// Like `contains_all`, comparing as `options` does.
+    pub fn contains_all_with_options(
+        &self,
+        needles: &[&str],
+        options: &CategoriesParseOptions,
+    ) -> bool {
+        needles
+            .iter()
+            .all(|needle| self.contains_with_options(needle, options))
+    }
+
+    /// Checks that at least one of `needles` is in the list (case-insensitive).
+    /// `false` for no needles. `GET /farms?match=any` applies the same rule in
+    /// SQL.
+    pub fn contains_any(&self, needles: &[&str]) -> bool {
+        self.contains_any_with_options(needles, &CategoriesParseOptions::default())
+    }
+
+    /// Like `contains_any`, comparing as `options` does.
+    pub fn contains_any_with_options(
+        &self,
+        needles: &[&str],
+        options: &CategoriesParseOptions,
+    ) -> bool {
+        needles
+            .iter()
+            .any(|needle| self.contains_with_options(needle, options))
     }
 }
 
@@ -173,8 +356,41 @@ impl_sqlx_for_vec_string_domain_type!(Categories);
 
 #[cfg(test)]
 mod tests {
-    use super::Categories;
-    use claims::{assert_err, assert_ok};
+    use super::{Categories, CategoriesError, CategoriesParseOptions};
+    use claims::{assert_err, assert_matches, assert_ok};
+
+    const REJECT_CONTROL: CategoriesParseOptions = CategoriesParseOptions {
+        reject_control_characters: true,
+        letters_spaces_and_hyphens_only: false,
+        max_total_bytes: CategoriesParseOptions::DEFAULT_MAX_TOTAL_BYTES,
+        collapse_internal_whitespace: false,
+        case_sensitive: false,
+    };
+
+    const LETTERS_ONLY: CategoriesParseOptions = CategoriesParseOptions {
+        reject_control_characters: false,
+        letters_spaces_and_hyphens_only: true,
+        max_total_bytes: CategoriesParseOptions::DEFAULT_MAX_TOTAL_BYTES,
+        collapse_internal_whitespace: false,
+        case_sensitive: false,
+    };
+
+    /// Caps the categories at 10 bytes in total.
+    const TEN_BYTES: CategoriesParseOptions = CategoriesParseOptions {
+        reject_control_characters: false,
+        letters_spaces_and_hyphens_only: false,
+        max_total_bytes: 10,
+        collapse_internal_whitespace: false,
+        case_sensitive: false,
+    };
+
+    const COLLAPSE_WHITESPACE: CategoriesParseOptions = CategoriesParseOptions {
+        reject_control_characters: false,
+        letters_spaces_and_hyphens_only: false,
+        max_total_bytes: CategoriesParseOptions::DEFAULT_MAX_TOTAL_BYTES,
+        collapse_internal_whitespace: true,
+        case_sensitive: false,
+    };
 
     #[test]
     fn valid_single_category_is_valid() {
@@ -195,6 +411,120 @@ mod tests {
         assert_eq!(categories.unwrap().len(), 4);
     }
 
+    #[test]
+    fn parse_dedup_collapses_case_insensitive_duplicates() {
+        let categories = Categories::parse_dedup(vec!["Egg".to_string(), "EGG".to_string()]);
+
+        assert_eq!(categories.unwrap().as_slice(), ["Egg"]);
+    }
+
+    #[test]
+    fn parse_dedup_keeps_the_first_occurrence_in_order() {
+        let categories = Categories::parse_dedup(vec![
+            "Milk".to_string(),
+            "Egg".to_string(),
+            " milk ".to_string(),
+            "Cheese".to_string(),
+            "egg".to_string(),
+        ]);
+
+        assert_eq!(categories.unwrap().as_slice(), ["Milk", "Egg", "Cheese"]);
+    }
+
+    #[test]
+    fn case_variants_are_distinct_in_case_sensitive_mode() {
+        let options = CategoriesParseOptions {
+            case_sensitive: true,
+            ..CategoriesParseOptions::default()
+        };
+
+        let categories =
+            Categories::parse_with_options(vec!["Co2".to_string(), "CO2".to_string()], options)
+                .unwrap();
+
+        assert_eq!(categories.as_slice(), ["Co2", "CO2"]);
+        assert!(categories.contains_with_options("CO2", &options));
+        assert!(!categories.contains_with_options("co2", &options));
+        assert!(categories.contains_all_with_options(&["Co2", "CO2"], &options));
+        assert!(!categories.contains_all_with_options(&["CO2", "co2"], &options));
+        assert!(!categories.contains_any_with_options(&["co2", "cO2"], &options));
+    }
+
+    #[test]
+    fn parse_dedup_keeps_case_variants_in_case_sensitive_mode() {
+        let options = CategoriesParseOptions {
+            case_sensitive: true,
+            ..CategoriesParseOptions::default()
+        };
+
+        let categories = Categories::parse_dedup_with_options(
+            vec!["Co2".to_string(), "CO2".to_string(), " CO2 ".to_string()],
+            options,
+        )
+        .unwrap();
+
+        assert_eq!(categories.as_slice(), ["Co2", "CO2"]);
+    }
+
+    #[test]
+    fn case_variants_are_duplicates_by_default() {
+        let categories = Categories::parse(vec!["Co2".to_string(), "CO2".to_string()]);
+
+        assert_matches!(categories, Err(CategoriesError::DuplicateCategory(_)));
+    }
+
+    #[test]
+    fn parse_still_rejects_duplicates() {
+        let categories = Categories::parse(vec!["Egg".to_string(), "EGG".to_string()]);
+
+        assert_matches!(categories, Err(CategoriesError::DuplicateCategory(_)));
+    }
+
+    #[test]
+    fn parse_dedup_still_validates() {
+        assert_matches!(
+            Categories::parse_dedup(vec![]),
+            Err(CategoriesError::EmptyCategories)
+        );
+        assert_matches!(
+            Categories::parse_dedup(vec!["Egg".to_string(), " ".to_string()]),
+            Err(CategoriesError::EmptyCategoryValue(_))
+        );
+
+        let too_many = (0..=Categories::MAX_CATEGORIES)
+            .map(|i| format!("Category {i}"))
+            .collect();
+        assert_matches!(
+            Categories::parse_dedup(too_many),
+            Err(CategoriesError::TooManyCategories { .. })
+        );
+    }
+
+    #[test]
+    fn parse_dedup_treats_only_blank_entries_as_no_categories() {
+        assert_matches!(
+            Categories::parse_dedup(vec![" ".to_string(), "".to_string()]),
+            Err(CategoriesError::EmptyCategories)
+        );
+    }
+
+    #[test]
+    fn parse_dedup_of_only_case_variants_keeps_one_category() {
+        let categories = Categories::parse_dedup(vec!["Egg".to_string(), "egg".to_string()]);
+
+        assert_eq!(categories.unwrap().len(), 1);
+    }
+
+    #[test]
+    fn parse_dedup_counts_categories_after_collapsing() {
+        let mut categories: Vec<String> = (0..Categories::MAX_CATEGORIES)
+            .map(|i| format!("Category {i}"))
+            .collect();
+        categories.push("CATEGORY 0".to_string());
+
+        assert_ok!(Categories::parse_dedup(categories));
+    }
+
     #[test]
     fn empty_categories_vector_is_rejected() {
         let empty_categories = Categories::parse(vec![]);
@@ -331,6 +661,26 @@ mod tests {
         assert!(categories.contains("EGG"));
     }
 
+    #[test]
+    fn contains_all_requires_every_needle_case_insensitive() {
+        let categories = Categories::parse(vec!["Dairy".to_string(), "Egg".to_string()]).unwrap();
+
+        assert!(categories.contains_all(&["dairy", "EGG"]));
+        assert!(categories.contains_all(&["Dairy"]));
+        assert!(categories.contains_all(&[]));
+        assert!(!categories.contains_all(&["dairy", "Fruit"]));
+    }
+
+    #[test]
+    fn contains_any_requires_one_needle_case_insensitive() {
+        let categories = Categories::parse(vec!["Dairy".to_string(), "Egg".to_string()]).unwrap();
+
+        assert!(categories.contains_any(&["fruit", "EGG"]));
+        assert!(categories.contains_any(&["dAiRy"]));
+        assert!(!categories.contains_any(&["Fruit", "Vegetables"]));
+        assert!(!categories.contains_any(&[]));
+    }
+
     #[test]
     fn displays_formats_correctly() {
         let categories = Categories::parse(vec!["Dairy".to_string(), "Egg".to_string()]).unwrap();
@@ -416,6 +766,22 @@ mod tests {
         assert_eq!(categories.len(), 2);
     }
 
+    #[test]
+    fn capacity_helpers_reflect_the_number_of_categories() {
+        let categories = Categories::parse(vec![
+            "Dairy".to_string(),
+            "Egg".to_string(),
+            "Fruit".to_string(),
+        ])
+        .unwrap();
+
+        assert_eq!(Categories::max_categories(), 50);
+        assert_eq!(categories.capacity_remaining(), 47);
+        assert!(categories.can_add(0));
+        assert!(categories.can_add(47));
+        assert!(!categories.can_add(48));
+    }
+
     #[test]
     fn is_empty_returns_false_after_validation() {
         let categories = Categories::parse(vec!["Dairy".to_string(), "Egg".to_string()]).unwrap();
@@ -463,4 +829,114 @@ mod tests {
         let cat2 = Categories::parse(vec!["B".to_string(), "A".to_string()]).unwrap();
         assert_eq!(cat1, cat2);
     }
+
+    #[test]
+    fn control_characters_are_allowed_by_default() {
+        let categories = Categories::parse(vec!["Dai\u{0007}ry".to_string()]);
+        assert_ok!(categories);
+    }
+
+    #[test]
+    fn control_characters_are_rejected_when_configured() {
+        let categories =
+            Categories::parse_with_options(vec!["Dai\u{0007}ry".to_string()], REJECT_CONTROL);
+
+        assert_matches!(categories, Err(CategoriesError::InvalidCharacters(_)));
+    }
+
+    #[test]
+    fn emoji_pass_the_control_character_check() {
+        let categories =
+            Categories::parse_with_options(vec!["Eggs 🥚".to_string()], REJECT_CONTROL);
+        assert_ok!(categories);
+    }
+
+    #[test]
+    fn letters_only_rejects_control_characters_digits_and_emoji() {
+        for category in ["Dai\u{0007}ry", "Category1", "Eggs 🥚", "Fruit & Veg"] {
+            let categories =
+                Categories::parse_with_options(vec![category.to_string()], LETTERS_ONLY);
+            assert_err!(categories, "{category:?} should be rejected");
+        }
+    }
+
+    #[test]
+    fn letters_only_accepts_diacritics_spaces_and_hyphens() {
+        let categories = Categories::parse_with_options(
+            vec![
+                "Gruyère".to_string(),
+                "Agriculture bio".to_string(),
+                "Berg-Landwirtschaft".to_string(),
+            ],
+            LETTERS_ONLY,
+        );
+
+        assert_ok!(categories);
+    }
+
+    #[test]
+    fn categories_at_the_size_cap_are_accepted() {
+        let categories = Categories::parse_with_options(
+            vec!["Dairy".to_string(), " Fruit ".to_string()],
+            TEN_BYTES,
+        );
+
+        assert_ok!(categories);
+    }
+
+    #[test]
+    fn categories_past_the_size_cap_are_rejected() {
+        let categories = Categories::parse_with_options(
+            vec!["Dairy".to_string(), "Fruits".to_string()],
+            TEN_BYTES,
+        );
+
+        assert_matches!(
+            categories,
+            Err(CategoriesError::TotalSizeTooLarge {
+                actual: 11,
+                max: 10
+            })
+        );
+    }
+
+    #[test]
+    fn the_default_size_cap_admits_the_largest_valid_list() {
+        let categories = (0..50)
+            .map(|i| format!("{i:0>50}"))
+            .collect::<Vec<String>>();
+
+        assert_ok!(Categories::parse(categories));
+    }
+
+    #[test]
+    fn internal_whitespace_is_kept_by_default() {
+        let categories = Categories::parse(vec![
+            "Organic   Dairy".to_string(),
+            "Organic Dairy".to_string(),
+        ]);
+
+        assert_eq!(
+            categories.unwrap().as_slice(),
+            ["Organic   Dairy", "Organic Dairy"]
+        );
+    }
+
+    #[test]
+    fn collapsed_internal_whitespace_makes_near_duplicates_duplicates() {
+        let collapsed = Categories::parse_with_options(
+            vec!["  Organic \t  Dairy ".to_string()],
+            COLLAPSE_WHITESPACE,
+        );
+        assert_eq!(collapsed.unwrap().as_slice(), ["Organic Dairy"]);
+
+        let categories = Categories::parse_with_options(
+            vec!["Organic   Dairy".to_string(), "Organic Dairy".to_string()],
+            COLLAPSE_WHITESPACE,
+        );
+        assert_matches!(
+            categories,
+            Err(CategoriesError::DuplicateCategory(category)) if category == "Organic Dairy"
+        );
+    }
 }
@@ -0,0 +1,155 @@
+//! Organic and production certifications a farm can hold.
+//!
+//! Stored in PostgreSQL as their codes in a TEXT[] column, and sent over the
+//! wire as the same codes (e.g. `"bio_suisse"`).
+
+use sqlx::encode::IsNull;
+use sqlx::error::BoxDynError;
+use sqlx::postgres::{PgArgumentBuffer, PgHasArrayType, PgTypeInfo, PgValueRef};
+use sqlx::{Decode, Encode, Postgres, Type};
+use std::fmt::Display;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Certification {
+    /// The Bio Suisse "Knospe" organic label.
+    BioSuisse,
+    /// Biodynamic (Demeter).
+    Demeter,
+    /// IP-SUISSE integrated production.
+    IpSuisse,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum CertificationError {
+    #[error("Certification cannot be empty.")]
+    EmptyCertification,
+
+    #[error("Invalid certification: {0}. Must be one of 'bio_suisse', 'demeter', 'ip_suisse'.")]
+    InvalidCertification(String),
+}
+
+impl Certification {
+    pub const ALL: [Certification; 3] = [Self::BioSuisse, Self::Demeter, Self::IpSuisse];
+
+    /// Parse a certification code, ignoring surrounding whitespace and case.
+    pub fn parse(s: String) -> Result<Self, CertificationError> {
+        let trimmed = s.trim();
+
+        if trimmed.is_empty() {
+            return Err(CertificationError::EmptyCertification);
+        }
+
+        let code = trimmed.to_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|certification| certification.as_str() == code)
+            .ok_or_else(|| CertificationError::InvalidCertification(trimmed.to_string()))
+    }
+
+    /// The stored (and serialized) code.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::BioSuisse => "bio_suisse",
+            Self::Demeter => "demeter",
+            Self::IpSuisse => "ip_suisse",
+        }
+    }
+}
+
+impl Display for Certification {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl serde::Serialize for Certification {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Certification {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Self::parse(s).map_err(serde::de::Error::custom)
+    }
+}
+
+impl Type<Postgres> for Certification {
+    fn type_info() -> PgTypeInfo {
+        <String as Type<Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        <String as Type<Postgres>>::compatible(ty)
+    }
+}
+
+impl PgHasArrayType for Certification {
+    fn array_type_info() -> PgTypeInfo {
+        <String as PgHasArrayType>::array_type_info()
+    }
+}
+
+impl<'r> Decode<'r, Postgres> for Certification {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        let s = <String as Decode<Postgres>>::decode(value)?;
+        Ok(Self::parse(s)?)
+    }
+}
+
+impl<'q> Encode<'q, Postgres> for Certification {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, BoxDynError> {
+        <&str as Encode<Postgres>>::encode_by_ref(&self.as_str(), buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Certification;
+    use claims::{assert_err, assert_ok_eq};
+    use serde_json::json;
+
+    #[test]
+    fn every_code_parses_back_to_its_certification() {
+        for certification in Certification::ALL {
+            assert_ok_eq!(
+                Certification::parse(certification.as_str().to_string()),
+                certification
+            );
+        }
+    }
+
+    #[test]
+    fn parsing_ignores_case_and_surrounding_whitespace() {
+        assert_ok_eq!(
+            Certification::parse("  Bio_Suisse ".to_string()),
+            Certification::BioSuisse
+        );
+    }
+
+    #[test]
+    fn unknown_codes_are_rejected() {
+        for code in ["bio-suisse", "organic", "demeter ip_suisse"] {
+            assert_err!(Certification::parse(code.to_string()));
+        }
+    }
+
+    #[test]
+    fn empty_code_is_rejected() {
+        assert_err!(Certification::parse("   ".to_string()));
+    }
+
+    #[test]
+    fn serializes_to_the_code() {
+        assert_eq!(
+            serde_json::to_value(Certification::IpSuisse).unwrap(),
+            json!("ip_suisse")
+        );
+    }
+
+    #[test]
+    fn deserialize_rejects_an_unknown_code() {
+        assert_err!(serde_json::from_value::<Certification>(json!("knospe")));
+    }
+}
@@ -0,0 +1,186 @@
+use crate::domain::farm::Point;
+use std::collections::BTreeMap;
+
+/// Anything with a position on the map, so geo helpers work on farms as well
+/// as bare points.
+pub trait Located {
+    fn location(&self) -> Point;
+}
+
+impl Located for Point {
+    fn location(&self) -> Point {
+        *self
+    }
+}
+
+/// The candidate closest to `target` by `Point::distance_to`, or `None` for
+/// an empty slice. Ties go to the candidate that comes first.
+///
+/// A linear scan, meant for small in-memory result sets; anything larger
+/// belongs in SQL.
+pub fn nearest<'a, T: Located>(target: &Point, candidates: &'a [T]) -> Option<&'a T> {
+    candidates
+        .iter()
+        .map(|candidate| (candidate, target.distance_to(&candidate.location())))
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(candidate, _)| candidate)
+}
+
+/// Buckets `points` into a latitude/longitude grid with cells `grid_deg`
+/// degrees wide and returns each occupied cell's centroid with its point count.
+///
+/// Clusters are ordered by cell (south to north, then west to east). A
+/// non-positive or non-finite `grid_deg` disables clustering: every point is
+/// returned as its own cluster of one.
+pub fn cluster_points(points: &[Point], grid_deg: f64) -> Vec<(Point, usize)> {
+    if !grid_deg.is_finite() || grid_deg <= 0.0 {
+        return points.iter().map(|point| (*point, 1)).collect();
+    }
+
+    // (latitude sum, longitude sum, count) per grid cell
+    let mut cells: BTreeMap<(i64, i64), (f64, f64, usize)> = BTreeMap::new();
+    for point in points {
+        let cell = (
+            (point.latitude / grid_deg).floor() as i64,
+            (point.longitude / grid_deg).floor() as i64,
+        );
+        let entry = cells.entry(cell).or_insert((0.0, 0.0, 0));
+        entry.0 += point.latitude;
+        entry.1 += point.longitude;
+        entry.2 += 1;
+    }
+
+    cells
+        .into_values()
+        .map(|(latitude_sum, longitude_sum, count)| {
+            let centroid = Point::new(latitude_sum / count as f64, longitude_sum / count as f64);
+            (centroid, count)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Located, cluster_points, nearest};
+    use crate::domain::farm::Point;
+
+    struct Farm {
+        name: &'static str,
+        coordinates: Point,
+    }
+
+    impl Located for Farm {
+        fn location(&self) -> Point {
+            self.coordinates
+        }
+    }
+
+    fn farm(name: &'static str, latitude: f64, longitude: f64) -> Farm {
+        Farm {
+            name,
+            coordinates: Point::new(latitude, longitude),
+        }
+    }
+
+    #[test]
+    fn no_points_yield_no_clusters() {
+        assert!(cluster_points(&[], 0.1).is_empty());
+    }
+
+    #[test]
+    fn closely_spaced_points_collapse_into_one_cluster() {
+        let points = [
+            Point::new(47.3769, 8.5417),
+            Point::new(47.3771, 8.5419),
+            Point::new(47.3773, 8.5421),
+        ];
+
+        let clusters = cluster_points(&points, 0.1);
+
+        assert_eq!(clusters.len(), 1);
+        let (centroid, count) = clusters[0];
+        assert_eq!(count, 3);
+        assert!((centroid.latitude - 47.3771).abs() < 1e-9);
+        assert!((centroid.longitude - 8.5419).abs() < 1e-9);
+    }
+
+    #[test]
+    fn distant_points_stay_in_separate_clusters() {
+        let zurich = Point::new(47.3769, 8.5417);
+        let geneva = Point::new(46.2044, 6.1432);
+
+        let clusters = cluster_points(&[zurich, geneva, zurich], 0.1);
+
+        assert_eq!(clusters.len(), 2);
+        assert_eq!(clusters[0], (geneva, 1));
+        assert_eq!(clusters[1].1, 2);
+        assert!((clusters[1].0.latitude - zurich.latitude).abs() < 1e-9);
+    }
+
+    #[test]
+    fn counts_add_up_to_the_number_of_points() {
+        let points: Vec<Point> = (0..50)
+            .map(|i| Point::new(46.0 + i as f64 * 0.03, 6.0 + i as f64 * 0.07))
+            .collect();
+
+        let clusters = cluster_points(&points, 0.25);
+
+        assert_eq!(
+            clusters.iter().map(|(_, count)| count).sum::<usize>(),
+            points.len()
+        );
+    }
+
+    #[test]
+    fn non_positive_grid_size_disables_clustering() {
+        let points = [Point::new(47.3769, 8.5417), Point::new(47.3769, 8.5417)];
+
+        assert_eq!(cluster_points(&points, 0.0).len(), 2);
+        assert_eq!(cluster_points(&points, f64::NAN).len(), 2);
+    }
+
+    #[test]
+    fn nearest_of_no_candidates_is_none() {
+        assert!(nearest::<Farm>(&Point::new(47.3769, 8.5417), &[]).is_none());
+    }
+
+    #[test]
+    fn nearest_picks_the_closest_farm() {
+        let farms = [
+            farm("Geneva", 46.2044, 6.1432),
+            farm("Bern", 46.9481, 7.4474),
+            farm("Oerlikon", 47.4111, 8.5441),
+            farm("Lugano", 46.0037, 8.9511),
+            farm("Basel", 47.5596, 7.5886),
+        ];
+        let zurich = Point::new(47.3769, 8.5417);
+
+        assert_eq!(nearest(&zurich, &farms).unwrap().name, "Oerlikon");
+    }
+
+    #[test]
+    fn nearest_resolves_ties_to_the_first_candidate() {
+        let zurich = Point::new(47.3769, 8.5417);
+        // Equally far north and south of Zurich.
+        let farms = [
+            farm("North", 47.4769, 8.5417),
+            farm("South", 47.2769, 8.5417),
+            farm("Far", 46.2044, 6.1432),
+        ];
+        let reversed = [
+            farm("South", 47.2769, 8.5417),
+            farm("North", 47.4769, 8.5417),
+        ];
+
+        assert_eq!(nearest(&zurich, &farms).unwrap().name, "North");
+        assert_eq!(nearest(&zurich, &reversed).unwrap().name, "South");
+    }
+
+    #[test]
+    fn nearest_works_on_bare_points() {
+        let bern = Point::new(46.9481, 7.4474);
+        let points = [Point::new(46.2044, 6.1432), bern];
+
+        assert_eq!(nearest(&Point::new(46.95, 7.45), &points), Some(&bern));
+    }
+}
@@ -1,16 +1,24 @@
 mod address;
+mod boundary;
 mod canton;
 mod categories;
+mod certification;
+mod geo;
 mod name;
 mod point;
+mod postal_code;
 mod product_slug;
 mod stock_status;
 
 // Public re-exports
 pub use address::Address;
-pub use canton::Canton;
-pub use categories::Categories;
-pub use name::Name;
-pub use point::{Point, PointError};
+pub use boundary::SwissBoundary;
+pub use canton::{Canton, CantonNames, LocalizedCanton};
+pub use categories::{Categories, CategoriesError, CategoriesParseOptions};
+pub use certification::{Certification, CertificationError};
+pub use geo::{Located, cluster_points, nearest};
+pub use name::{Name, NameError};
+pub use point::{Point, PointError, PointObj};
+pub use postal_code::{PostalCode, PostalCodeError};
 pub use product_slug::{ProductSlug, ProductSlugError};
 pub use stock_status::StockStatus;
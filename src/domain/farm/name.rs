@@ -3,8 +3,8 @@
 //! Provides a validated `Name` type that ensures farm names are non-empty,
 //! properly trimmed, and within reasonable length constraints.
 
-use crate::impl_sqlx_for_string_domain_type;
-use std::fmt::Display;
+use crate::domain::domain_string::{DomainString, DomainStringViolation};
+use crate::impl_domain_string;
 use thiserror::Error;
 use unicode_segmentation::UnicodeSegmentation;
 
@@ -21,6 +21,29 @@ pub enum NameError {
 
     #[error("Farm name contains forbidden characters: {0}.")]
     ForbiddenCharacters(String),
+
+    #[error("Farm name contains a blocked term: '{0}'.")]
+    Blocked(String),
+}
+
+impl From<DomainStringViolation> for NameError {
+    fn from(violation: DomainStringViolation) -> Self {
+        match violation {
+            // A non-blank name is at least one grapheme long.
+            DomainStringViolation::Empty | DomainStringViolation::TooShort(_) => Self::EmptyName,
+            DomainStringViolation::TooLong(length) => Self::TooLong(length),
+            DomainStringViolation::ForbiddenCharacter(c) => {
+                Self::ForbiddenCharacters(format!("'{}'", c))
+            }
+        }
+    }
+}
+
+impl DomainString for Name {
+    type Error = NameError;
+    const MAX_LENGTH: usize = 256;
+    const FORBIDDEN_CHARACTERS: &'static [char] = &['/', '(', ')', '"', '<', '>', '\\', '{', '}'];
+    const LENGTH_INCLUDES_SURROUNDING_WHITESPACE: bool = true;
 }
 
 impl Name {
@@ -31,74 +54,54 @@ impl Name {
     /// No forbidden characters
     /// Automatically trim whitespace
     pub fn parse(s: String) -> Result<Name, NameError> {
-        let is_empty_or_whitespace = s.trim().is_empty();
-
-        if is_empty_or_whitespace {
-            return Err(NameError::EmptyName);
-        }
-
-        let char_count = s.graphemes(true).count();
-        let is_too_long = char_count > 256;
-
-        if is_too_long {
-            return Err(NameError::TooLong(char_count));
-        }
+        Self::validate(&s).map(|trimmed| Self(trimmed.to_string()))
+    }
 
-        let forbidden_characters = ['/', '(', ')', '"', '<', '>', '\\', '{', '}'];
-        if let Some(forbidden) = s.chars().find(|g| forbidden_characters.contains(g)) {
-            return Err(NameError::ForbiddenCharacters(format!("'{}'", forbidden)));
+    /// Same as `parse`, additionally rejecting names that contain any of the
+    /// `blocklist` entries (case-insensitive substring match). Blank entries
+    /// are ignored.
+    pub fn parse_with_blocklist(s: String, blocklist: &[String]) -> Result<Name, NameError> {
+        let name = Self::parse(s)?;
+
+        let lowercase = name.0.to_lowercase();
+        if let Some(blocked) = blocklist
+            .iter()
+            .map(|term| term.trim())
+            .find(|term| !term.is_empty() && lowercase.contains(&term.to_lowercase()))
+        {
+            return Err(NameError::Blocked(blocked.to_string()));
         }
 
-        Ok(Self(s.trim().to_string()))
+        Ok(name)
     }
 
-    /// Returns the address as a string slice. Useful for logging and display.
-    pub fn as_str(&self) -> &str {
-        &self.0
+    /// Length in grapheme clusters, the unit the 256 limit is checked in, so
+    /// a character counter shows the same number the server enforces.
+    pub fn grapheme_len(&self) -> usize {
+        self.0.graphemes(true).count()
     }
 }
 
-impl AsRef<str> for Name {
-    fn as_ref(&self) -> &str {
-        &self.0
-    }
-}
+impl_domain_string!(Name);
 
-impl Display for Name {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        self.0.fmt(f)
-    }
-}
+#[cfg(test)]
+mod tests {
+    use super::{Name, NameError};
+    use claims::{assert_err, assert_matches, assert_ok};
+    use serde_json::json;
 
-// Serialize for JSON API responses
-impl serde::Serialize for Name {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: serde::Serializer,
-    {
-        serializer.serialize_str(&self.0)
+    #[test]
+    fn deserializes_and_trims_a_valid_name() {
+        let name: Name = serde_json::from_value(json!("  Hof Binzenhof ")).unwrap();
+        assert_eq!(name.as_str(), "Hof Binzenhof");
     }
-}
 
-// Deserialize from JSON API requests
-impl<'de> serde::Deserialize<'de> for Name {
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-    where
-        D: serde::Deserializer<'de>,
-    {
-        let s = String::deserialize(deserializer)?;
-        // When deserializing from API responses, trust the data is valid
-        // since it came from our own database
-        Ok(Name(s))
+    #[test]
+    fn deserialize_rejects_an_invalid_name() {
+        assert_err!(serde_json::from_value::<Name>(json!("   ")));
+        assert_err!(serde_json::from_value::<Name>(json!("Hof <script>")));
+        assert_err!(serde_json::from_value::<Name>(json!("k".repeat(257))));
     }
-}
-
-impl_sqlx_for_string_domain_type!(Name);
-
-#[cfg(test)]
-mod tests {
-    use super::Name;
-    use claims::{assert_err, assert_ok};
 
     #[test]
     fn farm_name_256_characters_long_are_valid() {
@@ -142,4 +145,86 @@ mod tests {
         let farm_name = "Hofträumli - Hofladen".to_string();
         assert_ok!(Name::parse(farm_name));
     }
+
+    #[test]
+    fn blocklisted_term_is_rejected_case_insensitively() {
+        let blocklist = vec!["test".to_string()];
+
+        assert_matches!(
+            Name::parse_with_blocklist("Test Hof".to_string(), &blocklist),
+            Err(NameError::Blocked(term)) if term == "test"
+        );
+        assert_err!(Name::parse_with_blocklist(
+            "Hof CONTEST".to_string(),
+            &blocklist
+        ));
+    }
+
+    #[test]
+    fn names_without_a_blocklisted_term_are_allowed() {
+        let blocklist = vec!["test".to_string()];
+        assert_ok!(Name::parse_with_blocklist(
+            "Bergmilch".to_string(),
+            &blocklist
+        ));
+    }
+
+    #[test]
+    fn blank_blocklist_entries_are_ignored() {
+        let blocklist = vec!["".to_string(), "  ".to_string()];
+        assert_ok!(Name::parse_with_blocklist(
+            "Bergmilch".to_string(),
+            &blocklist
+        ));
+    }
+
+    #[test]
+    fn parse_with_blocklist_still_applies_the_base_rules() {
+        assert_matches!(
+            Name::parse_with_blocklist("Hof (alt)".to_string(), &[]),
+            Err(NameError::ForbiddenCharacters(_))
+        );
+    }
+
+    #[test]
+    fn grapheme_len_counts_accented_letters_once() {
+        let name = Name::parse("Hofträumli".to_string()).unwrap();
+
+        assert_eq!(name.as_str().len(), 11);
+        assert_eq!(name.grapheme_len(), 10);
+    }
+
+    #[test]
+    fn grapheme_len_counts_combining_sequences_once() {
+        // "a" followed by a combining diaeresis: two chars, one grapheme.
+        let name = Name::parse("Hofa\u{0308}pfel".to_string()).unwrap();
+
+        assert_eq!(name.as_str().chars().count(), 9);
+        assert_eq!(name.grapheme_len(), 8);
+    }
+
+    #[test]
+    fn a_name_at_the_limit_has_a_grapheme_len_of_256() {
+        let name = Name::parse("ä".repeat(256)).unwrap();
+
+        assert_eq!(name.grapheme_len(), 256);
+        assert_eq!(name.as_str().len(), 512);
+    }
+
+    #[test]
+    fn each_rule_reports_its_own_error() {
+        assert_matches!(Name::parse("  ".to_string()), Err(NameError::EmptyName));
+        assert_matches!(Name::parse("k".repeat(257)), Err(NameError::TooLong(257)));
+        assert_matches!(
+            Name::parse("Hof {Nord}".to_string()),
+            Err(NameError::ForbiddenCharacters(c)) if c == "'{'"
+        );
+    }
+
+    #[test]
+    fn the_length_limit_counts_surrounding_whitespace() {
+        let padded = format!(" {} ", "k".repeat(255));
+
+        assert_matches!(Name::parse(padded), Err(NameError::TooLong(257)));
+    }
 }
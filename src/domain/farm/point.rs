@@ -1,7 +1,8 @@
+use crate::domain::farm::SwissBoundary;
 use sqlx::encode::IsNull;
 use sqlx::postgres::types::PgPoint;
-use sqlx::postgres::{PgArgumentBuffer, PgTypeInfo, PgValueFormat, PgValueRef};
-use sqlx::{Decode, Encode, Postgres, Type};
+use sqlx::postgres::{PgArgumentBuffer, PgTypeInfo, PgValueRef};
+use sqlx::{Decode, Encode, Postgres, Type, ValueRef};
 use thiserror::Error;
 
 /// Represents a PostgreSQL POINT (longitude, latitude) datatype
@@ -46,7 +47,7 @@ impl Point {
     }
 
     /// Check if coordinates are within Switzerland boundaries.
-    fn is_within_switzerland(lat: f64, lon: f64) -> bool {
+    pub(super) fn is_within_switzerland(lat: f64, lon: f64) -> bool {
         (Self::MIN_LATITUDE..=Self::MAX_LATITUDE).contains(&lat)
             && (Self::MIN_LONGITUDE..=Self::MAX_LONGITUDE).contains(&lon)
     }
@@ -62,16 +63,105 @@ impl Point {
             return Err(PointError::InvalidFormat);
         }
 
-        let lat = parts[0]
+        Self::parse_components(parts[0], parts[1])
+    }
+
+    /// Like `parse`, additionally checking the point against `boundary`.
+    pub fn parse_within(s: &str, boundary: SwissBoundary) -> Result<Self, PointError> {
+        let point = Self::parse(s)?;
+        if !boundary.contains(&point) {
+            return Err(PointError::NotInSwitzerland);
+        }
+        Ok(point)
+    }
+
+    /// Like `parse`, but also accepts a semicolon or whitespace between the
+    /// latitude and longitude, as some data sources write them.
+    ///
+    /// Accepted formats: "47.37,8.54", "47.37;8.54", "47.37 8.54"
+    pub fn parse_flexible(s: &str) -> Result<Self, PointError> {
+        let s = s.trim();
+        let parts: Vec<&str> = if s.contains([',', ';']) {
+            s.split([',', ';']).collect()
+        } else {
+            s.split_whitespace().collect()
+        };
+
+        match parts.as_slice() {
+            [lat, lon] => Self::parse_components(lat, lon),
+            _ => Err(PointError::InvalidFormat),
+        }
+    }
+
+    /// Parse degrees, minutes and seconds with hemisphere letters, e.g.
+    /// `47°22'37"N 8°32'30"E`, then apply the checks `parse` does.
+    ///
+    /// The two coordinates may come in either order, separated by whitespace
+    /// or a comma. Minutes and seconds are optional, may be decimal and must
+    /// be below 60; `′` and `″` are accepted for `'` and `"`.
+    pub fn parse_dms(s: &str) -> Result<Self, PointError> {
+        let mut latitude = None;
+        let mut longitude = None;
+        let mut rest = s.trim();
+        while !rest.is_empty() {
+            let end = rest
+                .find(['N', 'S', 'E', 'W'])
+                .ok_or(PointError::InvalidFormat)?;
+            let hemisphere = &rest[end..end + 1];
+            let degrees = parse_dms_degrees(&rest[..end])?;
+            let slot = match hemisphere {
+                "N" | "S" => &mut latitude,
+                _ => &mut longitude,
+            };
+            if slot.is_some() {
+                return Err(PointError::InvalidFormat);
+            }
+            *slot = Some(match hemisphere {
+                "S" | "W" => -degrees,
+                _ => degrees,
+            });
+            rest = rest[end + 1..].trim_start_matches([',', ' ', '\t']);
+        }
+
+        match (latitude, longitude) {
+            (Some(latitude), Some(longitude)) => Self::try_new(latitude, longitude),
+            _ => Err(PointError::InvalidFormat),
+        }
+    }
+
+    /// Validate a latitude and longitude given as separate strings.
+    fn parse_components(lat: &str, lon: &str) -> Result<Self, PointError> {
+        let lat = lat
             .trim()
             .parse::<f64>()
             .map_err(|_| PointError::InvalidFormat)?;
 
-        let lon = parts[1]
+        let lon = lon
             .trim()
             .parse::<f64>()
             .map_err(|_| PointError::InvalidFormat)?;
 
+        Self::try_new(lat, lon)
+    }
+
+    /// `parse` over every input, so an importer can report all bad rows at
+    /// once: the points that parsed, in input order, and each failure with
+    /// its index in `inputs`.
+    pub fn parse_many(inputs: &[&str]) -> (Vec<Point>, Vec<(usize, PointError)>) {
+        let mut points = Vec::with_capacity(inputs.len());
+        let mut failures = Vec::new();
+        for (index, input) in inputs.iter().enumerate() {
+            match Self::parse(input) {
+                Ok(point) => points.push(point),
+                Err(e) => failures.push((index, e)),
+            }
+        }
+        (points, failures)
+    }
+
+    /// Like `new`, but with the checks `parse` applies: valid ranges and
+    /// within Switzerland.
+    pub fn try_new(lat: f64, lon: f64) -> Result<Self, PointError> {
         // Validate basic coordinate ranges
         if !(-90.0..=90.0).contains(&lat) {
             return Err(PointError::InvalidLatitude);
@@ -94,6 +184,20 @@ impl Point {
         format!("{},{}", self.latitude, self.longitude)
     }
 
+    /// "latitude,longitude" with exactly `decimals` places on both, e.g.
+    /// `"47.376900,8.541700"` at 6, so values that differ only in float noise
+    /// (47.3769 and 47.376900000001) format alike. Places beyond 15 add
+    /// nothing an `f64` can hold and are treated as 15. A coordinate that
+    /// rounds to zero never prints as `-0`.
+    pub fn to_string_fixed(&self, decimals: u8) -> String {
+        let decimals = usize::from(decimals.min(15));
+        format!(
+            "{},{}",
+            format_fixed(self.latitude, decimals),
+            format_fixed(self.longitude, decimals)
+        )
+    }
+
     /// Returns the latitude coordinate.
     pub fn latitude(&self) -> f64 {
         self.latitude
@@ -115,6 +219,115 @@ impl Point {
     pub fn as_str(&self) -> String {
         self.to_string_format()
     }
+
+    /// Rounds both coordinates to `precision_digits` decimal places, e.g. 2
+    /// digits is roughly 1 km. Used to publish a farm's location without
+    /// disclosing its exact position.
+    ///
+    /// A point within Switzerland stays within Switzerland: coarse roundings
+    /// that would cross the border are clamped back onto it.
+    pub fn snap_to_grid(&self, precision_digits: u8) -> Point {
+        // Beyond this f64 cannot represent the extra digits anyway.
+        if precision_digits >= 15 {
+            return *self;
+        }
+
+        let factor = 10f64.powi(i32::from(precision_digits));
+        let mut latitude = (self.latitude * factor).round() / factor;
+        let mut longitude = (self.longitude * factor).round() / factor;
+
+        if Self::is_within_switzerland(self.latitude, self.longitude) {
+            latitude = latitude.clamp(Self::MIN_LATITUDE, Self::MAX_LATITUDE);
+            longitude = longitude.clamp(Self::MIN_LONGITUDE, Self::MAX_LONGITUDE);
+        }
+
+        Point::new(latitude, longitude)
+    }
+
+    /// Great-circle distance to `other` in km, the same formula `GET /farms`
+    /// uses for `distance_km`.
+    pub fn distance_to(&self, other: &Point) -> f64 {
+        let cosine = self.latitude.to_radians().sin() * other.latitude.to_radians().sin()
+            + self.latitude.to_radians().cos()
+                * other.latitude.to_radians().cos()
+                * (other.longitude - self.longitude).to_radians().cos();
+        6371.0 * cosine.clamp(-1.0, 1.0).acos()
+    }
+
+    /// Whether `other` lies within `epsilon` degrees of this point on both
+    /// axes (inclusive), e.g. `1e-9` to absorb float noise from arithmetic or
+    /// a text round-trip. The derived `PartialEq` stays exact. NaN is never
+    /// approximately equal to anything.
+    pub fn approx_eq(&self, other: &Point, epsilon: f64) -> bool {
+        (self.latitude - other.latitude).abs() <= epsilon
+            && (self.longitude - other.longitude).abs() <= epsilon
+    }
+
+    /// A hashable `(latitude, longitude)` key with both coordinates rounded to
+    /// `decimals` places and scaled to integers, e.g. 4 places is roughly
+    /// 10 m. `Point` itself is only `PartialEq` (floats), so dedup points by
+    /// collecting these keys into a `HashSet` instead.
+    ///
+    /// Rounding, not distance, decides: two points a hair apart on either side
+    /// of a rounding boundary (47.00049 and 47.00051 at 3 places) get
+    /// different keys. Halfway values follow their binary representation:
+    /// 8.075 times 100 is 807.4999…, so at 2 places it keys as 807, not 808.
+    /// Places beyond 15 add nothing an `f64` can hold and are treated as 15.
+    /// NaN maps to 0.
+    pub fn to_fixed_key(&self, decimals: u8) -> (i64, i64) {
+        let factor = 10f64.powi(i32::from(decimals.min(15)));
+        (
+            (self.latitude * factor).round() as i64,
+            (self.longitude * factor).round() as i64,
+        )
+    }
+}
+
+/// `value` with `decimals` places, without the sign of a negative value that
+/// rounds to zero.
+fn format_fixed(value: f64, decimals: usize) -> String {
+    let formatted = format!("{value:.decimals$}");
+    match formatted.strip_prefix('-') {
+        Some(unsigned) if unsigned.bytes().all(|b| b == b'0' || b == b'.') => unsigned.to_string(),
+        _ => formatted,
+    }
+}
+
+/// `47°22'37"` (the part of a DMS coordinate before its hemisphere letter)
+/// in decimal degrees.
+fn parse_dms_degrees(s: &str) -> Result<f64, PointError> {
+    const UNITS: [(&[char], f64); 3] = [
+        (&['°', 'º'], 1.0),
+        (&['\'', '′'], 60.0),
+        (&['"', '″'], 3600.0),
+    ];
+
+    let mut rest = s.trim();
+    if rest.is_empty() {
+        return Err(PointError::InvalidFormat);
+    }
+    let mut degrees = 0.0;
+    for (index, (marks, per_degree)) in UNITS.into_iter().enumerate() {
+        if rest.is_empty() {
+            break;
+        }
+        let end = rest.find(marks).ok_or(PointError::InvalidFormat)?;
+        let value = rest[..end]
+            .trim()
+            .parse::<f64>()
+            .map_err(|_| PointError::InvalidFormat)?;
+        let limit = if index == 0 { f64::INFINITY } else { 60.0 };
+        if !(0.0..limit).contains(&value) {
+            return Err(PointError::InvalidFormat);
+        }
+        degrees += value / per_degree;
+        let mark = rest[end..].chars().next().map_or(1, char::len_utf8);
+        rest = rest[end + mark..].trim_start();
+    }
+    if !rest.is_empty() {
+        return Err(PointError::InvalidFormat);
+    }
+    Ok(degrees)
 }
 
 // Display trait for easy printing
@@ -130,8 +343,9 @@ impl Type<Postgres> for Point {
         <PgPoint as Type<Postgres>>::type_info()
     }
 
+    // Text columns are accepted too, for legacy rows (see `Decode`).
     fn compatible(ty: &PgTypeInfo) -> bool {
-        <PgPoint as Type<Postgres>>::compatible(ty)
+        <PgPoint as Type<Postgres>>::compatible(ty) || <&str as Type<Postgres>>::compatible(ty)
     }
 }
 
@@ -167,50 +381,46 @@ impl Encode<'_, Postgres> for Point {
 
 impl<'r> Decode<'r, Postgres> for Point {
     fn decode(value: PgValueRef<'r>) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
-        match value.format() {
-            PgValueFormat::Binary => {
-                // If Binary, delegate to PgPoint's SQLx implementation
-                let pg_point = <PgPoint as Decode<Postgres>>::decode(value)?;
-                Ok(Point {
-                    longitude: pg_point.x,
-                    latitude: pg_point.y,
-                })
+        // Native `point` values, binary or text. Only attempted for a `point`
+        // column: PgPoint would misread (or panic on) the bytes of anything else.
+        if <PgPoint as Type<Postgres>>::compatible(&value.type_info()) {
+            return Ok(<PgPoint as Decode<Postgres>>::decode(value)?.into());
+        }
+
+        // Legacy rows still stored as text, read while a column is migrated.
+        let text = <&str as Decode<Postgres>>::decode(value)?;
+        Ok(Self::parse_legacy_text(text)?)
+    }
+}
+
+impl Point {
+    /// Parses coordinates stored as text: the Postgres POINT form
+    /// `"(longitude,latitude)"` or the API form `"latitude,longitude"`.
+    ///
+    /// Unlike `parse`, stored data is not checked against the Swiss bounds.
+    fn parse_legacy_text(text: &str) -> Result<Self, PointError> {
+        let text = text.trim();
+        let parse_pair = |pair: &str| -> Result<(f64, f64), PointError> {
+            let (first, second) = pair.split_once(',').ok_or(PointError::InvalidFormat)?;
+            let first = first
+                .trim()
+                .parse()
+                .map_err(|_| PointError::InvalidFormat)?;
+            let second = second
+                .trim()
+                .parse()
+                .map_err(|_| PointError::InvalidFormat)?;
+            Ok((first, second))
+        };
+
+        match text.strip_prefix('(').and_then(|s| s.strip_suffix(')')) {
+            Some(inner) => {
+                let (longitude, latitude) = parse_pair(inner)?;
+                Ok(Self::new(latitude, longitude))
             }
-            PgValueFormat::Text => {
-                // If Text, parse "(longitude,latitude)" format
-                let text = value.as_str()?;
-
-                // Remove parentheses
-                let inner = text
-                    .strip_prefix('(')
-                    .and_then(|s| s.strip_suffix(')'))
-                    .ok_or_else(|| {
-                        format!(
-                            "Invalid POINT text format: expected '(x,y)', got '{}'",
-                            text
-                        )
-                    })?;
-
-                // Split
-                let (x_str, y_str) = inner.split_once(',').ok_or_else(|| {
-                    format!("Invalid POINT text format: missing comma in '{}'", text)
-                })?;
-
-                // Parse coordinates
-                let longitude = x_str
-                    .trim()
-                    .parse::<f64>()
-                    .map_err(|e| format!("Failed to parse longitude '{}': {}", x_str.trim(), e))?;
-
-                let latitude = y_str
-                    .trim()
-                    .parse::<f64>()
-                    .map_err(|e| format!("Failed to parse latitude '{}': {}", y_str.trim(), e))?;
-
-                Ok(Point {
-                    longitude,
-                    latitude,
-                })
+            None => {
+                let (latitude, longitude) = parse_pair(text)?;
+                Ok(Self::new(latitude, longitude))
             }
         }
     }
@@ -226,21 +436,86 @@ impl serde::Serialize for Point {
     }
 }
 
-// Deserialize from JSON API requests
+/// The shapes a `Point` is accepted in from JSON: `"47.3769,8.5417"`,
+/// `{"lat": 47.3769, "lon": 8.5417}` or `[47.3769, 8.5417]`, latitude first.
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum PointInput {
+    Text(String),
+    Object(LatLon),
+    Pair([f64; 2]),
+}
+
+// Deserialize from JSON API requests, in any `PointInput` shape; each is
+// checked like `Point::parse`.
 impl<'de> serde::Deserialize<'de> for Point {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: serde::Deserializer<'de>,
     {
-        let s = String::deserialize(deserializer)?;
-        Point::parse(&s).map_err(serde::de::Error::custom)
+        use serde::de::Error;
+
+        let point = match PointInput::deserialize(deserializer)
+            .map_err(|_| D::Error::custom(PointError::InvalidFormat))?
+        {
+            PointInput::Text(s) => Point::parse(&s),
+            PointInput::Object(LatLon { lat, lon }) | PointInput::Pair([lat, lon]) => {
+                Point::try_new(lat, lon)
+            }
+        };
+        point.map_err(D::Error::custom)
+    }
+}
+
+/// A `Point` that serializes as `{"lat": 47.3769, "lon": 8.5417}` rather than
+/// the default `"47.3769,8.5417"` string, for clients that would rather not
+/// parse one. Deserializing validates like `Point::parse`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PointObj(pub Point);
+
+impl From<Point> for PointObj {
+    fn from(point: Point) -> Self {
+        Self(point)
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LatLon {
+    lat: f64,
+    lon: f64,
+}
+
+impl serde::Serialize for PointObj {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        LatLon {
+            lat: self.0.latitude,
+            lon: self.0.longitude,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for PointObj {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let LatLon { lat, lon } = LatLon::deserialize(deserializer)?;
+        Point::try_new(lat, lon)
+            .map(Self)
+            .map_err(serde::de::Error::custom)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::Point;
-    use claims::{assert_err, assert_ok};
+    use super::{Point, PointError, PointObj};
+    use crate::domain::farm::SwissBoundary;
+    use claims::{assert_err, assert_matches, assert_ok};
     use fake::Fake;
 
     fn random_swiss_coordinates() -> (f64, f64) {
@@ -411,6 +686,84 @@ mod tests {
         assert_err!(result);
     }
 
+    #[test]
+    fn parse_within_rejects_a_point_in_the_box_only_in_polygon_mode() {
+        // Friedrichshafen, across Lake Constance in Germany.
+        let coordinates = "47.65,9.48";
+
+        assert_ok!(Point::parse_within(coordinates, SwissBoundary::BoundingBox));
+        assert_err!(Point::parse_within(coordinates, SwissBoundary::Polygon));
+    }
+
+    #[test]
+    fn parse_dms_converts_to_decimal_degrees() {
+        // Zürich Hauptbahnhof.
+        let point = assert_ok!(Point::parse_dms("47°22'37\"N 8°32'30\"E"));
+
+        assert!(
+            point.approx_eq(&Point::new(47.376944, 8.541667), 1e-6),
+            "{point:?}"
+        );
+    }
+
+    #[test]
+    fn parse_dms_accepts_either_order_commas_and_typographic_marks() {
+        let expected = Point::new(47.376944, 8.541667);
+
+        for dms in [
+            "8°32'30\"E, 47°22'37\"N",
+            "47° 22′ 37″ N, 8° 32′ 30″ E",
+            "47°22.6167'N 8°32.5'E",
+        ] {
+            let point = assert_ok!(Point::parse_dms(dms), "{dms}");
+            assert!(point.approx_eq(&expected, 1e-4), "{dms}: {point:?}");
+        }
+    }
+
+    #[test]
+    fn parse_dms_applies_the_switzerland_check() {
+        assert_matches!(
+            Point::parse_dms("47°22'37\"S 8°32'30\"E"),
+            Err(PointError::NotInSwitzerland)
+        );
+        assert_matches!(
+            Point::parse_dms("48°8'N 11°34'E"),
+            Err(PointError::NotInSwitzerland)
+        );
+    }
+
+    #[test]
+    fn parse_dms_rejects_malformed_input() {
+        for dms in [
+            "",
+            "47°22'37\" 8°32'30\"",
+            "47°22'37\"N",
+            "47°22'37\"N 46°N",
+            "47°22'37\"N 8°32'30\"E 9°E",
+            "47°61'N 8°32'E",
+            "47°22'60\"N 8°32'E",
+            "47'N 8°E",
+            "47°22'37\"x N 8°32'30\"E",
+            "-47°N 8°E",
+            "N 8°E",
+            "47.3769,8.5417",
+        ] {
+            assert_matches!(
+                Point::parse_dms(dms),
+                Err(PointError::InvalidFormat),
+                "{dms}"
+            );
+        }
+    }
+
+    #[test]
+    fn parse_within_accepts_a_swiss_point_in_polygon_mode() {
+        assert_ok!(Point::parse_within(
+            "47.3769,8.5417",
+            SwissBoundary::Polygon
+        ));
+    }
+
     #[test]
     fn format_point_to_string() {
         let (lat, lon) = random_swiss_coordinates();
@@ -535,10 +888,11 @@ mod tests {
 
     #[test]
     fn all_canton_capitals_are_within_switzerland() {
-        use crate::domain::test_data::CANTON_CAPITALS;
+        use crate::domain::farm::canton::CANTON_CAPITALS;
 
-        for (city, coords) in CANTON_CAPITALS {
-            let result = Point::parse(coords);
+        for (_, city, latitude, longitude) in CANTON_CAPITALS {
+            let coords = format!("{latitude},{longitude}");
+            let result = Point::parse(&coords);
             assert_ok!(
                 &result,
                 "Canton capital {} with coordinates {} should be valid",
@@ -643,6 +997,37 @@ mod tests {
         assert_eq!(point.longitude, lon);
     }
 
+    #[test]
+    fn deserialize_accepts_string_object_and_array_forms() {
+        let from_string: Point = serde_json::from_str(r#""47.3769,8.5417""#).unwrap();
+        let from_object: Point =
+            serde_json::from_str(r#"{"lat": 47.3769, "lon": 8.5417}"#).unwrap();
+        let from_array: Point = serde_json::from_str("[47.3769, 8.5417]").unwrap();
+
+        assert_eq!(from_string, Point::new(47.3769, 8.5417));
+        assert_eq!(from_object, from_string);
+        assert_eq!(from_array, from_string);
+    }
+
+    #[test]
+    fn deserialize_checks_switzerland_bounds_in_every_form() {
+        for json in [
+            r#""48.8566,2.3522""#,
+            r#"{"lat": 48.8566, "lon": 2.3522}"#,
+            "[48.8566, 2.3522]",
+        ] {
+            let result: Result<Point, _> = serde_json::from_str(json);
+            assert!(result.is_err(), "{json}");
+        }
+        for json in ["[47.3769]", "[47.3769, 8.5417, 400.0]", "true"] {
+            let error = serde_json::from_str::<Point>(json).unwrap_err();
+            assert!(
+                error.to_string().starts_with("Invalid coordinate format"),
+                "{json}"
+            );
+        }
+    }
+
     #[test]
     fn deserialize_invalid_json_format_fails() {
         let json = r#""not-a-coordinate""#;
@@ -746,4 +1131,311 @@ mod tests {
         let converted: Point = pg_point.into();
         assert_eq!(original, converted);
     }
+
+    #[test]
+    fn snap_to_grid_rounds_to_the_given_decimal_places() {
+        let point = Point::new(47.376_912, 8.541_694);
+
+        assert_eq!(point.snap_to_grid(2), Point::new(47.38, 8.54));
+        assert_eq!(point.snap_to_grid(3), Point::new(47.377, 8.542));
+        assert_eq!(point.snap_to_grid(0), Point::new(47.0, 9.0));
+    }
+
+    #[test]
+    fn snap_to_grid_with_high_precision_keeps_the_point() {
+        let point = Point::new(47.376_912_345, 8.541_694_321);
+
+        assert_eq!(point.snap_to_grid(15), point);
+        assert_eq!(point.snap_to_grid(u8::MAX), point);
+    }
+
+    #[test]
+    fn points_equal_to_the_chosen_precision_share_a_fixed_key() {
+        let a = Point::new(47.376_912, 8.541_694);
+        let b = Point::new(47.376_94, 8.541_71);
+
+        assert_eq!(a.to_fixed_key(4), b.to_fixed_key(4));
+        assert_eq!(a.to_fixed_key(4), (473_769, 85_417));
+    }
+
+    #[test]
+    fn points_differing_at_the_chosen_precision_get_different_fixed_keys() {
+        let a = Point::new(47.376_912, 8.541_694);
+        let b = Point::new(47.376_94, 8.541_71);
+
+        assert_ne!(a.to_fixed_key(5), b.to_fixed_key(5));
+        assert_ne!(a.to_fixed_key(2), Point::new(47.39, 8.54).to_fixed_key(2));
+    }
+
+    #[test]
+    fn fixed_keys_dedup_points_in_a_hash_set() {
+        use std::collections::HashSet;
+
+        let points = [
+            Point::new(47.3769, 8.5417),
+            Point::new(47.376_900_001, 8.541_700_001),
+            Point::new(46.2044, 6.1432),
+        ];
+
+        let keys: HashSet<_> = points.iter().map(|p| p.to_fixed_key(6)).collect();
+
+        assert_eq!(keys.len(), 2);
+    }
+
+    #[test]
+    fn fixed_key_precision_is_capped() {
+        let point = Point::new(47.3769, 8.5417);
+
+        assert_eq!(point.to_fixed_key(15), point.to_fixed_key(u8::MAX));
+    }
+
+    #[test]
+    fn distance_from_zurich_to_bern() {
+        let zurich = Point::new(47.3769, 8.5417);
+        let bern = Point::new(46.9481, 7.4474);
+
+        assert!((zurich.distance_to(&bern) - 95.5).abs() < 0.1);
+        assert_eq!(zurich.distance_to(&bern), bern.distance_to(&zurich));
+    }
+
+    #[test]
+    fn points_one_ulp_apart_are_approximately_equal() {
+        let summed = Point::new(46.0 + 0.1 + 0.2, 8.3);
+        let literal = Point::new(46.3, 8.3);
+
+        assert_ne!(summed, literal);
+        assert!(summed.approx_eq(&literal, 1e-9));
+    }
+
+    #[test]
+    fn approx_eq_includes_the_epsilon_boundary() {
+        let origin = Point::new(47.0, 8.0);
+
+        assert!(origin.approx_eq(&Point::new(47.5, 8.0), 0.5));
+        assert!(origin.approx_eq(&Point::new(47.0, 7.5), 0.5));
+        assert!(origin.approx_eq(&Point::new(47.5, 8.5), 0.5));
+        assert!(!origin.approx_eq(&Point::new(47.500001, 8.0), 0.5));
+        assert!(!origin.approx_eq(&Point::new(47.0, 7.499999), 0.5));
+    }
+
+    #[test]
+    fn approx_eq_with_zero_epsilon_is_exact() {
+        let point = Point::new(47.3769, 8.5417);
+
+        assert!(point.approx_eq(&point, 0.0));
+        assert!(!point.approx_eq(&Point::new(47.3769, 8.5417f64.next_up()), 0.0));
+    }
+
+    #[test]
+    fn nan_is_never_approximately_equal() {
+        let nan = Point::new(f64::NAN, 8.5417);
+
+        assert!(!nan.approx_eq(&nan, f64::INFINITY));
+    }
+
+    #[test]
+    fn distance_to_itself_is_zero() {
+        let zurich = Point::new(47.3769, 8.5417);
+
+        assert_eq!(zurich.distance_to(&zurich), 0.0);
+    }
+
+    #[test]
+    fn snap_to_grid_stays_within_switzerland() {
+        for digits in 0..=6 {
+            for _ in 0..100 {
+                let (lat, lon) = random_swiss_coordinates();
+                let snapped = Point::new(lat, lon).snap_to_grid(digits);
+
+                assert!(
+                    Point::is_within_switzerland(snapped.latitude, snapped.longitude),
+                    "{lat},{lon} snapped to {snapped} with {digits} digits"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn snap_to_grid_clamps_border_points_back_into_switzerland() {
+        let north_east_corner = Point::new(Point::MAX_LATITUDE, Point::MAX_LONGITUDE);
+
+        assert_eq!(
+            north_east_corner.snap_to_grid(0),
+            Point::new(Point::MAX_LATITUDE, Point::MAX_LONGITUDE)
+        );
+    }
+
+    #[test]
+    fn legacy_text_in_postgres_point_form_is_longitude_first() {
+        let point = Point::parse_legacy_text("(8.5417,47.3769)").unwrap();
+        assert_eq!(point, Point::new(47.3769, 8.5417));
+    }
+
+    #[test]
+    fn legacy_text_in_api_form_is_latitude_first() {
+        let point = Point::parse_legacy_text(" 47.3769, 8.5417 ").unwrap();
+        assert_eq!(point, Point::new(47.3769, 8.5417));
+    }
+
+    #[test]
+    fn legacy_text_outside_switzerland_still_decodes() {
+        assert_ok!(Point::parse_legacy_text("(13.4050,52.5200)"));
+    }
+
+    #[test]
+    fn malformed_legacy_text_is_rejected() {
+        for text in ["", "47.3769", "(8.5417;47.3769)", "(a,b)", "(8.5,47.4"] {
+            assert_err!(
+                Point::parse_legacy_text(text),
+                "{text:?} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn flexible_parse_accepts_semicolon_and_whitespace_separators() {
+        for s in [
+            "47.37;8.54",
+            "47.37 8.54",
+            " 47.37 \t 8.54 ",
+            "47.37; 8.54",
+            "47.37,8.54",
+        ] {
+            let point = Point::parse_flexible(s).unwrap();
+            assert_eq!(point, Point::new(47.37, 8.54), "{s:?}");
+        }
+    }
+
+    #[test]
+    fn flexible_parse_still_requires_exactly_two_components() {
+        for s in [
+            "47.37",
+            "47.37 8.54 500",
+            "47.37;8.54;1",
+            "47.37,8.54;1",
+            "",
+        ] {
+            assert_err!(Point::parse_flexible(s), "{s:?} should be rejected");
+        }
+    }
+
+    #[test]
+    fn flexible_parse_still_enforces_switzerland_bounds() {
+        assert_err!(Point::parse_flexible("52.52;13.40"));
+    }
+
+    #[test]
+    fn default_parse_stays_comma_only() {
+        assert_err!(Point::parse("47.37;8.54"));
+        assert_err!(Point::parse("47.37 8.54"));
+    }
+
+    #[test]
+    fn point_obj_serializes_as_numbers() {
+        let point = PointObj(Point::new(47.3769, 8.5417));
+
+        assert_eq!(
+            serde_json::to_value(point).unwrap(),
+            serde_json::json!({"lat": 47.3769, "lon": 8.5417})
+        );
+    }
+
+    #[test]
+    fn point_obj_roundtrips() {
+        use crate::domain::farm::canton::CANTON_CAPITALS;
+
+        for (_, _, lat, lon) in CANTON_CAPITALS {
+            let original = PointObj(Point::new(lat, lon));
+
+            let json = serde_json::to_string(&original).unwrap();
+            let decoded: PointObj = serde_json::from_str(&json).unwrap();
+
+            assert_eq!(original, decoded);
+        }
+    }
+
+    #[test]
+    fn point_obj_rejects_points_outside_switzerland() {
+        for _ in 0..100 {
+            let (lat, lon) = random_non_swiss_coordinates();
+            let json = serde_json::json!({"lat": lat, "lon": lon});
+
+            assert_err!(serde_json::from_value::<PointObj>(json));
+        }
+    }
+
+    #[test]
+    fn point_obj_rejects_malformed_objects() {
+        for json in [
+            serde_json::json!({"lat": 47.3769}),
+            serde_json::json!({"lat": "47.3769", "lon": "8.5417"}),
+            serde_json::json!({"lat": 47.3769, "lon": 8.5417, "alt": 400}),
+            serde_json::json!("47.3769,8.5417"),
+        ] {
+            assert_err!(serde_json::from_value::<PointObj>(json));
+        }
+    }
+
+    #[test]
+    fn point_default_form_is_still_a_string() {
+        let point = Point::new(47.3769, 8.5417);
+
+        assert_eq!(
+            serde_json::to_value(point).unwrap(),
+            serde_json::json!("47.3769,8.5417")
+        );
+    }
+
+    #[test]
+    fn parse_many_partitions_points_and_indexed_failures() {
+        let (points, failures) = Point::parse_many(&[
+            "47.3769,8.5417",
+            "not a point",
+            "46.9480, 7.4474",
+            "91.0,8.5417",
+            "52.5200,13.4050",
+            "46.2044,6.1432",
+        ]);
+
+        assert_eq!(
+            points,
+            [
+                Point::new(47.3769, 8.5417),
+                Point::new(46.9480, 7.4474),
+                Point::new(46.2044, 6.1432),
+            ]
+        );
+        assert_eq!(failures.len(), 3);
+        assert_matches!(&failures[0], (1, PointError::InvalidFormat));
+        assert_matches!(&failures[1], (3, PointError::InvalidLatitude));
+        assert_matches!(&failures[2], (4, PointError::NotInSwitzerland));
+    }
+
+    #[test]
+    fn parse_many_of_nothing_is_empty() {
+        let (points, failures) = Point::parse_many(&[]);
+
+        assert!(points.is_empty());
+        assert!(failures.is_empty());
+    }
+
+    #[test]
+    fn to_string_fixed_formats_float_noise_alike() {
+        let clean = Point::new(47.3769, 8.5417);
+        let noisy = Point::new(47.376900000001, 8.541699999999);
+        assert_ne!(clean.to_string_format(), noisy.to_string_format());
+
+        assert_eq!("47.376900,8.541700", clean.to_string_fixed(6));
+        assert_eq!(clean.to_string_fixed(6), noisy.to_string_fixed(6));
+        assert_eq!("47.38,8.54", noisy.to_string_fixed(2));
+    }
+
+    #[test]
+    fn to_string_fixed_pads_rounds_and_drops_negative_zero() {
+        let point = Point::new(47.0, -0.0001);
+
+        assert_eq!("47.000,0.000", point.to_string_fixed(3));
+        assert_eq!("47,0", point.to_string_fixed(0));
+        assert_eq!(point.to_string_fixed(15), point.to_string_fixed(200));
+    }
 }
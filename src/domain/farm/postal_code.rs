@@ -0,0 +1,164 @@
+//! Swiss postal code (PLZ/NPA) validation.
+//!
+//! Provides a validated `PostalCode` type for the four-digit codes used in
+//! Switzerland, stored in PostgreSQL as a SMALLINT.
+
+use std::fmt::Display;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type)]
+#[sqlx(transparent)]
+pub struct PostalCode(i16);
+
+#[derive(Debug, thiserror::Error)]
+pub enum PostalCodeError {
+    #[error("Postal code cannot be empty.")]
+    EmptyPostalCode,
+
+    #[error("Invalid postal code: {0}. Must be a four-digit Swiss postal code (1000-9999).")]
+    InvalidPostalCode(String),
+}
+
+impl PostalCode {
+    const MIN: i16 = 1000;
+    const MAX: i16 = 9999;
+
+    /// Parse a postal code string into a validated PostalCode
+    ///
+    /// Rules:
+    /// - Cannot be empty or only whitespaces
+    /// - Must be exactly four ASCII digits
+    /// - Must be within 1000-9999
+    pub fn parse(s: String) -> Result<Self, PostalCodeError> {
+        let trimmed = s.trim();
+
+        if trimmed.is_empty() {
+            return Err(PostalCodeError::EmptyPostalCode);
+        }
+
+        if trimmed.len() != 4 || !trimmed.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(PostalCodeError::InvalidPostalCode(s));
+        }
+
+        match trimmed.parse::<i16>() {
+            Ok(code) if (Self::MIN..=Self::MAX).contains(&code) => Ok(Self(code)),
+            _ => Err(PostalCodeError::InvalidPostalCode(s)),
+        }
+    }
+
+    /// Extract the postal code from a free-form Swiss address.
+    ///
+    /// Addresses are typically written "Street Number, Postal Code City", so
+    /// the last standalone four-digit token (optionally prefixed `CH-`) wins;
+    /// this skips house numbers and PO box numbers that come before it.
+    pub fn from_address(address: &str) -> Option<Self> {
+        address
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .rev()
+            .map(|token| token.strip_prefix("CH-").unwrap_or(token))
+            .find_map(|token| Self::parse(token.to_string()).ok())
+    }
+
+    pub fn as_i16(&self) -> i16 {
+        self.0
+    }
+}
+
+impl Display for PostalCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+// Serialize for JSON API responses
+impl serde::Serialize for PostalCode {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_i16(self.0)
+    }
+}
+
+// Deserialize from JSON API requests
+impl<'de> serde::Deserialize<'de> for PostalCode {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let code = i64::deserialize(deserializer)?;
+        PostalCode::parse(code.to_string()).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PostalCode;
+    use claims::{assert_err, assert_none, assert_ok, assert_some_eq};
+
+    #[test]
+    fn lowest_postal_code_is_valid() {
+        assert_ok!(PostalCode::parse("1000".to_string()));
+    }
+
+    #[test]
+    fn highest_postal_code_is_valid() {
+        assert_ok!(PostalCode::parse("9999".to_string()));
+    }
+
+    #[test]
+    fn postal_code_below_range_is_rejected() {
+        assert_err!(PostalCode::parse("0999".to_string()));
+    }
+
+    #[test]
+    fn postal_code_with_wrong_number_of_digits_is_rejected() {
+        assert_err!(PostalCode::parse("800".to_string()));
+        assert_err!(PostalCode::parse("80010".to_string()));
+    }
+
+    #[test]
+    fn non_numeric_postal_code_is_rejected() {
+        assert_err!(PostalCode::parse("80a1".to_string()));
+        assert_err!(PostalCode::parse("+801".to_string()));
+    }
+
+    #[test]
+    fn empty_postal_code_is_rejected() {
+        assert_err!(PostalCode::parse("  ".to_string()));
+    }
+
+    #[test]
+    fn postal_code_is_extracted_from_a_typical_address() {
+        assert_some_eq!(
+            PostalCode::from_address("Bahnhofstrasse 1, 8001 Zürich"),
+            PostalCode::parse("8001".to_string()).unwrap()
+        );
+    }
+
+    #[test]
+    fn postal_code_after_a_po_box_number_wins() {
+        assert_some_eq!(
+            PostalCode::from_address("Postfach 1234\n3000 Bern"),
+            PostalCode::parse("3000".to_string()).unwrap()
+        );
+    }
+
+    #[test]
+    fn postal_code_with_country_prefix_is_extracted() {
+        assert_some_eq!(
+            PostalCode::from_address("Rue du Marché 5, CH-1204 Genève"),
+            PostalCode::parse("1204".to_string()).unwrap()
+        );
+    }
+
+    #[test]
+    fn address_without_postal_code_yields_none() {
+        assert_none!(PostalCode::from_address("Bahnhofstrasse 12"));
+    }
+
+    #[test]
+    fn postal_code_serializes_as_a_number() {
+        let code = PostalCode::parse("6900".to_string()).unwrap();
+        assert_eq!(serde_json::to_string(&code).unwrap(), "6900");
+    }
+}
@@ -9,6 +9,10 @@
 /// This is used to convert between the service types and the database types.
 /// It's useful for domain types like Name, Canton, Address, etc.
 ///
+/// `Decode` trusts the database and skips validation: values were parsed on
+/// the way in. Their `serde::Deserialize` impls, used for untrusted input,
+/// always parse.
+///
 /// # Example
 /// ```ignore
 /// use crate::impl_sqlx_for_string_domain_type;
@@ -90,3 +94,73 @@ macro_rules! impl_sqlx_for_vec_string_domain_type {
         }
     };
 }
+
+/// Macro to implement the accessors, `Display`, serde and sqlx traits for a
+/// new type wrapper around String that implements `DomainString`
+///
+/// `as_str`, `AsRef<str>` and `Display` expose the inner string and
+/// `Serialize` writes it as is. `Deserialize` goes through the type's own
+/// `parse`, so it must have one returning `Result<Self, E>` with a
+/// displayable `E`.
+///
+/// # Example
+/// ```ignore
+/// use crate::impl_domain_string;
+///
+/// #[derive(Debug, Clone)]
+/// pub struct StructName(String);
+///
+/// impl StructName {
+///     pub fn parse(s: String) -> Result<Self, StructNameError> {
+///         Self::validate(&s).map(|trimmed| Self(trimmed.to_string()))
+///     }
+/// }
+///
+/// impl_domain_string!(StructName);
+/// ```
+#[macro_export]
+macro_rules! impl_domain_string {
+    ($type_name:ident) => {
+        impl $type_name {
+            /// Returns the value as a string slice. Useful for logging and display.
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl AsRef<str> for $type_name {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl std::fmt::Display for $type_name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                self.0.fmt(f)
+            }
+        }
+
+        // Serialize for JSON API responses
+        impl serde::Serialize for $type_name {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+            {
+                serializer.serialize_str(&self.0)
+            }
+        }
+
+        // Deserialize from JSON API requests
+        impl<'de> serde::Deserialize<'de> for $type_name {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                let s = String::deserialize(deserializer)?;
+                $type_name::parse(s).map_err(serde::de::Error::custom)
+            }
+        }
+
+        $crate::impl_sqlx_for_string_domain_type!($type_name);
+    };
+}
@@ -1,4 +1,5 @@
 // Shared utilities
+pub mod domain_string;
 pub mod macros;
 
 #[cfg(test)]
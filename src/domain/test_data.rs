@@ -4,37 +4,6 @@
 //! testing Swiss locations. Used across domain type tests to ensure
 //! consistent test data.
 
-/// Swiss canton capitals with their coordinates
-/// Format: (City Name, "latitude,longitude")
-pub const CANTON_CAPITALS: &[(&str, &str)] = &[
-    ("Zürich", "47.3769,8.5417"),
-    ("Bern", "46.9481,7.4474"),
-    ("Lucerne", "47.0502,8.3093"),
-    ("Altdorf", "46.8805,8.6444"),
-    ("Schwyz", "47.0207,8.6532"),
-    ("Sarnen", "46.8960,8.2461"),
-    ("Stans", "46.9579,8.3659"),
-    ("Glarus", "47.0404,9.0679"),
-    ("Zug", "47.1724,8.5153"),
-    ("Fribourg", "46.8063,7.1608"),
-    ("Solothurn", "47.2084,7.5371"),
-    ("Basel", "47.5596,7.5886"),
-    ("Liestal", "47.4814,7.7343"),
-    ("Schaffhausen", "47.6979,8.6344"),
-    ("Herisau", "47.3859,9.2792"),
-    ("Appenzell", "47.3316,9.4094"),
-    ("St. Gallen", "47.4245,9.3767"),
-    ("Chur", "46.8499,9.5331"),
-    ("Aarau", "47.3925,8.0457"),
-    ("Frauenfeld", "47.5536,8.8988"),
-    ("Bellinzona", "46.1930,9.0208"),
-    ("Lausanne", "46.5197,6.6323"),
-    ("Sion", "46.2310,7.3603"),
-    ("Neuchâtel", "46.9896,6.9294"),
-    ("Geneva", "46.2044,6.1432"),
-    ("Delémont", "47.3653,7.3453"),
-];
-
 /// Valid Swiss addresses representing different formats and language regions
 pub const VALID_SWISS_ADDRESSES: &[&str] = &[
     // German-speaking region (Zürich, Bern, etc.)
@@ -10,3 +10,123 @@ pub fn error_chain_fmt(
     }
     Ok(())
 }
+
+/// Whether `e`, or any error in its source chain, means a backing service
+/// could not be reached (a saturated or closed connection pool, or a dropped
+/// Postgres/Redis connection) rather than a bug in the request handling.
+pub fn is_dependency_unavailable(e: &(dyn std::error::Error + 'static)) -> bool {
+    let mut current = Some(e);
+    while let Some(cause) = current {
+        if let Some(e) = cause.downcast_ref::<sqlx::Error>()
+            && matches!(
+                e,
+                sqlx::Error::PoolTimedOut | sqlx::Error::PoolClosed | sqlx::Error::Io(_)
+            )
+        {
+            return true;
+        }
+        if let Some(e) = cause.downcast_ref::<deadpool_redis::PoolError>() {
+            match e {
+                deadpool_redis::PoolError::Timeout(_) | deadpool_redis::PoolError::Closed => {
+                    return true;
+                }
+                deadpool_redis::PoolError::Backend(e) if is_redis_connection_error(e) => {
+                    return true;
+                }
+                _ => {}
+            }
+        }
+        if let Some(e) = cause.downcast_ref::<deadpool_redis::redis::RedisError>()
+            && is_redis_connection_error(e)
+        {
+            return true;
+        }
+        current = cause.source();
+    }
+    false
+}
+
+/// Whether `e`, or any error in its source chain, is a Postgres serialization
+/// failure (SQLSTATE 40001) or deadlock (40P01): the transaction lost a race
+/// and running it again is safe.
+pub fn is_retryable_transaction_error(e: &(dyn std::error::Error + 'static)) -> bool {
+    let mut current = Some(e);
+    while let Some(cause) = current {
+        if let Some(sqlx::Error::Database(e)) = cause.downcast_ref::<sqlx::Error>()
+            && matches!(e.code().as_deref(), Some("40001" | "40P01"))
+        {
+            return true;
+        }
+        current = cause.source();
+    }
+    false
+}
+
+/// The name of the constraint `e`, or any error in its source chain, reports
+/// as violated by Postgres.
+pub fn violated_constraint<'a>(e: &'a (dyn std::error::Error + 'static)) -> Option<&'a str> {
+    let mut current = Some(e);
+    while let Some(cause) = current {
+        if let Some(sqlx::Error::Database(e)) = cause.downcast_ref::<sqlx::Error>()
+            && let Some(constraint) = e.constraint()
+        {
+            return Some(constraint);
+        }
+        current = cause.source();
+    }
+    None
+}
+
+fn is_redis_connection_error(e: &deadpool_redis::redis::RedisError) -> bool {
+    e.is_io_error() || e.is_connection_refusal() || e.is_connection_dropped() || e.is_timeout()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_dependency_unavailable, is_retryable_transaction_error, violated_constraint};
+    use anyhow::Context;
+
+    #[test]
+    fn pool_timeout_anywhere_in_the_chain_is_an_outage() {
+        let e = Err::<(), _>(sqlx::Error::PoolTimedOut)
+            .context("Failed to fetch farms.")
+            .unwrap_err();
+
+        assert!(is_dependency_unavailable(e.as_ref()));
+    }
+
+    #[test]
+    fn redis_pool_timeout_is_an_outage() {
+        let e = deadpool_redis::PoolError::Timeout(deadpool_redis::TimeoutType::Wait);
+
+        assert!(is_dependency_unavailable(&e));
+    }
+
+    #[test]
+    fn query_errors_are_not_an_outage() {
+        let e = anyhow::Error::from(sqlx::Error::RowNotFound).context("Failed to fetch farm.");
+
+        assert!(!is_dependency_unavailable(e.as_ref()));
+    }
+
+    #[test]
+    fn plain_errors_are_not_an_outage() {
+        let e = anyhow::anyhow!("Something went wrong.");
+
+        assert!(!is_dependency_unavailable(e.as_ref()));
+    }
+
+    #[test]
+    fn errors_without_a_sqlstate_are_not_retryable() {
+        let e = anyhow::Error::from(sqlx::Error::PoolTimedOut).context("Failed to insert farm.");
+
+        assert!(!is_retryable_transaction_error(e.as_ref()));
+    }
+
+    #[test]
+    fn errors_from_outside_the_database_violate_no_constraint() {
+        let e = anyhow::Error::from(sqlx::Error::RowNotFound).context("Failed to insert farm.");
+
+        assert_eq!(violated_constraint(e.as_ref()), None);
+    }
+}
@@ -0,0 +1,247 @@
+//! Response language negotiation.
+//!
+//! Picks one of the languages the API localizes into from an explicit
+//! `?lang=` query parameter or, failing that, the `Accept-Language` header.
+//! Which of them a deployment serves is configured in `LanguageSettings`.
+
+use crate::configuration::{LanguageSettings, SharedSettings};
+use crate::errors::error_chain_fmt;
+use actix_web::{
+    FromRequest, HttpRequest, ResponseError, dev::Payload, http::StatusCode, http::header, web,
+};
+use serde::Deserializer;
+use std::fmt::Formatter;
+use std::future::{Ready, ready};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Language {
+    En,
+    #[default]
+    De,
+    Fr,
+    It,
+    Rm,
+}
+
+impl Language {
+    /// Every language the API can localize into.
+    pub const ALL: [Language; 5] = [
+        Language::En,
+        Language::De,
+        Language::Fr,
+        Language::It,
+        Language::Rm,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Language::En => "en",
+            Language::De => "de",
+            Language::Fr => "fr",
+            Language::It => "it",
+            Language::Rm => "rm",
+        }
+    }
+
+    /// Match a language tag (`fr`, `fr-CH`, `FR_ch`) on its primary subtag.
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let primary = tag.trim().split(['-', '_']).next()?.to_lowercase();
+        match primary.as_str() {
+            "en" => Some(Language::En),
+            "de" => Some(Language::De),
+            "fr" => Some(Language::Fr),
+            "it" => Some(Language::It),
+            "rm" => Some(Language::Rm),
+            _ => None,
+        }
+    }
+
+    /// The best supported language of an `Accept-Language` header value.
+    ///
+    /// Ranges are ordered by quality (`q`, default 1); on a tie the one listed
+    /// first wins. Ranges with `q=0`, wildcards and unsupported languages are
+    /// skipped.
+    pub fn negotiate(accept_language: &str) -> Option<Self> {
+        Self::negotiate_among(accept_language, &Self::ALL)
+    }
+
+    /// Same as `negotiate`, treating languages missing from `supported` as
+    /// unsupported.
+    pub fn negotiate_among(accept_language: &str, supported: &[Language]) -> Option<Self> {
+        let mut best: Option<(Language, f32)> = None;
+
+        for range in accept_language.split(',') {
+            let mut parts = range.split(';');
+            let Some(language) = parts
+                .next()
+                .and_then(Language::from_tag)
+                .filter(|language| supported.contains(language))
+            else {
+                continue;
+            };
+            let quality = parts
+                .filter_map(|param| param.trim().strip_prefix("q="))
+                .find_map(|q| q.trim().parse::<f32>().ok())
+                .unwrap_or(1.0);
+
+            if quality <= 0.0 {
+                continue;
+            }
+            if best.is_none_or(|(_, best_quality)| quality > best_quality) {
+                best = Some((language, quality));
+            }
+        }
+
+        best.map(|(language, _)| language)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Language {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        Language::from_tag(&s).ok_or_else(|| {
+            serde::de::Error::custom(format!(
+                "{s} is not a supported language. Use one of `en`, `de`, `fr`, `it` or `rm`."
+            ))
+        })
+    }
+}
+
+#[derive(thiserror::Error)]
+pub enum LanguageError {
+    #[error("Unsupported language '{requested}'. Use one of: {supported}.")]
+    Unsupported {
+        requested: String,
+        supported: String,
+    },
+}
+
+impl ResponseError for LanguageError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            Self::Unsupported { .. } => StatusCode::BAD_REQUEST,
+        }
+    }
+}
+
+impl std::fmt::Debug for LanguageError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        error_chain_fmt(self, f)
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct LanguageQuery {
+    lang: Option<String>,
+}
+
+/// The language a response should be localized into.
+///
+/// `?lang=` wins, and must name one of the configured `languages.supported`
+/// (`400` otherwise); without it the `Accept-Language` header is negotiated
+/// among them, falling back to `languages.default`.
+#[derive(Debug, Clone, Copy)]
+pub struct PreferredLanguage(pub Language);
+
+impl PreferredLanguage {
+    fn resolve(req: &HttpRequest, settings: &LanguageSettings) -> Result<Self, LanguageError> {
+        let requested = web::Query::<LanguageQuery>::from_query(req.query_string())
+            .ok()
+            .and_then(|query| query.into_inner().lang);
+        if let Some(requested) = requested {
+            return Language::from_tag(&requested)
+                .filter(|language| settings.supported.contains(language))
+                .map(PreferredLanguage)
+                .ok_or_else(|| LanguageError::Unsupported {
+                    requested,
+                    supported: settings
+                        .supported
+                        .iter()
+                        .map(Language::as_str)
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                });
+        }
+
+        let language = req
+            .headers()
+            .get(header::ACCEPT_LANGUAGE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| Language::negotiate_among(value, &settings.supported))
+            .unwrap_or(settings.default);
+
+        Ok(PreferredLanguage(language))
+    }
+}
+
+impl FromRequest for PreferredLanguage {
+    type Error = LanguageError;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let settings = req
+            .app_data::<web::Data<SharedSettings>>()
+            .map(|configuration| configuration.load().languages.clone())
+            .unwrap_or_default();
+
+        ready(Self::resolve(req, &settings))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Language;
+    use claims::{assert_none, assert_some_eq};
+
+    #[test]
+    fn highest_quality_supported_language_is_selected() {
+        assert_some_eq!(
+            Language::negotiate("fr-CH, fr;q=0.9, en;q=0.5"),
+            Language::Fr
+        );
+    }
+
+    #[test]
+    fn lower_quality_language_listed_first_loses() {
+        assert_some_eq!(Language::negotiate("en;q=0.4, it;q=0.8"), Language::It);
+    }
+
+    #[test]
+    fn unsupported_languages_are_skipped() {
+        assert_some_eq!(Language::negotiate("es, pt;q=0.9, rm;q=0.1"), Language::Rm);
+    }
+
+    #[test]
+    fn unsupported_only_header_yields_none() {
+        assert_none!(Language::negotiate("es-ES, ja;q=0.8, *;q=0.1"));
+    }
+
+    #[test]
+    fn zero_quality_excludes_a_language() {
+        assert_some_eq!(Language::negotiate("fr;q=0, de;q=0.2"), Language::De);
+    }
+
+    #[test]
+    fn negotiation_skips_languages_that_are_not_enabled() {
+        assert_some_eq!(
+            Language::negotiate_among("rm, fr;q=0.8, de;q=0.5", &[Language::De, Language::Fr]),
+            Language::Fr
+        );
+        assert_none!(Language::negotiate_among("rm", &[Language::De]));
+    }
+
+    #[test]
+    fn default_language_is_german() {
+        assert_eq!(Language::default(), Language::De);
+    }
+
+    #[test]
+    fn tags_match_on_their_primary_subtag_case_insensitively() {
+        assert_some_eq!(Language::from_tag("IT-ch"), Language::It);
+        assert_some_eq!(Language::from_tag("de_CH"), Language::De);
+        assert_none!(Language::from_tag("english"));
+    }
+}
@@ -1,5 +1,6 @@
 use crate::errors::error_chain_fmt;
 use crate::idempotency::persistence::IdempotencyPersistenceError;
+use std::time::Duration;
 
 #[derive(thiserror::Error)]
 pub enum IdempotencyError {
@@ -9,6 +10,12 @@ pub enum IdempotencyError {
     KeyValidation(String),
     #[error("We expected a saved response, we didn't find it")]
     ExpectedResponseNotFoundError,
+    #[error("A request with this idempotency key is still being processed")]
+    KeyInFlight { expires_in: Duration },
+    #[error("The idempotency key was already used with a different request payload")]
+    PayloadMismatch,
+    #[error("The response is larger than the {max} bytes an idempotency key can save")]
+    ResponseTooLarge { max: usize },
     #[error("Selected Idempotency engine is not supported")]
     InvalidEngineError,
     #[error(transparent)]
@@ -1,24 +1,41 @@
-use actix_web::{HttpResponse, body::to_bytes, http::StatusCode};
+use crate::idempotency::IdempotencyError;
+use actix_web::{HttpResponse, body::to_bytes_limited, http::StatusCode};
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, PartialEq, sqlx::Type, Debug)]
+/// Largest response body an idempotency key saves, in bytes. Responses are
+/// buffered whole before being stored, so a larger one (say a streamed
+/// export) is refused rather than held in memory or stored cut short.
+pub const MAX_SAVED_BODY_BYTES: usize = 64 * 1024;
+
+#[derive(Serialize, Deserialize, PartialEq, Clone, sqlx::Type, Debug)]
 #[sqlx(type_name = "header_pair")]
 pub struct HeaderPair {
     pub name: String,
     pub value: Vec<u8>,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct IdempotencyData {
     pub response_status_code: u16,
     pub response_headers: Vec<HeaderPair>,
     pub response_body: Vec<u8>,
+    /// Hash of the request payload that produced this response. Entries
+    /// written before hashing was introduced have none and are not compared.
+    #[serde(default)]
+    pub request_hash: Option<String>,
 }
 impl IdempotencyData {
-    pub async fn try_from_response(http_response: HttpResponse) -> Result<Self, anyhow::Error> {
+    /// Buffers `http_response` for saving, streamed bodies included. Fails
+    /// with `ResponseTooLarge` past `MAX_SAVED_BODY_BYTES`.
+    pub async fn try_from_response(http_response: HttpResponse) -> Result<Self, IdempotencyError> {
         let (response_head, body) = http_response.into_parts();
 
-        let body_bytes = to_bytes(body).await.map_err(|e| anyhow::anyhow!("{}", e))?;
+        let body_bytes = to_bytes_limited(body, MAX_SAVED_BODY_BYTES)
+            .await
+            .map_err(|_| IdempotencyError::ResponseTooLarge {
+                max: MAX_SAVED_BODY_BYTES,
+            })?
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
         let status_code = response_head.status().as_u16();
         let headers = {
             let mut h = Vec::with_capacity(response_head.headers().len());
@@ -34,9 +51,18 @@ impl IdempotencyData {
             response_status_code: status_code,
             response_headers: headers,
             response_body: body_bytes.to_vec(),
+            request_hash: None,
         })
     }
 
+    /// Whether this saved response was produced by a request with the given
+    /// payload hash.
+    pub fn matches_request_hash(&self, request_hash: &str) -> bool {
+        self.request_hash
+            .as_deref()
+            .is_none_or(|saved| saved == request_hash)
+    }
+
     pub fn into_response(self) -> Result<HttpResponse, anyhow::Error> {
         if self.response_status_code == 0 {
             return Err(anyhow::anyhow!("No available StatusCode to build Response"));
@@ -51,3 +77,48 @@ impl IdempotencyData {
         Ok(response.body(self.response_body))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{IdempotencyData, MAX_SAVED_BODY_BYTES};
+    use crate::idempotency::IdempotencyError;
+    use actix_web::{HttpResponse, web::Bytes};
+    use claims::{assert_matches, assert_ok};
+
+    fn streamed(chunks: usize, chunk_size: usize) -> HttpResponse {
+        let chunks =
+            (0..chunks).map(move |_| Ok::<_, std::io::Error>(Bytes::from(vec![b'x'; chunk_size])));
+        HttpResponse::Ok().streaming(tokio_stream::iter(chunks))
+    }
+
+    #[tokio::test]
+    async fn a_streamed_body_within_the_cap_is_saved_whole() {
+        let data = assert_ok!(IdempotencyData::try_from_response(streamed(4, 1024)).await);
+
+        assert_eq!(data.response_body.len(), 4 * 1024);
+    }
+
+    #[tokio::test]
+    async fn a_body_at_the_cap_is_saved() {
+        let response = HttpResponse::Ok().body(vec![b'x'; MAX_SAVED_BODY_BYTES]);
+
+        let data = assert_ok!(IdempotencyData::try_from_response(response).await);
+
+        assert_eq!(data.response_body.len(), MAX_SAVED_BODY_BYTES);
+    }
+
+    #[tokio::test]
+    async fn a_body_past_the_cap_is_refused_rather_than_truncated() {
+        for response in [
+            HttpResponse::Ok().body(vec![b'x'; MAX_SAVED_BODY_BYTES + 1]),
+            streamed(MAX_SAVED_BODY_BYTES / 1024 + 1, 1024),
+        ] {
+            assert_matches!(
+                IdempotencyData::try_from_response(response).await,
+                Err(IdempotencyError::ResponseTooLarge {
+                    max: MAX_SAVED_BODY_BYTES
+                })
+            );
+        }
+    }
+}
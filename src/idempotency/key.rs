@@ -1,4 +1,10 @@
+use crate::configuration::IdempotencySettings;
 use crate::idempotency::IdempotencyError;
+use uuid::Uuid;
+
+/// The longest key, in bytes, any engine stores once the client's key is
+/// namespaced. Also enforced by the `idempotency` table.
+pub const MAX_STORED_KEY_LENGTH: usize = 200;
 
 #[derive(Debug)]
 pub struct IdempotencyKey(String);
@@ -27,6 +33,48 @@ impl TryFrom<String> for IdempotencyKey {
         Ok(Self(value))
     }
 }
+impl IdempotencyKey {
+    /// Validates the client's key and namespaces it by the configured API
+    /// version, so a response saved by one version is never replayed to
+    /// another.
+    ///
+    /// The length limit is checked against the longest key any engine
+    /// derives from it (Redis's `{prefix}:{user_id}:{api_version}:{key}`),
+    /// so a key is accepted or rejected the same way whichever engine runs.
+    pub fn for_storage(
+        value: String,
+        idempotency_settings: &IdempotencySettings,
+    ) -> Result<Self, IdempotencyError> {
+        let key = Self::try_from(value)?.namespaced(&idempotency_settings.api_version);
+        // Any user id is as long as the nil one.
+        let stored_length = key
+            .user_scoped(&idempotency_settings.redis_key_prefix, Uuid::nil())
+            .0
+            .len();
+        if stored_length > MAX_STORED_KEY_LENGTH {
+            return Err(IdempotencyError::KeyValidation(format!(
+                "The idempotency key is too long: stored with its namespace it takes {} bytes, at most {} are allowed",
+                stored_length, MAX_STORED_KEY_LENGTH
+            )));
+        }
+
+        Ok(key)
+    }
+
+    /// The key as the Redis engine stores it, prefixed with
+    /// `{prefix}:{user_id}:`.
+    pub(crate) fn user_scoped(&self, prefix: &str, user_id: Uuid) -> Self {
+        self.namespaced(&format!("{}:{}", prefix, user_id))
+    }
+
+    /// Prefixes the key with `{namespace}:`.
+    ///
+    /// No limit is re-applied: [`IdempotencyKey::for_storage`] already
+    /// accounted for every namespace the engines add.
+    pub(crate) fn namespaced(&self, namespace: &str) -> Self {
+        Self(format!("{}:{}", namespace, self.0))
+    }
+}
 impl From<IdempotencyKey> for String {
     fn from(key: IdempotencyKey) -> Self {
         key.0
@@ -40,10 +88,92 @@ impl AsRef<str> for IdempotencyKey {
 
 #[cfg(test)]
 mod tests {
-    use super::IdempotencyKey;
+    use super::{IdempotencyKey, MAX_STORED_KEY_LENGTH};
+    use crate::configuration::{IdempotencyEngine, IdempotencySettings};
+    use crate::idempotency::IdempotencyError;
     use claims::{assert_err, assert_ok};
     use uuid::Uuid;
 
+    fn settings(redis_key_prefix: &str, api_version: &str) -> IdempotencySettings {
+        IdempotencySettings {
+            engine: IdempotencyEngine::Redis,
+            ttl_seconds: 600,
+            redis_key_prefix: redis_key_prefix.to_string(),
+            cleanup_worker_run_interval: 60,
+            api_version: api_version.to_string(),
+            exempt_endpoints: vec![],
+            in_flight_retry_after_seconds: None,
+        }
+    }
+
+    /// The length of a client key that, stored by Redis, is exactly at the limit.
+    fn boundary_length(settings: &IdempotencySettings) -> usize {
+        let user_id = Uuid::new_v4().to_string();
+        let namespace = format!(
+            "{}:{}:{}:",
+            settings.redis_key_prefix, user_id, settings.api_version
+        );
+        MAX_STORED_KEY_LENGTH - namespace.len()
+    }
+
+    #[test]
+    fn key_for_storage_is_namespaced_by_api_version() {
+        let key = IdempotencyKey::for_storage("abc".to_string(), &settings("idem", "v1")).unwrap();
+        assert_eq!(key.as_ref(), "v1:abc");
+    }
+
+    #[test]
+    fn key_composing_to_the_stored_limit_is_accepted() {
+        let settings = settings("idem", &"v".repeat(100));
+        let length = boundary_length(&settings);
+
+        let key = IdempotencyKey::for_storage("a".repeat(length), &settings).unwrap();
+
+        let redis_key = key.user_scoped(&settings.redis_key_prefix, Uuid::new_v4());
+        assert_eq!(redis_key.as_ref().len(), MAX_STORED_KEY_LENGTH);
+    }
+
+    #[test]
+    fn key_composing_past_the_stored_limit_is_rejected() {
+        let settings = settings("idem", &"v".repeat(100));
+        let length = boundary_length(&settings) + 1;
+
+        let result = IdempotencyKey::for_storage("a".repeat(length), &settings);
+
+        let Err(IdempotencyError::KeyValidation(message)) = result else {
+            panic!("Expected a key validation error, got {result:?}");
+        };
+        assert!(message.contains("201 bytes"), "{message}");
+    }
+
+    #[test]
+    fn default_namespaces_leave_the_whole_client_limit_usable() {
+        assert_ok!(IdempotencyKey::for_storage(
+            "a".repeat(79),
+            &settings("idem", "v1")
+        ));
+    }
+
+    #[test]
+    fn key_for_storage_still_applies_the_client_limit() {
+        assert_err!(IdempotencyKey::for_storage(
+            "a".repeat(80),
+            &settings("idem", "v1")
+        ));
+    }
+
+    #[test]
+    fn namespaced_key_is_prefixed() {
+        let key = IdempotencyKey::try_from("abc".to_string()).unwrap();
+        assert_eq!(key.namespaced("v1").as_ref(), "v1:abc");
+    }
+
+    #[test]
+    fn namespaced_key_may_exceed_the_client_length_limit() {
+        let key = IdempotencyKey::try_from("a".repeat(79)).unwrap();
+        assert_eq!(key.namespaced("v1").as_ref().len(), 82);
+    }
+
     #[test]
     fn empty_string_is_rejected() {
         let key = "".to_string();
@@ -3,11 +3,16 @@ mod idempotency_data;
 mod key;
 mod persistence;
 mod postgres_cleanup_worker;
+mod request_hash;
 
 pub use error::IdempotencyError;
-pub use idempotency_data::{HeaderPair, IdempotencyData};
-pub use key::IdempotencyKey;
-pub use persistence::{IdempotencyNextAction, save_response, try_processing};
+pub use idempotency_data::{HeaderPair, IdempotencyData, MAX_SAVED_BODY_BYTES};
+pub use key::{IdempotencyKey, MAX_STORED_KEY_LENGTH};
+pub use persistence::{
+    IdempotencyNextAction, IdempotencyStore, MemoryIdempotencyStore, PostgresIdempotencyStore,
+    RedisIdempotencyStore, StoreNextAction, save_response, try_processing,
+};
 pub use postgres_cleanup_worker::{
     ExpiryOutcome, run_expiry_worker_until_stopped, try_to_execute_task,
 };
+pub use request_hash::hash_request_payload;
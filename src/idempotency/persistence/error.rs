@@ -1,5 +1,6 @@
 use crate::errors::error_chain_fmt;
 use std::fmt::Formatter;
+use std::time::Duration;
 
 #[derive(thiserror::Error)]
 pub enum IdempotencyPersistenceError {
@@ -15,6 +16,8 @@ pub enum IdempotencyPersistenceError {
     SqlError(#[from] sqlx::Error),
     #[error("We expected a saved response, we didn't find it")]
     ExpectedResponseNotFoundError,
+    #[error("Another request holds the idempotency key; its claim expires in {expires_in:?}")]
+    KeyInFlight { expires_in: Duration },
     #[error(transparent)]
     UnexpectedError(#[from] anyhow::Error),
 }
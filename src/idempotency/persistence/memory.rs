@@ -0,0 +1,244 @@
+use crate::idempotency::{
+    IdempotencyData, IdempotencyKey,
+    persistence::{IdempotencyPersistenceError, IdempotencyStore, StoreNextAction},
+};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+use uuid::Uuid;
+
+struct Entry {
+    /// `None` while the key is in flight.
+    data: Option<IdempotencyData>,
+    expire_at: Instant,
+}
+
+impl Entry {
+    fn is_expired(&self, now: Instant) -> bool {
+        self.expire_at <= now
+    }
+}
+
+/// Entries keyed by (user, key).
+type Entries = HashMap<(Uuid, String), Entry>;
+
+/// Idempotency keys in a process-local `HashMap`.
+///
+/// Entries live for `ttl`; expired ones are treated as absent on read and
+/// evicted on the next `try_processing` call.
+/// Cloning the store hands out another handle to the same map.
+#[derive(Clone)]
+pub struct MemoryIdempotencyStore {
+    entries: Arc<Mutex<Entries>>,
+    ttl: Duration,
+}
+
+impl MemoryIdempotencyStore {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            entries: Arc::new(Mutex::new(HashMap::new())),
+            ttl,
+        }
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, Entries>, IdempotencyPersistenceError> {
+        self.entries.lock().map_err(|_| {
+            IdempotencyPersistenceError::UnexpectedError(anyhow::anyhow!(
+                "The in-memory idempotency store lock is poisoned."
+            ))
+        })
+    }
+}
+
+impl IdempotencyStore for MemoryIdempotencyStore {
+    async fn try_processing(
+        &mut self,
+        idempotency_key: &IdempotencyKey,
+        user_id: Uuid,
+        _request_hash: &str,
+    ) -> Result<StoreNextAction, IdempotencyPersistenceError> {
+        let now = Instant::now();
+        let mut entries = self.lock()?;
+        entries.retain(|_, entry| !entry.is_expired(now));
+        let entry_key = (user_id, idempotency_key.as_ref().to_string());
+
+        match entries.get(&entry_key).map(|entry| &entry.data) {
+            None => {
+                entries.insert(
+                    entry_key,
+                    Entry {
+                        data: None,
+                        expire_at: now + self.ttl,
+                    },
+                );
+                Ok(StoreNextAction::StartProcessing)
+            }
+            Some(None) => Err(IdempotencyPersistenceError::KeyInFlight {
+                expires_in: entries[&entry_key].expire_at - now,
+            }),
+            Some(Some(saved_response_data)) => Ok(StoreNextAction::ReturnSavedData(
+                saved_response_data.clone(),
+            )),
+        }
+    }
+
+    async fn save_response(
+        &mut self,
+        idempotency_key: &IdempotencyKey,
+        user_id: Uuid,
+        idempotency_data: &IdempotencyData,
+    ) -> Result<(), IdempotencyPersistenceError> {
+        let mut entries = self.lock()?;
+        entries.insert(
+            (user_id, idempotency_key.as_ref().to_string()),
+            Entry {
+                data: Some(idempotency_data.clone()),
+                expire_at: Instant::now() + self.ttl,
+            },
+        );
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MemoryIdempotencyStore;
+    use crate::idempotency::{
+        IdempotencyData, IdempotencyKey,
+        persistence::{IdempotencyPersistenceError, IdempotencyStore, StoreNextAction},
+    };
+    use claims::{assert_err, assert_matches};
+    use std::time::Duration;
+    use uuid::Uuid;
+
+    fn store() -> MemoryIdempotencyStore {
+        MemoryIdempotencyStore::new(Duration::from_secs(60))
+    }
+
+    fn key() -> IdempotencyKey {
+        IdempotencyKey::try_from(Uuid::new_v4().to_string()).unwrap()
+    }
+
+    fn created_response() -> IdempotencyData {
+        IdempotencyData {
+            response_status_code: 201,
+            response_headers: Vec::new(),
+            response_body: b"created".to_vec(),
+            request_hash: Some("hash".to_string()),
+        }
+    }
+
+    #[tokio::test]
+    async fn unseen_key_starts_processing() {
+        let mut store = store();
+
+        let next_action = store.try_processing(&key(), Uuid::new_v4(), "hash").await;
+
+        assert_matches!(next_action, Ok(StoreNextAction::StartProcessing));
+    }
+
+    #[tokio::test]
+    async fn saved_response_is_returned_for_a_repeated_key() {
+        let mut store = store();
+        let (key, user_id) = (key(), Uuid::new_v4());
+
+        store.try_processing(&key, user_id, "hash").await.unwrap();
+        store
+            .save_response(&key, user_id, &created_response())
+            .await
+            .unwrap();
+        let next_action = store.try_processing(&key, user_id, "hash").await;
+
+        match next_action {
+            Ok(StoreNextAction::ReturnSavedData(data)) => {
+                assert_eq!(data.response_status_code, 201);
+                assert_eq!(data.response_body, b"created".to_vec());
+            }
+            _ => panic!("Expected the saved response to be returned."),
+        }
+    }
+
+    #[tokio::test]
+    async fn key_in_flight_without_a_saved_response_is_an_error() {
+        let mut store = store();
+        let (key, user_id) = (key(), Uuid::new_v4());
+
+        store.try_processing(&key, user_id, "hash").await.unwrap();
+        let next_action = store.try_processing(&key, user_id, "hash").await;
+
+        assert_err!(&next_action);
+        assert_matches!(
+            next_action,
+            Err(IdempotencyPersistenceError::KeyInFlight { expires_in })
+                if expires_in > Duration::ZERO && expires_in <= Duration::from_secs(60)
+        );
+    }
+
+    #[tokio::test]
+    async fn same_key_is_scoped_per_user() {
+        let mut store = store();
+        let key = key();
+
+        store
+            .try_processing(&key, Uuid::new_v4(), "hash")
+            .await
+            .unwrap();
+        let next_action = store.try_processing(&key, Uuid::new_v4(), "hash").await;
+
+        assert_matches!(next_action, Ok(StoreNextAction::StartProcessing));
+    }
+
+    #[tokio::test]
+    async fn clones_share_the_same_entries() {
+        let mut store = store();
+        let mut handle = store.clone();
+        let (key, user_id) = (key(), Uuid::new_v4());
+
+        store.try_processing(&key, user_id, "hash").await.unwrap();
+        handle
+            .save_response(&key, user_id, &created_response())
+            .await
+            .unwrap();
+
+        assert_matches!(
+            store.try_processing(&key, user_id, "hash").await,
+            Ok(StoreNextAction::ReturnSavedData(_))
+        );
+    }
+
+    #[tokio::test]
+    async fn saved_response_expires_after_the_ttl() {
+        let mut store = MemoryIdempotencyStore::new(Duration::from_millis(20));
+        let (key, user_id) = (key(), Uuid::new_v4());
+
+        store.try_processing(&key, user_id, "hash").await.unwrap();
+        store
+            .save_response(&key, user_id, &created_response())
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(40)).await;
+        let next_action = store.try_processing(&key, user_id, "hash").await;
+
+        assert_matches!(next_action, Ok(StoreNextAction::StartProcessing));
+    }
+
+    #[tokio::test]
+    async fn expired_entries_are_evicted() {
+        let mut store = MemoryIdempotencyStore::new(Duration::from_millis(20));
+
+        store
+            .try_processing(&key(), Uuid::new_v4(), "hash")
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(40)).await;
+        store
+            .try_processing(&key(), Uuid::new_v4(), "hash")
+            .await
+            .unwrap();
+
+        assert_eq!(store.lock().unwrap().len(), 1);
+    }
+}
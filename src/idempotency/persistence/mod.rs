@@ -1,147 +1,175 @@
 use crate::{
     configuration::{IdempotencyEngine, IdempotencySettings},
-    idempotency::{
-        IdempotencyData, IdempotencyError, IdempotencyKey,
-        persistence::{postgres::PostgresPersistenceNextAction, redis::RedisPersistenceNextAction},
-    },
+    db::StatementCounter,
+    idempotency::{IdempotencyData, IdempotencyError, IdempotencyKey},
 };
 use actix_web::HttpResponse;
 use deadpool_redis::Pool;
-use sqlx::{PgPool, Postgres, Transaction};
+use sqlx::{Postgres, Transaction};
 use uuid::Uuid;
 
 mod error;
+mod memory;
 mod postgres;
 mod redis;
+mod store;
 
 pub use error::IdempotencyPersistenceError;
+pub use memory::MemoryIdempotencyStore;
+pub use postgres::PostgresIdempotencyStore;
+pub use redis::RedisIdempotencyStore;
+pub use store::{IdempotencyStore, StoreNextAction};
 
-fn build_user_scoped_idempotency_key(
-    redis_key_prefix: &str,
-    user_id: Uuid,
-    idempotency_key: &str,
-) -> Result<IdempotencyKey, IdempotencyError> {
-    IdempotencyKey::try_from(format!(
-        "{}:{}:{}",
-        redis_key_prefix, user_id, idempotency_key
-    ))
-    .map_err(|e| IdempotencyError::UnexpectedError(e.into()))
-}
-
+#[allow(clippy::too_many_arguments)]
+#[tracing::instrument(
+    name = "Save idempotency response",
+    skip_all,
+    fields(
+        engine = idempotency_settings.engine.as_str(),
+        api_version = %idempotency_settings.api_version,
+    )
+)]
 pub async fn save_response(
     redis_pool: &Pool,
-    transaction: Transaction<'static, Postgres>,
+    memory_store: &MemoryIdempotencyStore,
+    statements: &StatementCounter,
+    transaction: &mut Transaction<'static, Postgres>,
     idempotency_key: &str,
     user_id: Uuid,
+    request_hash: &str,
     idempotency_settings: &IdempotencySettings,
     http_response: HttpResponse,
-) -> Result<(HttpResponse, Transaction<'static, Postgres>), IdempotencyError> {
-    let idempotency_data = IdempotencyData::try_from_response(http_response).await?;
+) -> Result<HttpResponse, IdempotencyError> {
+    // Validated whatever the engine, so every engine accepts the same keys.
+    let idempotency_key =
+        IdempotencyKey::for_storage(idempotency_key.to_string(), idempotency_settings)?;
+    let mut idempotency_data = IdempotencyData::try_from_response(http_response).await?;
+    idempotency_data.request_hash = Some(request_hash.to_string());
+
     match idempotency_settings.engine {
         // No idempotency just return the provided response
-        IdempotencyEngine::None => Ok((idempotency_data.into_response()?, transaction)),
+        IdempotencyEngine::None => {}
         IdempotencyEngine::Redis => {
-            let idempotency_key = build_user_scoped_idempotency_key(
-                &idempotency_settings.redis_key_prefix,
-                user_id,
-                idempotency_key,
-            )?;
-            redis::save_response(
-                redis_pool,
-                &idempotency_key,
-                idempotency_settings.ttl_seconds,
-                &idempotency_data,
-            )
-            .await
-            .map_err(IdempotencyError::from)?;
-
-            Ok((idempotency_data.into_response()?, transaction))
+            let mut store = RedisIdempotencyStore::new(redis_pool.clone(), idempotency_settings);
+            save_to_store(&mut store, &idempotency_key, user_id, &idempotency_data).await?;
+        }
+        IdempotencyEngine::Memory => {
+            let mut store = memory_store.clone();
+            save_to_store(&mut store, &idempotency_key, user_id, &idempotency_data).await?;
         }
         IdempotencyEngine::Postgres => {
-            let idempotency_key = IdempotencyKey::try_from(idempotency_key.to_string())
-                .map_err(|e| IdempotencyError::UnexpectedError(e.into()))?;
-            let transaction = postgres::save_response(
+            let mut store = PostgresIdempotencyStore::new(
                 transaction,
-                &idempotency_key,
-                user_id,
+                statements,
                 idempotency_settings.ttl_seconds,
-                &idempotency_data,
-            )
-            .await
-            .map_err(IdempotencyError::from)?;
-
-            Ok((idempotency_data.into_response()?, transaction))
+            );
+            save_to_store(&mut store, &idempotency_key, user_id, &idempotency_data).await?;
         }
     }
+
+    Ok(idempotency_data.into_response()?)
+}
+
+async fn save_to_store(
+    store: &mut impl IdempotencyStore,
+    idempotency_key: &IdempotencyKey,
+    user_id: Uuid,
+    idempotency_data: &IdempotencyData,
+) -> Result<(), IdempotencyError> {
+    store
+        .save_response(idempotency_key, user_id, idempotency_data)
+        .await
+        .map_err(IdempotencyError::from)
 }
 
 pub enum IdempotencyNextAction {
-    StartProcessing(Transaction<'static, Postgres>),
+    StartProcessing,
     ReturnSavedResponse(HttpResponse),
 }
 
+/// Claims `idempotency_key` for `user_id`, in `transaction` when the keys are
+/// kept in Postgres, the request then being processed in that transaction.
+#[allow(clippy::too_many_arguments)]
+#[tracing::instrument(
+    name = "Claim idempotency key",
+    skip_all,
+    fields(
+        engine = idempotency_settings.engine.as_str(),
+        api_version = %idempotency_settings.api_version,
+    )
+)]
 pub async fn try_processing(
     redis_pool: &Pool,
-    db_pool: &PgPool,
+    memory_store: &MemoryIdempotencyStore,
+    statements: &StatementCounter,
+    transaction: &mut Transaction<'static, Postgres>,
     idempotency_key: &str,
     user_id: Uuid,
+    request_hash: &str,
     idempotency_settings: &IdempotencySettings,
 ) -> Result<IdempotencyNextAction, IdempotencyError> {
-    let transaction = db_pool
-        .begin()
-        .await
-        .map_err(IdempotencyPersistenceError::from)?;
+    let idempotency_key =
+        IdempotencyKey::for_storage(idempotency_key.to_string(), idempotency_settings)?;
 
-    match idempotency_settings.engine {
-        IdempotencyEngine::None => Ok(IdempotencyNextAction::StartProcessing(transaction)),
+    let next_action = match idempotency_settings.engine {
+        IdempotencyEngine::None => StoreNextAction::StartProcessing,
         IdempotencyEngine::Redis => {
-            let idempotency_key = build_user_scoped_idempotency_key(
-                &idempotency_settings.redis_key_prefix,
-                user_id,
-                idempotency_key,
-            )?;
-
-            match redis::try_processing(redis_pool, &idempotency_key, idempotency_settings)
-                .await
-                .map_err(|e| match e {
-                    IdempotencyPersistenceError::ExpectedResponseNotFoundError => {
-                        IdempotencyError::ExpectedResponseNotFoundError
-                    }
-                    _ => IdempotencyError::from(e),
-                })? {
-                RedisPersistenceNextAction::ReturnSavedData(response_data) => Ok(
-                    IdempotencyNextAction::ReturnSavedResponse(response_data.into_response()?),
-                ),
-                RedisPersistenceNextAction::StartProcessing => {
-                    Ok(IdempotencyNextAction::StartProcessing(transaction))
-                }
-            }
+            let mut store = RedisIdempotencyStore::new(redis_pool.clone(), idempotency_settings);
+            try_processing_with_store(&mut store, &idempotency_key, user_id, request_hash).await?
+        }
+        IdempotencyEngine::Memory => {
+            let mut store = memory_store.clone();
+            try_processing_with_store(&mut store, &idempotency_key, user_id, request_hash).await?
         }
         IdempotencyEngine::Postgres => {
-            let idempotency_key = IdempotencyKey::try_from(idempotency_key.to_string())
-                .map_err(|e| IdempotencyError::UnexpectedError(e.into()))?;
-
-            match postgres::try_processing(
+            let mut store = PostgresIdempotencyStore::new(
                 transaction,
-                db_pool,
-                &idempotency_key,
-                user_id,
+                statements,
                 idempotency_settings.ttl_seconds,
-            )
-            .await
-            .map_err(|e| match e {
-                IdempotencyPersistenceError::ExpectedResponseNotFoundError => {
-                    IdempotencyError::ExpectedResponseNotFoundError
-                }
-                _ => IdempotencyError::from(e),
-            })? {
-                PostgresPersistenceNextAction::ReturnSavedData(response_data) => Ok(
-                    IdempotencyNextAction::ReturnSavedResponse(response_data.into_response()?),
-                ),
-                PostgresPersistenceNextAction::StartProcessing(transaction) => {
-                    Ok(IdempotencyNextAction::StartProcessing(transaction))
-                }
-            }
+            );
+            try_processing_with_store(&mut store, &idempotency_key, user_id, request_hash).await?
         }
+    };
+
+    match next_action {
+        StoreNextAction::StartProcessing => Ok(IdempotencyNextAction::StartProcessing),
+        StoreNextAction::ReturnSavedData(response_data) => {
+            replay_saved_response(response_data, request_hash)
+        }
+    }
+}
+
+async fn try_processing_with_store(
+    store: &mut impl IdempotencyStore,
+    idempotency_key: &IdempotencyKey,
+    user_id: Uuid,
+    request_hash: &str,
+) -> Result<StoreNextAction, IdempotencyError> {
+    store
+        .try_processing(idempotency_key, user_id, request_hash)
+        .await
+        .map_err(|e| match e {
+            IdempotencyPersistenceError::ExpectedResponseNotFoundError => {
+                IdempotencyError::ExpectedResponseNotFoundError
+            }
+            IdempotencyPersistenceError::KeyInFlight { expires_in } => {
+                IdempotencyError::KeyInFlight { expires_in }
+            }
+            _ => IdempotencyError::from(e),
+        })
+}
+
+/// Replays a saved response, refusing to do so when the key is being reused
+/// for a request whose payload differs from the one that produced it.
+fn replay_saved_response(
+    response_data: IdempotencyData,
+    request_hash: &str,
+) -> Result<IdempotencyNextAction, IdempotencyError> {
+    if !response_data.matches_request_hash(request_hash) {
+        return Err(IdempotencyError::PayloadMismatch);
     }
+
+    Ok(IdempotencyNextAction::ReturnSavedResponse(
+        response_data.into_response()?,
+    ))
 }
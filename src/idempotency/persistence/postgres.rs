@@ -1,103 +1,87 @@
-use crate::idempotency::{
-    HeaderPair, IdempotencyData, IdempotencyKey, persistence::IdempotencyPersistenceError,
+use crate::{
+    db::StatementCounter,
+    idempotency::{
+        HeaderPair, IdempotencyData, IdempotencyKey,
+        persistence::{IdempotencyPersistenceError, IdempotencyStore, StoreNextAction},
+    },
 };
-use sqlx::{PgPool, Postgres, Row, Transaction};
-use std::num::TryFromIntError;
+use sqlx::{Postgres, Row, Transaction};
+use std::{num::TryFromIntError, time::Duration};
 use uuid::Uuid;
 
-// TODO Remove dead code after further development and testing
-
-#[allow(dead_code)]
-pub enum PostgresPersistenceNextAction {
-    StartProcessing(Transaction<'static, Postgres>),
-    ReturnSavedData(IdempotencyData),
+/// Idempotency keys in the `idempotency` table.
+///
+/// The key is claimed inside the request's own transaction, so a concurrent
+/// request with the same key blocks on the row until that transaction ends.
+pub struct PostgresIdempotencyStore<'a> {
+    transaction: &'a mut Transaction<'static, Postgres>,
+    statements: &'a StatementCounter,
+    ttl_seconds: u64,
 }
 
-#[allow(dead_code)]
-pub async fn try_processing(
-    mut transaction: Transaction<'static, Postgres>,
-    db_pool: &PgPool,
-    idempotency_key: &IdempotencyKey,
-    user_id: Uuid,
-    ttl_seconds: u64,
-) -> Result<PostgresPersistenceNextAction, IdempotencyPersistenceError> {
-    let ttl_seconds = ttl_seconds_to_i64(ttl_seconds)?;
-    let query = sqlx::query(
-        r#"
-        INSERT INTO idempotency (
-            user_id,
-            key,
-            created_at,
-            expire_at
+impl<'a> PostgresIdempotencyStore<'a> {
+    pub fn new(
+        transaction: &'a mut Transaction<'static, Postgres>,
+        statements: &'a StatementCounter,
+        ttl_seconds: u64,
+    ) -> Self {
+        Self {
+            transaction,
+            statements,
+            ttl_seconds,
+        }
+    }
+
+    #[tracing::instrument(
+        name = "Read saved idempotency response from Postgres",
+        skip_all,
+        fields(engine = "postgres")
+    )]
+    /// The response saved under a key someone else claimed. Fails with
+    /// `KeyInFlight` while there is none yet, and with
+    /// `ExpectedResponseNotFoundError` once the claim itself is gone.
+    async fn get_saved_response(
+        &mut self,
+        idempotency_key: &IdempotencyKey,
+        user_id: Uuid,
+    ) -> Result<IdempotencyData, IdempotencyPersistenceError> {
+        let saved_response = sqlx::query(
+            r#"
+            SELECT
+                response_status_code,
+                response_headers,
+                response_body,
+                request_hash,
+                extract(epoch FROM expire_at - now())::float8 AS expires_in_seconds
+            FROM idempotency
+            WHERE
+                user_id = $1 AND
+                key = $2 AND
+                expire_at > now()
+            "#,
         )
-        VALUES ($1, $2, now(), now() + ($3::bigint * interval '1 second'))
-        ON CONFLICT (user_id, key) DO UPDATE
-        SET
-            created_at = EXCLUDED.created_at,
-            expire_at = EXCLUDED.expire_at,
-            response_status_code = NULL,
-            response_headers = NULL,
-            response_body = NULL
-        WHERE
-            idempotency.expire_at <= now()
-        "#,
-    );
-    let n_inserted_rows = query
         .bind(user_id)
         .bind(idempotency_key.as_ref())
-        .bind(ttl_seconds)
-        .execute(&mut *transaction)
-        .await?
-        .rows_affected();
-    if n_inserted_rows > 0 {
-        Ok(PostgresPersistenceNextAction::StartProcessing(transaction))
-    } else {
-        let saved_response_data = get_saved_response(db_pool, idempotency_key, user_id)
-            .await?
-            .ok_or(IdempotencyPersistenceError::ExpectedResponseNotFoundError)?;
-
-        Ok(PostgresPersistenceNextAction::ReturnSavedData(
-            saved_response_data,
-        ))
-    }
-}
+        .fetch_optional(self.statements.on(&mut **self.transaction))
+        .await?;
 
-#[allow(dead_code)]
-pub async fn get_saved_response(
-    pool: &PgPool,
-    idempotency_key: &IdempotencyKey,
-    user_id: Uuid,
-) -> Result<Option<IdempotencyData>, IdempotencyPersistenceError> {
-    let saved_response = sqlx::query(
-        r#"
-        SELECT
-            response_status_code,
-            response_headers,
-            response_body
-        FROM idempotency
-        WHERE
-            user_id = $1 AND
-            key = $2 AND
-            expire_at > now()
-        "#,
-    )
-    .bind(user_id)
-    .bind(idempotency_key.as_ref())
-    .fetch_optional(pool)
-    .await?;
-
-    if let Some(r) = saved_response {
+        let Some(r) = saved_response else {
+            return Err(IdempotencyPersistenceError::ExpectedResponseNotFoundError);
+        };
+        let in_flight = || IdempotencyPersistenceError::KeyInFlight {
+            expires_in: Duration::from_secs_f64(r.get::<f64, _>("expires_in_seconds").max(0.0)),
+        };
         let Some(response_status_code) = r.get::<Option<i16>, _>("response_status_code") else {
-            return Ok(None);
+            return Err(in_flight());
         };
         let response_status_code: u16 = response_status_code
             .try_into()
             .map_err(|e: TryFromIntError| IdempotencyPersistenceError::UnexpectedError(e.into()))?;
         if response_status_code == 0 {
-            return Ok(None);
+            return Err(in_flight());
         }
 
-        let saved_response_data = IdempotencyData {
+        Ok(IdempotencyData {
             response_status_code,
             response_headers: r
                 .get::<Option<Vec<HeaderPair>>, _>("response_headers")
@@ -105,46 +89,99 @@ pub async fn get_saved_response(
             response_body: r
                 .get::<Option<Vec<u8>>, _>("response_body")
                 .unwrap_or_default(),
-        };
-
-        Ok(Some(saved_response_data))
-    } else {
-        Ok(None)
+            request_hash: r.get::<Option<String>, _>("request_hash"),
+        })
     }
 }
 
-#[allow(dead_code)]
-pub async fn save_response(
-    mut transaction: Transaction<'static, Postgres>,
-    idempotency_key: &IdempotencyKey,
-    user_id: Uuid,
-    ttl_seconds: u64,
-    idempotency_data: &IdempotencyData,
-) -> Result<Transaction<'static, Postgres>, IdempotencyPersistenceError> {
-    let ttl_seconds = ttl_seconds_to_i64(ttl_seconds)?;
-    sqlx::query(
-        r#"
-        UPDATE idempotency
-        SET
-            response_status_code = $3,
-            response_headers = $4,
-            response_body = $5,
-            expire_at = now() + ($6::bigint * interval '1 second')
-        WHERE
-            user_id = $1 AND
-            key = $2
-        "#,
-    )
-    .bind(user_id)
-    .bind(idempotency_key.as_ref())
-    .bind(idempotency_data.response_status_code as i16)
-    .bind(&idempotency_data.response_headers)
-    .bind(&idempotency_data.response_body)
-    .bind(ttl_seconds)
-    .execute(&mut *transaction)
-    .await?;
+impl IdempotencyStore for PostgresIdempotencyStore<'_> {
+    #[tracing::instrument(
+        name = "Claim idempotency key in Postgres",
+        skip_all,
+        fields(engine = "postgres")
+    )]
+    async fn try_processing(
+        &mut self,
+        idempotency_key: &IdempotencyKey,
+        user_id: Uuid,
+        request_hash: &str,
+    ) -> Result<StoreNextAction, IdempotencyPersistenceError> {
+        let ttl_seconds = ttl_seconds_to_i64(self.ttl_seconds)?;
+        let query = sqlx::query(
+            r#"
+            INSERT INTO idempotency (
+                user_id,
+                key,
+                request_hash,
+                created_at,
+                expire_at
+            )
+            VALUES ($1, $2, $3, now(), now() + ($4::bigint * interval '1 second'))
+            ON CONFLICT (user_id, key) DO UPDATE
+            SET
+                request_hash = EXCLUDED.request_hash,
+                created_at = EXCLUDED.created_at,
+                expire_at = EXCLUDED.expire_at,
+                response_status_code = NULL,
+                response_headers = NULL,
+                response_body = NULL
+            WHERE
+                idempotency.expire_at <= now()
+            "#,
+        );
+        let n_inserted_rows = query
+            .bind(user_id)
+            .bind(idempotency_key.as_ref())
+            .bind(request_hash)
+            .bind(ttl_seconds)
+            .execute(self.statements.on(&mut **self.transaction))
+            .await?
+            .rows_affected();
+        if n_inserted_rows > 0 {
+            Ok(StoreNextAction::StartProcessing)
+        } else {
+            let saved_response_data = self.get_saved_response(idempotency_key, user_id).await?;
+
+            Ok(StoreNextAction::ReturnSavedData(saved_response_data))
+        }
+    }
 
-    Ok(transaction)
+    #[tracing::instrument(
+        name = "Save idempotency response in Postgres",
+        skip_all,
+        fields(engine = "postgres")
+    )]
+    async fn save_response(
+        &mut self,
+        idempotency_key: &IdempotencyKey,
+        user_id: Uuid,
+        idempotency_data: &IdempotencyData,
+    ) -> Result<(), IdempotencyPersistenceError> {
+        let ttl_seconds = ttl_seconds_to_i64(self.ttl_seconds)?;
+        sqlx::query(
+            r#"
+            UPDATE idempotency
+            SET
+                response_status_code = $3,
+                response_headers = $4,
+                response_body = $5,
+                expire_at = now() + ($6::bigint * interval '1 second')
+            WHERE
+                user_id = $1 AND
+                key = $2
+            "#,
+        )
+        .bind(user_id)
+        .bind(idempotency_key.as_ref())
+        .bind(idempotency_data.response_status_code as i16)
+        .bind(&idempotency_data.response_headers)
+        .bind(&idempotency_data.response_body)
+        .bind(ttl_seconds)
+        .execute(self.statements.on(&mut **self.transaction))
+        .await?;
+
+        Ok(())
+    }
 }
 
 fn ttl_seconds_to_i64(ttl_seconds: u64) -> Result<i64, IdempotencyPersistenceError> {
@@ -1,92 +1,135 @@
 use crate::{
     configuration::IdempotencySettings,
-    idempotency::{IdempotencyData, IdempotencyKey, persistence::IdempotencyPersistenceError},
+    idempotency::{
+        IdempotencyData, IdempotencyKey,
+        persistence::{IdempotencyPersistenceError, IdempotencyStore, StoreNextAction},
+    },
 };
 use deadpool_redis::{
     Pool,
     redis::{AsyncCommands, AsyncTypedCommands, ExistenceCheck, SetExpiry, SetOptions},
 };
+use std::time::Duration;
+use uuid::Uuid;
 
-pub enum RedisPersistenceNextAction {
-    StartProcessing,
-    ReturnSavedData(IdempotencyData),
+/// Idempotency keys in Redis, one `{prefix}:{user_id}:{api_version}:{key}` entry per key.
+///
+/// An empty value marks a key that is claimed but has no saved response yet.
+pub struct RedisIdempotencyStore {
+    pool: Pool,
+    key_prefix: String,
+    ttl_seconds: u64,
 }
 
-pub async fn try_processing(
-    pool: &Pool,
-    idempotency_key: &IdempotencyKey,
-    idempotency_settings: &IdempotencySettings,
-) -> Result<RedisPersistenceNextAction, IdempotencyPersistenceError> {
-    // let data = IdempotencyData {
-    //     response_status_code: 0,
-    //     response_headers: Vec::new(),
-    //     response_body: Vec::new(),
-    // };
-    // let data = rmp_serde::to_vec(&data)?;
-    let data: Vec<u8> = Vec::new();
-
-    let mut connection = pool.get().await?;
-
-    let result: Option<String> = AsyncTypedCommands::set_options(
-        &mut connection,
-        idempotency_key.as_ref(),
-        &data,
-        SetOptions::default()
-            .conditional_set(ExistenceCheck::NX)
-            .with_expiration(SetExpiry::EX(idempotency_settings.ttl_seconds)),
-    )
-    .await?;
-
-    if result.is_some() {
-        Ok(RedisPersistenceNextAction::StartProcessing)
-    } else {
-        let saved_response_data = get_saved_response(pool, idempotency_key)
-            .await?
-            .ok_or(IdempotencyPersistenceError::ExpectedResponseNotFoundError)?;
-
-        Ok(RedisPersistenceNextAction::ReturnSavedData(
-            saved_response_data,
-        ))
+impl RedisIdempotencyStore {
+    pub fn new(pool: Pool, idempotency_settings: &IdempotencySettings) -> Self {
+        Self {
+            pool,
+            key_prefix: idempotency_settings.redis_key_prefix.clone(),
+            ttl_seconds: idempotency_settings.ttl_seconds,
+        }
+    }
+
+    fn user_scoped_key(&self, idempotency_key: &IdempotencyKey, user_id: Uuid) -> IdempotencyKey {
+        idempotency_key.user_scoped(&self.key_prefix, user_id)
+    }
+
+    #[tracing::instrument(
+        name = "Read saved idempotency response from Redis",
+        skip_all,
+        fields(engine = "redis", key_prefix = %self.key_prefix)
+    )]
+    async fn get_saved_response(
+        &self,
+        idempotency_key: &IdempotencyKey,
+    ) -> Result<Option<IdempotencyData>, IdempotencyPersistenceError> {
+        let mut connection = self.pool.get().await?;
+        let bytes: Option<Vec<u8>> =
+            AsyncCommands::get(&mut connection, idempotency_key.as_ref()).await?;
+
+        let Some(bytes) = bytes else {
+            return Ok(None);
+        };
+
+        if bytes.is_empty() {
+            return Ok(None);
+        }
+
+        let data: IdempotencyData = rmp_serde::from_slice(&bytes)?;
+
+        Ok(Some(data))
     }
 }
 
-pub async fn get_saved_response(
-    pool: &Pool,
-    idempotency_key: &IdempotencyKey,
-) -> Result<Option<IdempotencyData>, IdempotencyPersistenceError> {
-    let mut connection = pool.get().await?;
-    let bytes: Option<Vec<u8>> =
-        AsyncCommands::get(&mut connection, idempotency_key.as_ref()).await?;
+impl IdempotencyStore for RedisIdempotencyStore {
+    #[tracing::instrument(
+        name = "Claim idempotency key in Redis",
+        skip_all,
+        fields(engine = "redis", key_prefix = %self.key_prefix)
+    )]
+    async fn try_processing(
+        &mut self,
+        idempotency_key: &IdempotencyKey,
+        user_id: Uuid,
+        _request_hash: &str,
+    ) -> Result<StoreNextAction, IdempotencyPersistenceError> {
+        let idempotency_key = self.user_scoped_key(idempotency_key, user_id);
+        let data: Vec<u8> = Vec::new();
+
+        let mut connection = self.pool.get().await?;
+
+        let result: Option<String> = AsyncTypedCommands::set_options(
+            &mut connection,
+            idempotency_key.as_ref(),
+            &data,
+            SetOptions::default()
+                .conditional_set(ExistenceCheck::NX)
+                .with_expiration(SetExpiry::EX(self.ttl_seconds)),
+        )
+        .await?;
 
-    let Some(bytes) = bytes else {
-        return Ok(None);
-    };
+        if result.is_some() {
+            return Ok(StoreNextAction::StartProcessing);
+        }
+        if let Some(saved_response_data) = self.get_saved_response(&idempotency_key).await? {
+            return Ok(StoreNextAction::ReturnSavedData(saved_response_data));
+        }
 
-    if bytes.is_empty() {
-        return Ok(None);
+        // Claimed without a response: tell the caller how long the claim
+        // lasts. A key that expired in the meantime has no TTL left to give.
+        let remaining_ms: i64 =
+            AsyncCommands::pttl(&mut connection, idempotency_key.as_ref()).await?;
+        match u64::try_from(remaining_ms) {
+            Ok(remaining_ms) if remaining_ms > 0 => Err(IdempotencyPersistenceError::KeyInFlight {
+                expires_in: Duration::from_millis(remaining_ms),
+            }),
+            _ => Err(IdempotencyPersistenceError::ExpectedResponseNotFoundError),
+        }
     }
 
-    let data: IdempotencyData = rmp_serde::from_slice(&bytes)?;
+    #[tracing::instrument(
+        name = "Save idempotency response in Redis",
+        skip_all,
+        fields(engine = "redis", key_prefix = %self.key_prefix)
+    )]
+    async fn save_response(
+        &mut self,
+        idempotency_key: &IdempotencyKey,
+        user_id: Uuid,
+        idempotency_data: &IdempotencyData,
+    ) -> Result<(), IdempotencyPersistenceError> {
+        let idempotency_key = self.user_scoped_key(idempotency_key, user_id);
+        let data_bytes = rmp_serde::to_vec(idempotency_data)?;
 
-    Ok(Some(data))
-}
+        let mut connection = self.pool.get().await?;
+        AsyncTypedCommands::set_ex(
+            &mut connection,
+            idempotency_key.as_ref(),
+            data_bytes,
+            self.ttl_seconds,
+        )
+        .await?;
 
-pub async fn save_response(
-    pool: &Pool,
-    idempotency_key: &IdempotencyKey,
-    ttl_seconds: u64,
-    idempotency_data: &IdempotencyData,
-) -> Result<(), IdempotencyPersistenceError> {
-    let data_bytes = rmp_serde::to_vec(idempotency_data)?;
-
-    let mut connection = pool.get().await?;
-    AsyncTypedCommands::set_ex(
-        &mut connection,
-        idempotency_key.as_ref(),
-        data_bytes,
-        ttl_seconds,
-    )
-    .await?;
-
-    Ok(())
+        Ok(())
+    }
 }
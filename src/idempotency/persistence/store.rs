@@ -0,0 +1,38 @@
+use crate::idempotency::{
+    IdempotencyData, IdempotencyKey, persistence::IdempotencyPersistenceError,
+};
+use std::future::Future;
+use uuid::Uuid;
+
+#[derive(Debug)]
+pub enum StoreNextAction {
+    StartProcessing,
+    ReturnSavedData(IdempotencyData),
+}
+
+/// Storage backend for idempotency keys and the responses saved under them.
+///
+/// Keys are scoped per user: the same key used by two users never collides.
+pub trait IdempotencyStore {
+    /// Claim `idempotency_key` for processing, or return the response already
+    /// saved under it.
+    ///
+    /// Fails with `KeyInFlight`, carrying how long the claim has left, while
+    /// another request holds the key but has not saved its response yet, or
+    /// with `ExpectedResponseNotFoundError` when that claim expired before
+    /// its remaining time could be read.
+    fn try_processing(
+        &mut self,
+        idempotency_key: &IdempotencyKey,
+        user_id: Uuid,
+        request_hash: &str,
+    ) -> impl Future<Output = Result<StoreNextAction, IdempotencyPersistenceError>> + Send;
+
+    /// Save the response produced for a key claimed by `try_processing`.
+    fn save_response(
+        &mut self,
+        idempotency_key: &IdempotencyKey,
+        user_id: Uuid,
+        idempotency_data: &IdempotencyData,
+    ) -> impl Future<Output = Result<(), IdempotencyPersistenceError>> + Send;
+}
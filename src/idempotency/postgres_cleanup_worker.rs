@@ -13,7 +13,7 @@ pub enum ExpiryOutcome {
 pub async fn run_expiry_worker_until_stopped(configuration: Settings) -> Result<(), anyhow::Error> {
     let idempotency_settings = configuration.idempotency;
     match idempotency_settings.engine {
-        IdempotencyEngine::Redis | IdempotencyEngine::None => {
+        IdempotencyEngine::Redis | IdempotencyEngine::Memory | IdempotencyEngine::None => {
             return Ok(());
         }
         _ => {}
@@ -0,0 +1,37 @@
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+/// Fingerprint of a request payload, stored next to the idempotency key so a
+/// retry carrying a different body can be told apart from a genuine replay.
+pub fn hash_request_payload<T: Serialize>(payload: &T) -> Result<String, anyhow::Error> {
+    let bytes = serde_json::to_vec(payload)?;
+
+    Ok(hex::encode(Sha256::digest(&bytes)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::hash_request_payload;
+    use serde_json::json;
+
+    #[test]
+    fn same_payload_produces_same_hash() {
+        let payload = json!({ "name": "Hof Sonnenberg", "canton": "ZH" });
+
+        assert_eq!(
+            hash_request_payload(&payload).unwrap(),
+            hash_request_payload(&payload).unwrap()
+        );
+    }
+
+    #[test]
+    fn different_payloads_produce_different_hashes() {
+        let first = json!({ "name": "Hof Sonnenberg", "canton": "ZH" });
+        let second = json!({ "name": "Hof Sonnenberg", "canton": "BE" });
+
+        assert_ne!(
+            hash_request_payload(&first).unwrap(),
+            hash_request_payload(&second).unwrap()
+        );
+    }
+}
@@ -1,11 +1,17 @@
+pub mod audit;
 pub mod configuration;
+pub mod db;
 pub mod domain;
 pub mod errors;
+pub mod i18n;
 pub mod idempotency;
+pub mod metrics;
 pub mod routes;
 pub mod startup;
 pub mod taxonomy;
 pub mod telemetry;
+#[cfg(feature = "test-util")]
+pub mod test_util;
 
 pub mod authentication;
 mod email_client;
@@ -24,6 +24,9 @@ async fn main() -> anyhow::Result<()> {
         configuration.application.host,
         configuration.application.port,
     );
+    for warning in configuration.warnings() {
+        tracing::warn!("{warning}");
+    }
     tracing::info!(
         environment = %configuration.telemetry.environment,
         log_format = ?configuration.logging.format,
@@ -0,0 +1,157 @@
+//! Connection pool utilization gauges, rendered in the Prometheus text
+//! exposition format by `GET /metrics`.
+
+use deadpool_redis::Pool;
+use sqlx::PgPool;
+use std::{
+    fmt::Write,
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+/// How often the background sampler refreshes the pool gauges.
+pub const POOL_METRICS_SAMPLE_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Last sampled utilization of the Postgres and Redis pools.
+///
+/// Gauges are written by the sampler and read by scrapes, so plain relaxed
+/// atomics are enough: a scrape may mix two samples, never a torn value.
+#[derive(Default)]
+pub struct PoolMetrics {
+    db_pool_idle: AtomicU64,
+    db_pool_active: AtomicU64,
+    db_pool_max: AtomicU64,
+    redis_pool_idle: AtomicU64,
+    redis_pool_active: AtomicU64,
+    redis_pool_max: AtomicU64,
+    redis_pool_waiting: AtomicU64,
+}
+
+impl PoolMetrics {
+    /// Records the current state of both pools.
+    pub fn sample(&self, db_pool: &PgPool, redis_pool: &Pool) {
+        let db_size = u64::from(db_pool.size());
+        let db_idle = db_pool.num_idle() as u64;
+        self.db_pool_idle.store(db_idle, Ordering::Relaxed);
+        self.db_pool_active
+            .store(db_size.saturating_sub(db_idle), Ordering::Relaxed);
+        self.db_pool_max.store(
+            u64::from(db_pool.options().get_max_connections()),
+            Ordering::Relaxed,
+        );
+
+        let redis = redis_pool.status();
+        self.redis_pool_idle
+            .store(redis.available as u64, Ordering::Relaxed);
+        self.redis_pool_active.store(
+            redis.size.saturating_sub(redis.available) as u64,
+            Ordering::Relaxed,
+        );
+        self.redis_pool_max
+            .store(redis.max_size as u64, Ordering::Relaxed);
+        self.redis_pool_waiting
+            .store(redis.waiting as u64, Ordering::Relaxed);
+    }
+
+    /// The gauges in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let load = |gauge: &AtomicU64| gauge.load(Ordering::Relaxed);
+        let mut out = String::new();
+
+        write_gauge(
+            &mut out,
+            "farms_db_pool_connections",
+            "Postgres pool connections by state.",
+            &[
+                ("state=\"idle\"", load(&self.db_pool_idle)),
+                ("state=\"active\"", load(&self.db_pool_active)),
+            ],
+        );
+        write_gauge(
+            &mut out,
+            "farms_db_pool_max_connections",
+            "Maximum number of Postgres pool connections.",
+            &[("", load(&self.db_pool_max))],
+        );
+        write_gauge(
+            &mut out,
+            "farms_redis_pool_connections",
+            "Redis pool connections by state.",
+            &[
+                ("state=\"idle\"", load(&self.redis_pool_idle)),
+                ("state=\"active\"", load(&self.redis_pool_active)),
+            ],
+        );
+        write_gauge(
+            &mut out,
+            "farms_redis_pool_max_connections",
+            "Maximum number of Redis pool connections.",
+            &[("", load(&self.redis_pool_max))],
+        );
+        write_gauge(
+            &mut out,
+            "farms_redis_pool_waiting",
+            "Tasks waiting for a Redis pool connection.",
+            &[("", load(&self.redis_pool_waiting))],
+        );
+
+        out
+    }
+}
+
+fn write_gauge(out: &mut String, name: &str, help: &str, samples: &[(&str, u64)]) {
+    // Writing to a `String` cannot fail.
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} gauge");
+    for (labels, value) in samples {
+        if labels.is_empty() {
+            let _ = writeln!(out, "{name} {value}");
+        } else {
+            let _ = writeln!(out, "{name}{{{labels}}} {value}");
+        }
+    }
+}
+
+/// Samples both pools every `interval` until the runtime shuts down.
+pub async fn run_pool_metrics_sampler(
+    metrics: actix_web::web::Data<PoolMetrics>,
+    db_pool: PgPool,
+    redis_pool: Pool,
+    interval: Duration,
+) {
+    let mut ticker = tokio::time::interval(interval);
+    loop {
+        ticker.tick().await;
+        metrics.sample(&db_pool, &redis_pool);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PoolMetrics;
+    use std::sync::atomic::Ordering;
+
+    #[test]
+    fn render_emits_help_type_and_samples() {
+        let metrics = PoolMetrics::default();
+        metrics.db_pool_idle.store(3, Ordering::Relaxed);
+        metrics.db_pool_active.store(2, Ordering::Relaxed);
+        metrics.db_pool_max.store(10, Ordering::Relaxed);
+
+        let rendered = metrics.render();
+
+        assert!(rendered.contains("# TYPE farms_db_pool_connections gauge\n"));
+        assert!(rendered.contains("farms_db_pool_connections{state=\"idle\"} 3\n"));
+        assert!(rendered.contains("farms_db_pool_connections{state=\"active\"} 2\n"));
+        assert!(rendered.contains("farms_db_pool_max_connections 10\n"));
+        assert!(rendered.contains("farms_redis_pool_waiting 0\n"));
+    }
+
+    #[test]
+    fn every_gauge_is_declared_once() {
+        let rendered = PoolMetrics::default().render();
+
+        assert_eq!(rendered.matches("# TYPE ").count(), 5);
+        assert_eq!(rendered.matches("# HELP ").count(), 5);
+    }
+}
@@ -0,0 +1,115 @@
+use crate::errors::error_chain_fmt;
+use actix_web::{ResponseError, http::StatusCode};
+use std::collections::HashMap;
+use std::fmt::Formatter;
+use std::sync::{Arc, Mutex};
+
+/// A counting semaphore per key (a client IP): caps how many requests each
+/// key may have in flight at once, unlike `check_rate_limit` which counts
+/// requests over a time window. In-process, shared by every worker.
+#[derive(Default)]
+pub struct ConcurrencyLimiter {
+    in_flight: Arc<Mutex<HashMap<String, usize>>>,
+}
+
+/// A slot taken from a `ConcurrencyLimiter`, given back on drop: however the
+/// request ends, completed, failed or cancelled by a timeout or a disconnect.
+#[derive(Debug)]
+pub struct ConcurrencyPermit {
+    in_flight: Arc<Mutex<HashMap<String, usize>>>,
+    key: String,
+}
+
+#[derive(thiserror::Error)]
+pub enum ConcurrencyError {
+    #[error("Too many requests in flight. Try again once one has finished.")]
+    TooManyInFlight,
+}
+
+impl ResponseError for ConcurrencyError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            Self::TooManyInFlight => StatusCode::TOO_MANY_REQUESTS,
+        }
+    }
+}
+
+impl std::fmt::Debug for ConcurrencyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        error_chain_fmt(self, f)
+    }
+}
+
+impl ConcurrencyLimiter {
+    /// A slot for `key`, unless it already holds `max_in_flight` of them.
+    pub fn try_acquire(
+        &self,
+        key: &str,
+        max_in_flight: usize,
+    ) -> Result<ConcurrencyPermit, ConcurrencyError> {
+        let mut in_flight = self.in_flight.lock().expect("Limiter lock poisoned.");
+        let count = in_flight.entry(key.to_string()).or_default();
+        if *count >= max_in_flight {
+            return Err(ConcurrencyError::TooManyInFlight);
+        }
+        *count += 1;
+        Ok(ConcurrencyPermit {
+            in_flight: Arc::clone(&self.in_flight),
+            key: key.to_string(),
+        })
+    }
+}
+
+impl Drop for ConcurrencyPermit {
+    fn drop(&mut self) {
+        let mut in_flight = self.in_flight.lock().expect("Limiter lock poisoned.");
+        if let Some(count) = in_flight.get_mut(&self.key) {
+            *count -= 1;
+            // Forget idle keys so the map only holds clients with requests
+            // in flight.
+            if *count == 0 {
+                in_flight.remove(&self.key);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ConcurrencyError, ConcurrencyLimiter};
+    use claims::{assert_err, assert_matches, assert_ok};
+
+    #[test]
+    fn a_key_gets_at_most_max_in_flight_slots() {
+        let limiter = ConcurrencyLimiter::default();
+
+        let _first = assert_ok!(limiter.try_acquire("10.0.0.1", 2));
+        let _second = assert_ok!(limiter.try_acquire("10.0.0.1", 2));
+
+        assert_matches!(
+            limiter.try_acquire("10.0.0.1", 2),
+            Err(ConcurrencyError::TooManyInFlight)
+        );
+    }
+
+    #[test]
+    fn dropping_a_permit_frees_its_slot() {
+        let limiter = ConcurrencyLimiter::default();
+        let permit = assert_ok!(limiter.try_acquire("10.0.0.1", 1));
+        assert_err!(limiter.try_acquire("10.0.0.1", 1));
+
+        drop(permit);
+
+        assert!(limiter.in_flight.lock().unwrap().is_empty());
+        assert_ok!(limiter.try_acquire("10.0.0.1", 1));
+    }
+
+    #[test]
+    fn keys_are_limited_independently() {
+        let limiter = ConcurrencyLimiter::default();
+        let _permit = assert_ok!(limiter.try_acquire("10.0.0.1", 1));
+
+        assert_ok!(limiter.try_acquire("10.0.0.2", 1));
+        assert_err!(limiter.try_acquire("10.0.0.1", 1));
+    }
+}
@@ -1,3 +1,6 @@
+mod concurrency;
+
+pub use concurrency::*;
 use deadpool_redis::{Pool, redis::AsyncTypedCommands};
 
 pub enum RateLimitDecision {
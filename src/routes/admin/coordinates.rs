@@ -0,0 +1,107 @@
+use crate::{
+    authentication::AdminUser,
+    configuration::SharedSettings,
+    domain::farm::{Point, PointError, SwissBoundary},
+    routes::admin::error::AdminError,
+};
+use actix_web::{HttpResponse, web};
+use anyhow::Context;
+use chrono::Utc;
+use sqlx::PgPool;
+use tokio_stream::StreamExt;
+use uuid::Uuid;
+
+#[derive(Debug, serde::Deserialize)]
+pub struct ReindexQuery {
+    /// Report the failing farms without flagging them.
+    #[serde(default)]
+    dry_run: bool,
+}
+
+#[derive(serde::Serialize)]
+pub struct ReindexReport {
+    scanned: usize,
+    dry_run: bool,
+    invalid: Vec<InvalidCoordinates>,
+}
+
+#[derive(serde::Serialize)]
+pub struct InvalidCoordinates {
+    id: Uuid,
+    name: String,
+    coordinates: Point,
+    reason: String,
+}
+
+/// POST /farms/reindex-coordinates — re-checks every stored point against the
+/// current `farms.swiss_boundary`, e.g. after switching to `polygon`. Unless
+/// `?dry_run=true`, failing farms get `coordinates_flagged_at` set and farms
+/// that pass again have it cleared.
+#[tracing::instrument(name = "Reindex farm coordinates", skip(pool, configuration))]
+pub async fn reindex_coordinates(
+    _admin: AdminUser,
+    query: web::Query<ReindexQuery>,
+    pool: web::Data<PgPool>,
+    configuration: web::Data<SharedSettings>,
+) -> Result<HttpResponse, AdminError> {
+    let boundary = configuration.load().farms.swiss_boundary;
+
+    // Streamed rather than loaded at once: only the failing farms are kept.
+    let mut rows = sqlx::query!(
+        r#"
+        SELECT id, name, coordinates AS "coordinates: Point"
+        FROM farms
+        ORDER BY created_at, id
+        "#,
+    )
+    .fetch(pool.get_ref());
+
+    let mut scanned = 0;
+    let mut invalid = Vec::new();
+    while let Some(row) = rows.next().await {
+        let row = row.context("Failed to load farm coordinates.")?;
+        scanned += 1;
+        if let Err(e) = check_within(&row.coordinates, boundary) {
+            invalid.push(InvalidCoordinates {
+                id: row.id,
+                name: row.name,
+                coordinates: row.coordinates,
+                reason: e.to_string(),
+            });
+        }
+    }
+
+    if !query.dry_run {
+        let ids: Vec<Uuid> = invalid.iter().map(|farm| farm.id).collect();
+        // Only rows whose flag changes are touched; a farm flagged earlier
+        // keeps its original timestamp.
+        sqlx::query!(
+            r#"
+            UPDATE farms
+            SET coordinates_flagged_at = CASE WHEN id = ANY($1) THEN $2::timestamptz END
+            WHERE (id = ANY($1)) <> (coordinates_flagged_at IS NOT NULL)
+            "#,
+            &ids,
+            Utc::now(),
+        )
+        .execute(pool.get_ref())
+        .await
+        .context("Failed to flag farm coordinates.")?;
+    }
+
+    Ok(HttpResponse::Ok().json(ReindexReport {
+        scanned,
+        dry_run: query.dry_run,
+        invalid,
+    }))
+}
+
+/// The checks a newly created farm's point goes through, applied to a stored
+/// one: valid ranges, then `boundary`.
+fn check_within(point: &Point, boundary: SwissBoundary) -> Result<(), PointError> {
+    let point = Point::try_new(point.latitude, point.longitude)?;
+    if !boundary.contains(&point) {
+        return Err(PointError::NotInSwitzerland);
+    }
+    Ok(())
+}
@@ -1,5 +1,7 @@
+mod coordinates;
 mod error;
 mod suggestions;
 
+pub use coordinates::reindex_coordinates;
 pub use error::AdminError;
 pub use suggestions::{approve, list_pending, reject};
@@ -1,10 +1,13 @@
 use crate::{
-    authentication::AdminUser, domain::suggestion::SuggestionAction,
-    routes::admin::error::AdminError,
+    authentication::AdminUser,
+    configuration::SharedSettings,
+    domain::suggestion::SuggestionAction,
+    routes::{admin::error::AdminError, farms::cache::ListCache},
 };
 use actix_web::{HttpResponse, web};
 use anyhow::Context;
 use chrono::{DateTime, Utc};
+use deadpool_redis::Pool;
 use sqlx::PgPool;
 use uuid::Uuid;
 
@@ -77,11 +80,13 @@ pub async fn list_pending(
 }
 
 /// POST /admin/product-suggestions/{id}/approve — claim the row and apply it.
-#[tracing::instrument(name = "Approve suggestion", skip(pool))]
+#[tracing::instrument(name = "Approve suggestion", skip(pool, redis_pool, configuration))]
 pub async fn approve(
     admin: AdminUser,
     path: web::Path<Uuid>,
     pool: web::Data<PgPool>,
+    redis_pool: web::Data<Pool>,
+    configuration: web::Data<SharedSettings>,
 ) -> Result<HttpResponse, AdminError> {
     let id = path.into_inner();
     let mut transaction = pool
@@ -148,6 +153,12 @@ pub async fn approve(
         .await
         .context("Failed to commit approval.")?;
 
+    // The farm's products changed; cached lists must not show the old ones.
+    let configuration = configuration.load();
+    if let Some(cache) = ListCache::new(&redis_pool, &configuration.farms.list_cache) {
+        cache.invalidate().await;
+    }
+
     Ok(HttpResponse::Ok().finish())
 }
 
@@ -1,5 +1,5 @@
 use crate::authentication::{RegisterUserError, register_user};
-use crate::configuration::Settings;
+use crate::configuration::{Settings, SharedSettings};
 use crate::domain::user::{Email, UserPassword, Username};
 use crate::email_client::EmailClient;
 use crate::rate_limit::{RateLimitDecision, check_rate_limit};
@@ -25,9 +25,10 @@ pub async fn register(
     pool: web::Data<PgPool>,
     redis_pool: web::Data<Pool>,
     email_client: web::Data<EmailClient>,
-    configuration: web::Data<Settings>,
+    configuration: web::Data<SharedSettings>,
     request: HttpRequest,
 ) -> Result<HttpResponse, RegisterError> {
+    let configuration = configuration.load_full();
     let body = body.into_inner();
 
     let username = Username::parse(body.username)
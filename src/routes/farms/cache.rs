@@ -0,0 +1,102 @@
+use crate::configuration::ListCacheSettings;
+use deadpool_redis::{
+    Pool,
+    redis::{AsyncCommands, AsyncTypedCommands},
+};
+use sha2::{Digest, Sha256};
+
+/// Short-lived cache of `GET /farms` response bodies.
+///
+/// Entries live under `{prefix}:{version}:{generation}:{hash}`, `hash` being
+/// a digest of the normalized query. Writing farms bumps the generation, so
+/// older entries are never served again and simply expire. Every Redis error
+/// is logged and treated as a miss: the cache must never fail a request.
+///
+/// A page is stored under the generation read before its rows were, never a
+/// later one: a write committed in between then leaves the page under a
+/// generation already out of date, rather than serving it as current.
+pub(crate) struct ListCache<'a> {
+    pool: &'a Pool,
+    settings: &'a ListCacheSettings,
+}
+
+impl<'a> ListCache<'a> {
+    /// `None` when caching is disabled.
+    pub(crate) fn new(pool: &'a Pool, settings: &'a ListCacheSettings) -> Option<Self> {
+        settings.enabled.then_some(Self { pool, settings })
+    }
+
+    /// The cached body for `query`, a normalized description of the request,
+    /// along with the generation it was looked up in, to `put` the page under
+    /// on a miss. No generation when Redis could not be read: such a page is
+    /// not cached.
+    #[tracing::instrument(name = "Read farm list cache", skip(self, query))]
+    pub(crate) async fn get(&self, query: &str) -> (Option<Vec<u8>>, Option<String>) {
+        let result: Result<(Option<Vec<u8>>, String), anyhow::Error> = async {
+            let mut connection = self.pool.get().await?;
+            let generation = AsyncTypedCommands::get(&mut connection, self.generation_key())
+                .await?
+                .unwrap_or_else(|| "0".to_string());
+            let body = AsyncCommands::get(&mut connection, self.key(&generation, query)).await?;
+            Ok((body, generation))
+        }
+        .await;
+        match result {
+            Ok((body, generation)) => (body, Some(generation)),
+            Err(e) => {
+                tracing::warn!(error = ?e, "Failed to read the farm list cache.");
+                (None, None)
+            }
+        }
+    }
+
+    /// Caches `body` as the response to `query` in `generation`, the one
+    /// `get` returned before the rows were read, for `ttl_seconds`.
+    #[tracing::instrument(name = "Write farm list cache", skip(self, query, body))]
+    pub(crate) async fn put(&self, generation: &str, query: &str, body: &[u8]) {
+        let result: Result<(), anyhow::Error> = async {
+            let mut connection = self.pool.get().await?;
+            let key = self.key(generation, query);
+            AsyncCommands::set_ex::<_, _, ()>(
+                &mut connection,
+                key,
+                body,
+                self.settings.ttl_seconds,
+            )
+            .await?;
+            Ok(())
+        }
+        .await;
+        if let Err(e) = result {
+            tracing::warn!(error = ?e, "Failed to write the farm list cache.");
+        }
+    }
+
+    /// Stops every cached page from being served again.
+    #[tracing::instrument(name = "Invalidate farm list cache", skip(self))]
+    pub(crate) async fn invalidate(&self) {
+        let result: Result<(), anyhow::Error> = async {
+            let mut connection = self.pool.get().await?;
+            AsyncTypedCommands::incr(&mut connection, self.generation_key(), 1).await?;
+            Ok(())
+        }
+        .await;
+        if let Err(e) = result {
+            tracing::warn!(error = ?e, "Failed to invalidate the farm list cache.");
+        }
+    }
+
+    fn generation_key(&self) -> String {
+        format!("{}:generation", self.settings.key_prefix)
+    }
+
+    fn key(&self, generation: &str, query: &str) -> String {
+        format!(
+            "{}:{}:{}:{}",
+            self.settings.key_prefix,
+            env!("CARGO_PKG_VERSION"),
+            generation,
+            hex::encode(Sha256::digest(query.as_bytes()))
+        )
+    }
+}
@@ -1,33 +1,245 @@
-use crate::{errors::error_chain_fmt, idempotency::IdempotencyError};
-use actix_web::{ResponseError, http::StatusCode};
+use crate::{
+    configuration::ErrorVerbosity,
+    errors::{error_chain_fmt, is_dependency_unavailable, violated_constraint},
+    i18n::LanguageError,
+    idempotency::IdempotencyError,
+    routes::{JSON_CONTENT_TYPE, describe_json_error},
+};
+use actix_web::{
+    HttpResponse, ResponseError,
+    http::{StatusCode, header},
+};
+use std::borrow::Cow;
 use std::fmt::Formatter;
+use std::time::Duration;
+
+/// Seconds clients are told to wait before retrying a `503`, unless
+/// `application.retry_after_seconds` says otherwise.
+pub const RETRY_AFTER_SECONDS: u64 = 5;
+
+/// Most seconds a `409` for a key still in flight asks clients to wait, unless
+/// `idempotency.in_flight_retry_after_seconds` says otherwise: the request
+/// holding the key normally finishes well within that.
+pub const IN_FLIGHT_RETRY_AFTER_SECONDS: u64 = 2;
 
 #[derive(thiserror::Error)]
 pub enum FarmError {
     // `error` Implements the Display for this enum variant
     #[error("{0}")]
     ValidationError(String),
+    // A `ValidationError` pinned to one request field or query parameter.
+    #[error("{message}")]
+    InvalidField {
+        field: Cow<'static, str>,
+        message: String,
+    },
+    // A body turned away before it could be read as a farm: too large, not
+    // sent as JSON or cut short. Keeps the status actix gives it.
+    #[error("{message}")]
+    RejectedPayload { status: StatusCode, message: String },
+    // Well-formed input that breaks a domain rule, e.g. coordinates outside
+    // Switzerland or an unknown product.
+    #[error("{0}")]
+    Unprocessable(String),
+    // An `Unprocessable` pinned to one request field.
+    #[error("{message}")]
+    UnprocessableField {
+        field: Cow<'static, str>,
+        message: String,
+    },
     #[error(transparent)]
-    UnexpectedError(#[from] anyhow::Error),
+    UnexpectedError(anyhow::Error),
+    // A backing service (database, Redis) is saturated or unreachable.
+    #[error("The service is temporarily unavailable. Please retry later.")]
+    ServiceUnavailable(#[source] anyhow::Error),
     // `from` derives an implementation of From for the type
     // this field is also used as error `source`. this denotes what should be returned as root cause
     #[error(transparent)]
     DuplicateRequestConflict(#[from] IdempotencyError),
+    #[error(transparent)]
+    IdempotencyKeyReused(IdempotencyError),
     #[error("Farm not found.")]
     NotFound,
 }
+/// Enforced by the `farms` table, backing `Canton::parse`.
+const CANTON_CONSTRAINT: &str = "farms_canton_valid";
+
+// Dependency outages are told apart from genuine bugs by walking the error chain.
+impl From<anyhow::Error> for FarmError {
+    fn from(e: anyhow::Error) -> Self {
+        if is_dependency_unavailable(e.as_ref()) {
+            Self::ServiceUnavailable(e)
+        } else if violated_constraint(e.as_ref()) == Some(CANTON_CONSTRAINT) {
+            tracing::warn!(error = ?e, "A farm with an invalid canton reached the database.");
+            Self::Unprocessable("Invalid canton code.".to_string())
+        } else {
+            Self::UnexpectedError(e)
+        }
+    }
+}
+/// An unsupported `?lang=`, answered like any other invalid query parameter.
+impl From<LanguageError> for FarmError {
+    fn from(e: LanguageError) -> Self {
+        Self::InvalidField {
+            field: "lang".into(),
+            message: e.to_string(),
+        }
+    }
+}
+
+impl FarmError {
+    /// A body that is JSON but not a farm: naming the field at `path` when
+    /// there is one, so the client knows which value to fix.
+    pub fn invalid_json(e: &serde_json::Error, path: Option<&str>) -> Self {
+        match describe_json_error(e, path) {
+            (Some(field), message) => Self::InvalidField {
+                field: field.into(),
+                message,
+            },
+            (None, message) => Self::ValidationError(message),
+        }
+    }
+
+    /// A stable, machine-readable code for the variant, so clients can branch
+    /// on it rather than on the status or the message.
+    pub fn error_code(&self) -> &'static str {
+        match self {
+            Self::ValidationError(_) | Self::InvalidField { .. } | Self::RejectedPayload { .. } => {
+                "VALIDATION_ERROR"
+            }
+            Self::Unprocessable(_) | Self::UnprocessableField { .. } => "UNPROCESSABLE",
+            Self::UnexpectedError(_) => "INTERNAL_ERROR",
+            Self::ServiceUnavailable(_) => "SERVICE_UNAVAILABLE",
+            Self::DuplicateRequestConflict(_) => "IDEMPOTENCY_CONFLICT",
+            Self::IdempotencyKeyReused(_) => "IDEMPOTENCY_KEY_REUSED",
+            Self::NotFound => "NOT_FOUND",
+        }
+    }
+
+    /// The `Retry-After` a `409` for a key in flight carries, in seconds:
+    /// `cap`, or what the claim on the key has left when that is sooner. A
+    /// `503` gets its own from `startup::render_error`, which knows
+    /// `application.retry_after_seconds`.
+    pub fn retry_after_seconds(&self, cap: u64) -> Option<u64> {
+        match self {
+            Self::DuplicateRequestConflict(IdempotencyError::KeyInFlight { expires_in }) => {
+                Some(in_flight_retry_after_seconds(*expires_in, cap))
+            }
+            // The claim expired before its remaining time was read.
+            Self::DuplicateRequestConflict(_) => {
+                Some(in_flight_retry_after_seconds(Duration::ZERO, cap))
+            }
+            _ => None,
+        }
+    }
+
+    /// The request field or query parameter the error is about, if any.
+    pub fn field(&self) -> Option<&str> {
+        match self {
+            Self::InvalidField { field, .. } | Self::UnprocessableField { field, .. } => {
+                Some(field)
+            }
+            _ => None,
+        }
+    }
+
+    /// The JSON body of the error's response, with as many keys as
+    /// `verbosity` asks for. The app fills in `request_id` on the way out
+    /// (see `startup::render_error`).
+    pub fn error_body(
+        &self,
+        verbosity: ErrorVerbosity,
+        request_id: Option<String>,
+    ) -> FarmErrorBody {
+        let error_code = self.error_code();
+        match verbosity {
+            ErrorVerbosity::Minimal => FarmErrorBody::Minimal { error_code },
+            ErrorVerbosity::Standard => FarmErrorBody::Standard {
+                error_code,
+                message: self.to_string(),
+            },
+            ErrorVerbosity::Verbose => FarmErrorBody::Verbose {
+                error_code,
+                message: self.to_string(),
+                field: self.field().map(str::to_string),
+                request_id,
+            },
+        }
+    }
+}
+
+/// `{"error_code": "NOT_FOUND", "message": "Farm not found.", "field": null,
+/// "request_id": ".."}` at `verbose`, fewer keys below (see `ErrorVerbosity`).
+#[derive(Debug, serde::Serialize)]
+#[serde(untagged)]
+pub enum FarmErrorBody {
+    Minimal {
+        error_code: &'static str,
+    },
+    Standard {
+        error_code: &'static str,
+        message: String,
+    },
+    Verbose {
+        error_code: &'static str,
+        message: String,
+        field: Option<String>,
+        request_id: Option<String>,
+    },
+    /// A `500` whose cause is hidden: at every verbosity it still names the
+    /// request id, the only way to find the error in the logs.
+    Hidden {
+        error_code: &'static str,
+        message: &'static str,
+        request_id: Option<String>,
+    },
+}
+
+impl FarmErrorBody {
+    /// The body of a `500` with `application.hide_internal_errors` set.
+    pub fn hidden(error_code: &'static str, request_id: Option<String>) -> Self {
+        Self::Hidden {
+            error_code,
+            message: "Internal error.",
+            request_id,
+        }
+    }
+}
+
 impl ResponseError for FarmError {
     fn status_code(&self) -> StatusCode {
         match self {
-            Self::ValidationError(_) => StatusCode::BAD_REQUEST,
+            Self::ValidationError(_) | Self::InvalidField { .. } => StatusCode::BAD_REQUEST,
+            Self::RejectedPayload { status, .. } => *status,
+            Self::Unprocessable(_) | Self::UnprocessableField { .. } => {
+                StatusCode::UNPROCESSABLE_ENTITY
+            }
             Self::UnexpectedError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::ServiceUnavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
             Self::DuplicateRequestConflict(_) => StatusCode::CONFLICT,
+            Self::IdempotencyKeyReused(_) => StatusCode::UNPROCESSABLE_ENTITY,
             Self::NotFound => StatusCode::NOT_FOUND,
         }
     }
+
+    fn error_response(&self) -> HttpResponse {
+        let mut response = HttpResponse::build(self.status_code());
+        if let Some(seconds) = self.retry_after_seconds(IN_FLIGHT_RETRY_AFTER_SECONDS) {
+            response.insert_header((header::RETRY_AFTER, seconds.to_string()));
+        }
+        let body = serde_json::to_string(&self.error_body(ErrorVerbosity::default(), None))
+            .expect("Error bodies always serialize.");
+        response.content_type(JSON_CONTENT_TYPE).body(body)
+    }
 }
 impl std::fmt::Debug for FarmError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         error_chain_fmt(self, f)
     }
 }
+
+/// `expires_in` rounded up to whole seconds, between 1 and `cap`.
+fn in_flight_retry_after_seconds(expires_in: Duration, cap: u64) -> u64 {
+    let seconds = expires_in.as_secs() + u64::from(expires_in.subsec_nanos() > 0);
+    seconds.min(cap).max(1)
+}
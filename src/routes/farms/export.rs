@@ -0,0 +1,115 @@
+use crate::{
+    domain::farm::{Address, Canton, Certification, Name, Point, PostalCode},
+    routes::{JSON_CONTENT_TYPE, farms::FarmError},
+};
+use actix_web::{HttpResponse, web, web::Bytes};
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use tokio::sync::mpsc;
+use tokio_stream::{StreamExt, wrappers::ReceiverStream};
+use uuid::Uuid;
+
+/// Chunks buffered between the database cursor and the response body. Once
+/// full, reading from the database waits for the client to catch up.
+const EXPORT_BUFFER_CHUNKS: usize = 32;
+
+/// A farm as written to `GET /farms/export.json`: its own columns plus the
+/// slugs of its direct categories and products.
+#[derive(serde::Serialize)]
+pub struct FarmExportRow {
+    pub id: Uuid,
+    pub name: Name,
+    pub address: Address,
+    pub postal_code: Option<PostalCode>,
+    pub canton: Canton,
+    pub coordinates: Point,
+    pub certifications: Vec<Certification>,
+    pub categories: Vec<String>,
+    pub products: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    /// `null` until the farm is first edited; always present, never omitted.
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// `GET /farms/export.json` — every farm as a single JSON array.
+///
+/// Rows are streamed from a database cursor and written out one by one, so
+/// memory stays bounded however many farms there are. A database error
+/// part-way through aborts the response, leaving the client with a
+/// truncated (invalid) JSON document rather than a silently partial one.
+#[tracing::instrument(name = "Export farms", skip(pool))]
+pub async fn export(pool: web::Data<PgPool>) -> HttpResponse {
+    let (sender, receiver) = mpsc::channel(EXPORT_BUFFER_CHUNKS);
+    let pool = pool.get_ref().clone();
+
+    tokio::spawn(async move {
+        if let Err(e) = write_export(&pool, &sender).await {
+            tracing::error!(error = ?e, "Farm export failed.");
+            // The client may already be gone; nothing left to tell it then.
+            let _ = sender.send(Err(e)).await;
+        }
+    });
+
+    HttpResponse::Ok()
+        .content_type(JSON_CONTENT_TYPE)
+        .streaming(ReceiverStream::new(receiver))
+}
+
+/// Sends the export as `[`, comma-separated rows and `]`. Stops early,
+/// without error, once the client disconnects.
+async fn write_export(
+    pool: &PgPool,
+    sender: &mpsc::Sender<Result<Bytes, FarmError>>,
+) -> Result<(), FarmError> {
+    let mut rows = sqlx::query_as!(
+        FarmExportRow,
+        r#"
+        SELECT
+            f.id,
+            f.name        AS "name: Name",
+            f.address     AS "address: Address",
+            f.postal_code AS "postal_code: PostalCode",
+            f.canton      AS "canton: Canton",
+            f.coordinates AS "coordinates: Point",
+            COALESCE(f.certifications, '{}') AS "certifications!: Vec<Certification>",
+            ARRAY(
+                SELECT c.slug
+                FROM farm_categories fc
+                JOIN product_categories c ON c.id = fc.category_id
+                WHERE fc.farm_id = f.id
+                ORDER BY c.slug
+            ) AS "categories!",
+            ARRAY(
+                SELECT p.slug
+                FROM farm_products fp
+                JOIN products p ON p.id = fp.product_id
+                WHERE fp.farm_id = f.id
+                ORDER BY p.slug
+            ) AS "products!",
+            f.created_at,
+            f.updated_at
+        FROM farms f
+        ORDER BY f.created_at, f.id
+        "#,
+    )
+    .fetch(pool);
+
+    if sender.send(Ok(Bytes::from_static(b"["))).await.is_err() {
+        return Ok(());
+    }
+
+    let mut separator: &[u8] = b"";
+    while let Some(row) = rows.next().await {
+        let row = row.context("Failed to read a farm for the export.")?;
+        let mut chunk = separator.to_vec();
+        serde_json::to_writer(&mut chunk, &row).context("Failed to serialize an exported farm.")?;
+        if sender.send(Ok(Bytes::from(chunk))).await.is_err() {
+            return Ok(());
+        }
+        separator = b",";
+    }
+
+    let _ = sender.send(Ok(Bytes::from_static(b"]"))).await;
+    Ok(())
+}
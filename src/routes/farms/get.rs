@@ -1,17 +1,43 @@
 use crate::{
-    domain::farm::{Address, Canton, Name, Point, StockStatus},
-    routes::farms::{FarmError, FarmListResponse, FarmResponse, FarmRow, ProductDto},
+    configuration::SharedSettings,
+    db::StatementCounter,
+    domain::farm::{
+        Address, Canton, CantonNames, Certification, Name, Point, PostalCode, StockStatus,
+    },
+    i18n::{Language, LanguageError, PreferredLanguage},
+    routes::{
+        JSON_CONTENT_TYPE,
+        farms::{
+            FarmCountResponse, FarmError, FarmListResponse, FarmNeighborsResponse,
+            FarmPageEnvelope, FarmResponse, FarmRow, FarmWithDistance, PageMeta, ProductDto,
+            cache::ListCache,
+        },
+    },
     taxonomy::TaxonomySnapshot,
 };
 use actix_web::{HttpResponse, web};
 use anyhow::Context;
+use chrono::{DateTime, Utc};
+use deadpool_redis::Pool;
 use sqlx::PgPool;
 use std::collections::HashMap;
 use uuid::Uuid;
 
 #[derive(Debug, serde::Deserialize)]
 pub struct FarmPath {
-    id: String,
+    pub(super) id: String,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct FarmDetailQuery {
+    /// Round coordinates to roughly 1 km (see `FarmResponse::obfuscate_location`).
+    #[serde(default)]
+    pub obfuscate: bool,
+    /// Decimal places to round the returned coordinates to, 0 to
+    /// `MAX_COORD_PRECISION`.
+    pub coord_precision: Option<u8>,
+    /// Comma-separated farm fields to return, e.g. `?fields=id,name,canton`.
+    pub fields: Option<String>,
 }
 
 #[derive(Debug, serde::Deserialize)]
@@ -21,12 +47,27 @@ pub struct FarmListQuery {
     pub category: Option<String>,
     /// Comma-separated product slugs, e.g. `?product=strawberries,cherries`.
     pub product: Option<String>,
-    /// `"all"` requires every product; anything else (or absent) means "any of".
+    /// `"all"` requires every listed category and every listed product;
+    /// anything else (or absent) means "any of".
     pub r#match: Option<String>,
     /// Comma-separated canton codes, e.g. `?canton=ZH,BE`.
     pub canton: Option<String>,
+    /// Four-digit Swiss postal code, e.g. `?postal_code=8001`.
+    pub postal_code: Option<String>,
     /// Free-text query matched against farm name, address and product names.
     pub q: Option<String>,
+    /// Comma-separated certification codes, e.g. `?certification=bio_suisse`
+    /// ("any of").
+    pub certification: Option<String>,
+    /// `true` keeps only farms missing optional data: no postal code, no
+    /// products, or no category beyond `farms.default_categories`. `false`
+    /// keeps only the others.
+    pub incomplete: Option<bool>,
+    /// Keep only farms created at or after this RFC 3339 instant, e.g.
+    /// `?created_after=2025-01-01T00:00:00Z`.
+    pub created_after: Option<DateTime<Utc>>,
+    /// Keep only farms created before this RFC 3339 instant.
+    pub created_before: Option<DateTime<Utc>>,
     /// The requester's location. When both are given, each farm carries a
     /// `distance_km`, `radius_km` can filter, and `sort=nearest` is allowed.
     pub lat: Option<f64>,
@@ -35,6 +76,17 @@ pub struct FarmListQuery {
     pub radius_km: Option<f64>,
     /// `newest` (default) | `name` | `canton` | `nearest` (needs lat/lng).
     pub sort: Option<String>,
+    /// Round coordinates to roughly 1 km (see `FarmResponse::obfuscate_location`).
+    #[serde(default)]
+    pub obfuscate: bool,
+    /// Decimal places to round the returned coordinates to, 0 to
+    /// `MAX_COORD_PRECISION`.
+    pub coord_precision: Option<u8>,
+    /// Answer with `{"data": [...], "meta": {...}}` (see `FarmPageEnvelope`).
+    #[serde(default)]
+    pub envelope: bool,
+    /// Comma-separated farm fields to return, e.g. `?fields=id,name,canton`.
+    pub fields: Option<String>,
     #[serde(default = "default_limit")]
     pub limit: i64,
     #[serde(default)]
@@ -45,6 +97,38 @@ fn default_limit() -> i64 {
     20
 }
 
+/// Largest page `GET /farms` serves; larger `limit`s are clamped.
+const MAX_PAGE_SIZE: i64 = 100;
+
+/// Hard ceiling on the rows `list_farms` fetches in one query, whatever the
+/// caller asks for. A safety net behind `MAX_PAGE_SIZE`, not a page size.
+const MAX_ROWS_PER_QUERY: i64 = 1000;
+
+/// Most decimal places `?coord_precision=` accepts; 9 is well below a
+/// millimetre.
+const MAX_COORD_PRECISION: u8 = 9;
+
+#[derive(Debug, serde::Deserialize)]
+pub struct FarmCountQuery {
+    /// Comma-separated category (group) slugs, as in `FarmListQuery`.
+    pub category: Option<String>,
+    /// Comma-separated canton codes, as in `FarmListQuery`.
+    pub canton: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct FarmNeighborsQuery {
+    /// How many neighbors to return, 1 to `MAX_NEIGHBORS`.
+    #[serde(default = "default_neighbors_limit")]
+    pub limit: i64,
+}
+
+const MAX_NEIGHBORS: i64 = 20;
+
+fn default_neighbors_limit() -> i64 {
+    5
+}
+
 /// Escape LIKE/ILIKE wildcards in user input so `%` and `_` are literal.
 fn escape_like(input: &str) -> String {
     let mut out = String::with_capacity(input.len() + 2);
@@ -57,13 +141,24 @@ fn escape_like(input: &str) -> String {
     out
 }
 
-#[tracing::instrument(name = "List farms", skip(pool, taxonomy))]
+#[allow(clippy::too_many_arguments)]
+#[tracing::instrument(
+    name = "List farms",
+    skip(pool, redis_pool, taxonomy, canton_names, configuration, statements)
+)]
 pub async fn get_all(
     query: web::Query<FarmListQuery>,
+    language: Result<PreferredLanguage, LanguageError>,
+    statements: StatementCounter,
     pool: web::Data<PgPool>,
+    redis_pool: web::Data<Pool>,
     taxonomy: web::Data<TaxonomySnapshot>,
+    canton_names: web::Data<CantonNames>,
+    configuration: web::Data<SharedSettings>,
 ) -> Result<HttpResponse, FarmError> {
-    let limit = query.limit.clamp(1, 100);
+    let language = language?;
+    let configuration = configuration.load_full();
+    let limit = query.limit.clamp(1, MAX_PAGE_SIZE);
     let offset = query.offset.max(0);
     let sort = query.sort.as_deref().unwrap_or("newest");
 
@@ -78,14 +173,28 @@ pub async fn get_all(
 
     let match_all = query.r#match.as_deref() == Some("all");
 
-    let canton_codes: Vec<String> = query
-        .canton
-        .as_deref()
-        .unwrap_or("")
-        .split(',')
-        .map(|c| c.trim().to_uppercase())
-        .filter(|c| !c.is_empty())
+    let canton_codes = parse_canton_codes(query.canton.as_deref())?;
+
+    let postal_code = query
+        .postal_code
+        .clone()
+        .map(PostalCode::parse)
+        .transpose()
+        .map_err(|e| FarmError::InvalidField {
+            field: "postal_code".into(),
+            message: e.to_string(),
+        })?;
+
+    let certifications = parse_certifications(query.certification.as_deref())?;
+    // Unknown default slugs are rejected at creation, so they tag no farm.
+    let default_category_ids: Vec<i16> = configuration
+        .farms
+        .default_categories
+        .iter()
+        .filter_map(|slug| taxonomy.category_id_for_slug(slug))
         .collect();
+    let fields = parse_fields(query.fields.as_deref())?;
+    let coord_precision = parse_coord_precision(query.coord_precision)?;
 
     let q_pattern = query
         .q
@@ -94,6 +203,14 @@ pub async fn get_all(
         .filter(|s| !s.is_empty())
         .map(|s| format!("%{}%", escape_like(s)));
 
+    if let (Some(after), Some(before)) = (query.created_after, query.created_before)
+        && after > before
+    {
+        return Err(FarmError::ValidationError(
+            "created_after must not be later than created_before.".to_string(),
+        ));
+    }
+
     // Distance-dependent features need a location.
     if (sort == "nearest" || query.radius_km.is_some())
         && (query.lat.is_none() || query.lng.is_none())
@@ -103,24 +220,80 @@ pub async fn get_all(
         ));
     }
 
-    let farms = list_farms(
-        &pool,
-        ListParams {
-            category_ids: &category_ids,
-            product_ids: &product_ids,
-            match_all,
-            canton_codes: &canton_codes,
-            q_pattern: q_pattern.as_deref(),
-            lat: query.lat,
-            lng: query.lng,
-            radius_km: query.radius_km,
-            sort,
-            limit,
-            offset,
-        },
-    )
-    .await?;
+    let params = ListParams {
+        category_ids: &category_ids,
+        product_ids: &product_ids,
+        match_all,
+        canton_codes: &canton_codes,
+        postal_code,
+        certifications: &certifications,
+        incomplete: query.incomplete,
+        default_category_ids: &default_category_ids,
+        created_after: query.created_after,
+        created_before: query.created_before,
+        q_pattern: q_pattern.as_deref(),
+        lat: query.lat,
+        lng: query.lng,
+        radius_km: query.radius_km,
+        sort,
+        limit,
+        offset,
+        language: language.0,
+    };
 
+    // Everything the body depends on, normalized by the parsing above.
+    let cache_query = format!(
+        "{params:?} obfuscate={} coord_precision={coord_precision:?} envelope={} fields={fields:?}",
+        query.obfuscate, query.envelope
+    );
+    let cache = ListCache::new(&redis_pool, &configuration.farms.list_cache);
+    // Read before the rows are, so the page is never cached as newer than it is.
+    let mut cache_generation = None;
+    if let Some(cache) = &cache {
+        let (body, generation) = cache.get(&cache_query).await;
+        if let Some(body) = body {
+            return Ok(json_response(body, Some("HIT")));
+        }
+        cache_generation = generation;
+    }
+
+    let farms = match fields.as_deref() {
+        Some(fields) if FarmSummary::covers(fields) => {
+            let mut farms = list_farm_summaries(&pool, &statements, &canton_names, &params).await?;
+            if query.obfuscate {
+                farms.iter_mut().for_each(FarmSummary::obfuscate_location);
+            }
+            ListedFarms::Selected(select_each(&farms, fields)?)
+        }
+        fields => {
+            let relations = FarmRelations::for_fields(fields);
+            let mut farms =
+                list_farms(&pool, &statements, &canton_names, &params, relations).await?;
+            if query.obfuscate {
+                farms.iter_mut().for_each(FarmResponse::obfuscate_location);
+            }
+            if let Some(digits) = coord_precision {
+                farms
+                    .iter_mut()
+                    .for_each(|farm| farm.round_coordinates(digits));
+            }
+            match fields {
+                Some(fields) => ListedFarms::Selected(select_each(&farms, fields)?),
+                None => ListedFarms::Full(farms),
+            }
+        }
+    };
+    let total = if query.envelope {
+        Some(count_listed_farms(&pool, &statements, &params).await?)
+    } else {
+        None
+    };
+
+    let meta = total.map(|total| PageMeta {
+        page: offset / limit + 1,
+        per_page: limit,
+        total,
+    });
     // A full page implies there may be more; hand back the next offset.
     let next_cursor = if farms.len() as i64 == limit {
         Some((offset + limit).to_string())
@@ -128,7 +301,170 @@ pub async fn get_all(
         None
     };
 
-    Ok(HttpResponse::Ok().json(FarmListResponse { farms, next_cursor }))
+    let body = match farms {
+        ListedFarms::Selected(farms) => page_body(farms, meta, next_cursor)?,
+        ListedFarms::Full(farms) => page_body(farms, meta, next_cursor)?,
+    };
+    if let Some(cache) = &cache
+        && let Some(generation) = &cache_generation
+    {
+        cache.put(generation, &cache_query, &body).await;
+    }
+
+    Ok(json_response(body, cache.is_some().then_some("MISS")))
+}
+
+/// A page as `FarmPageEnvelope` when `meta` is given, else `FarmListResponse`.
+fn page_body<T: serde::Serialize>(
+    farms: Vec<T>,
+    meta: Option<PageMeta>,
+    next_cursor: Option<String>,
+) -> Result<Vec<u8>, FarmError> {
+    let body = match meta {
+        Some(meta) => serde_json::to_vec(&FarmPageEnvelope { data: farms, meta }),
+        None => serde_json::to_vec(&FarmListResponse { farms, next_cursor }),
+    };
+    Ok(body.context("Failed to serialize a page of farms.")?)
+}
+
+/// A `200` with a JSON `body`, plus `X-Cache` when the list cache is enabled.
+fn json_response(body: Vec<u8>, cache_status: Option<&'static str>) -> HttpResponse {
+    let mut response = HttpResponse::Ok();
+    response.content_type(JSON_CONTENT_TYPE);
+    if let Some(cache_status) = cache_status {
+        response.insert_header(("X-Cache", cache_status));
+    }
+    response.body(body)
+}
+
+/// `?coord_precision=`, 400 above `MAX_COORD_PRECISION`.
+fn parse_coord_precision(raw: Option<u8>) -> Result<Option<u8>, FarmError> {
+    match raw {
+        Some(digits) if digits > MAX_COORD_PRECISION => Err(FarmError::InvalidField {
+            field: "coord_precision".into(),
+            message: format!("coord_precision must be between 0 and {MAX_COORD_PRECISION}."),
+        }),
+        _ => Ok(raw),
+    }
+}
+
+/// Parse a comma-separated `?fields=` list, 400 on a name not in
+/// `FarmResponse::FIELDS`. `id` is always selected; `None` means every field.
+fn parse_fields(raw: Option<&str>) -> Result<Option<Vec<&'static str>>, FarmError> {
+    let Some(raw) = raw.filter(|raw| !raw.trim().is_empty()) else {
+        return Ok(None);
+    };
+    let mut fields = vec!["id"];
+    for name in raw.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        let field = FarmResponse::FIELDS
+            .into_iter()
+            .find(|field| *field == name)
+            .ok_or_else(|| FarmError::InvalidField {
+                field: "fields".into(),
+                message: format!("Unknown field '{name}'."),
+            })?;
+        if !fields.contains(&field) {
+            fields.push(field);
+        }
+    }
+    Ok(Some(fields))
+}
+
+/// `farm` as a JSON object holding only `fields`.
+fn select_fields<T: serde::Serialize>(
+    farm: &T,
+    fields: &[&str],
+) -> Result<serde_json::Value, FarmError> {
+    let mut value = serde_json::to_value(farm).context("Failed to serialize a farm.")?;
+    if let serde_json::Value::Object(map) = &mut value {
+        map.retain(|key, _| fields.contains(&key.as_str()));
+    }
+    Ok(value)
+}
+
+/// `select_fields` applied to every farm of a page.
+fn select_each<T: serde::Serialize>(
+    farms: &[T],
+    fields: &[&str],
+) -> Result<Vec<serde_json::Value>, FarmError> {
+    farms
+        .iter()
+        .map(|farm| select_fields(farm, fields))
+        .collect()
+}
+
+/// `GET /farms/count` — how many farms match the optional `category` and
+/// `canton` filters, without loading any of them.
+#[tracing::instrument(name = "Count farms", skip(statements, pool, taxonomy))]
+pub async fn count(
+    query: web::Query<FarmCountQuery>,
+    statements: StatementCounter,
+    pool: web::Data<PgPool>,
+    taxonomy: web::Data<TaxonomySnapshot>,
+) -> Result<HttpResponse, FarmError> {
+    let category_ids = resolve_slugs(
+        &query.category,
+        |slug| taxonomy.category_id_for_slug(slug),
+        "category",
+    )?;
+    let canton_codes = parse_canton_codes(query.canton.as_deref())?;
+
+    let count = sqlx::query_scalar!(
+        r#"
+        SELECT count(*) AS "count!"
+        FROM farms f
+        WHERE
+            (
+                cardinality($1::int2[]) = 0
+                OR f.id IN (
+                    SELECT fc.farm_id FROM farm_categories fc WHERE fc.category_id = ANY($1)
+                    UNION
+                    SELECT fp.farm_id FROM farm_products fp
+                        JOIN products p ON p.id = fp.product_id
+                        WHERE p.category_id = ANY($1)
+                )
+            )
+            AND (cardinality($2::text[]) = 0 OR f.canton = ANY($2))
+        "#,
+        &category_ids,
+        &canton_codes,
+    )
+    .fetch_one(statements.on(pool.get_ref()))
+    .await
+    .context("Failed to count farms.")?;
+
+    Ok(HttpResponse::Ok().json(FarmCountResponse { count }))
+}
+
+/// Parse a comma-separated canton list into uppercased codes, 400 naming the
+/// first entry that is not a canton.
+fn parse_canton_codes(raw: Option<&str>) -> Result<Vec<String>, FarmError> {
+    raw.unwrap_or("")
+        .split(',')
+        .filter(|code| !code.trim().is_empty())
+        .map(|code| {
+            Canton::parse(code.to_string())
+                .map(|canton| canton.as_str().to_string())
+                .map_err(|e| FarmError::InvalidField {
+                    field: "canton".into(),
+                    message: e.to_string(),
+                })
+        })
+        .collect()
+}
+
+/// Parse a comma-separated certification list, 400 on an unknown code.
+fn parse_certifications(raw: Option<&str>) -> Result<Vec<Certification>, FarmError> {
+    let mut certifications = raw
+        .unwrap_or("")
+        .split(',')
+        .filter(|code| !code.trim().is_empty())
+        .map(|code| Certification::parse(code.to_string()))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| FarmError::ValidationError(e.to_string()))?;
+    certifications.sort_unstable();
+    certifications.dedup();
+    Ok(certifications)
 }
 
 /// Resolve a comma-separated slug list to ids via `resolver`, 400 on unknown.
@@ -153,11 +489,18 @@ fn resolve_slugs<T: Ord>(
     Ok(ids)
 }
 
+#[derive(Debug)]
 struct ListParams<'a> {
     category_ids: &'a [i16],
     product_ids: &'a [i32],
     match_all: bool,
     canton_codes: &'a [String],
+    postal_code: Option<PostalCode>,
+    certifications: &'a [Certification],
+    incomplete: Option<bool>,
+    default_category_ids: &'a [i16],
+    created_after: Option<DateTime<Utc>>,
+    created_before: Option<DateTime<Utc>>,
     q_pattern: Option<&'a str>,
     lat: Option<f64>,
     lng: Option<f64>,
@@ -165,80 +508,148 @@ struct ListParams<'a> {
     sort: &'a str,
     limit: i64,
     offset: i64,
+    language: Language,
 }
 
-#[tracing::instrument(name = "Query farms page", skip(pool, params), fields(sort = params.sort))]
-async fn list_farms(pool: &PgPool, params: ListParams<'_>) -> Result<Vec<FarmResponse>, FarmError> {
-    // A page of farms. Filters: category (group directly OR via a product in
-    // it), product (granular, any/all), canton, and free-text q over name /
-    // address / product names. `distance_km` (great-circle) is computed once in
-    // the CTE and reused for the radius filter and `sort=nearest`. Offset
-    // pagination keeps every sort (newest/name/canton/nearest) uniform.
+/// The per-farm lists a page loads, one query each. `?fields=` leaving them
+/// out skips the queries; the lists are then empty in the response.
+#[derive(Debug, Clone, Copy)]
+struct FarmRelations {
+    direct_categories: bool,
+    products: bool,
+}
+
+impl FarmRelations {
+    const ALL: Self = Self {
+        direct_categories: true,
+        products: true,
+    };
+
+    /// What `fields` (`None` for every field) needs: `categories` is derived
+    /// from the direct categories and the products' groups.
+    fn for_fields(fields: Option<&[&str]>) -> Self {
+        let Some(fields) = fields else {
+            return Self::ALL;
+        };
+        let categories = fields.contains(&"categories");
+        Self {
+            direct_categories: categories,
+            products: categories || fields.contains(&"products"),
+        }
+    }
+}
+
+/// A page of `GET /farms`, whole or cut down to `?fields=`.
+enum ListedFarms {
+    Full(Vec<FarmResponse>),
+    Selected(Vec<serde_json::Value>),
+}
+
+impl ListedFarms {
+    fn len(&self) -> usize {
+        match self {
+            Self::Full(farms) => farms.len(),
+            Self::Selected(farms) => farms.len(),
+        }
+    }
+}
+
+/// The columns of a farm `?fields=` can ask for without its coordinates or
+/// its categories and products, read by `list_farm_summaries` so a page like
+/// `?fields=id,name` neither fetches nor decodes the rest.
+#[derive(serde::Serialize)]
+struct FarmSummary {
+    id: Uuid,
+    name: Name,
+    address: Address,
+    postal_code: Option<PostalCode>,
+    canton: Canton,
+    canton_name: String,
+    certifications: Vec<Certification>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    distance_km: Option<f64>,
+    created_at: DateTime<Utc>,
+    updated_at: Option<DateTime<Utc>>,
+}
+
+impl FarmSummary {
+    const FIELDS: [&'static str; 10] = [
+        "id",
+        "name",
+        "address",
+        "postal_code",
+        "canton",
+        "canton_name",
+        "certifications",
+        "distance_km",
+        "created_at",
+        "updated_at",
+    ];
+
+    /// Whether every one of `fields` is a summary field.
+    fn covers(fields: &[&str]) -> bool {
+        fields.iter().all(|field| Self::FIELDS.contains(field))
+    }
+
+    /// As `FarmResponse::obfuscate_location`, which leaves only the distance
+    /// to blur here.
+    fn obfuscate_location(&mut self) {
+        self.distance_km = self.distance_km.map(f64::round);
+    }
+}
+
+/// `requested` rows, at most `MAX_ROWS_PER_QUERY`.
+fn capped_limit(requested: i64) -> i64 {
+    if requested > MAX_ROWS_PER_QUERY {
+        tracing::warn!(
+            requested,
+            cap = MAX_ROWS_PER_QUERY,
+            "Farm query limit exceeds the row cap; capping it."
+        );
+    }
+    requested.min(MAX_ROWS_PER_QUERY)
+}
+
+/// A page of farms.
+#[tracing::instrument(
+    name = "Query farms page",
+    skip(pool, statements, canton_names, params),
+    fields(sort = params.sort)
+)]
+async fn list_farms(
+    pool: &PgPool,
+    statements: &StatementCounter,
+    canton_names: &CantonNames,
+    params: &ListParams<'_>,
+    relations: FarmRelations,
+) -> Result<Vec<FarmResponse>, FarmError> {
+    let limit = capped_limit(params.limit);
+
+    // A page of the farms `listed_farms` lets through; the function holds
+    // every filter (see its migration) and the great-circle `distance_km`,
+    // reused here for `sort=nearest`. Offset pagination keeps every sort
+    // (newest/name/canton/nearest) uniform.
     let farm_rows = sqlx::query!(
         r#"
-        WITH base AS (
-            SELECT
-                f.id, f.name, f.address, f.canton, f.coordinates,
-                f.created_at, f.updated_at,
-                CASE
-                    WHEN $6::float8 IS NULL OR $7::float8 IS NULL THEN NULL
-                    ELSE 6371.0 * acos(least(1, greatest(-1,
-                        sin(radians($6)) * sin(radians(f.coordinates[1]))
-                      + cos(radians($6)) * cos(radians(f.coordinates[1]))
-                        * cos(radians(f.coordinates[0] - $7))
-                    )))
-                END AS distance_km
-            FROM farms f
-        )
         SELECT
             f.id,
             f.name        AS "name: Name",
             f.address     AS "address: Address",
+            f.postal_code AS "postal_code: PostalCode",
             f.canton      AS "canton: Canton",
             f.coordinates AS "coordinates: Point",
+            COALESCE(f.certifications, '{}') AS "certifications!: Vec<Certification>",
             f.created_at,
             f.updated_at,
-            f.distance_km AS "distance_km?"
-        FROM base f
-        WHERE
-            (
-                cardinality($1::int2[]) = 0
-                OR f.id IN (
-                    SELECT fc.farm_id FROM farm_categories fc WHERE fc.category_id = ANY($1)
-                    UNION
-                    SELECT fp.farm_id FROM farm_products fp
-                        JOIN products p ON p.id = fp.product_id
-                        WHERE p.category_id = ANY($1)
-                )
-            )
-            AND (
-                cardinality($2::int[]) = 0
-                OR f.id IN (
-                    SELECT fp.farm_id FROM farm_products fp
-                    WHERE fp.product_id = ANY($2)
-                    GROUP BY fp.farm_id
-                    HAVING $3 = false OR count(DISTINCT fp.product_id) = cardinality($2)
-                )
-            )
-            AND (cardinality($4::text[]) = 0 OR f.canton = ANY($4))
-            AND (
-                $5::text IS NULL
-                OR f.name ILIKE $5
-                OR f.address ILIKE $5
-                OR EXISTS (
-                    SELECT 1 FROM farm_products fpq
-                    JOIN products pq ON pq.id = fpq.product_id
-                    WHERE fpq.farm_id = f.id
-                      AND (pq.key_de ILIKE $5 OR pq.name_en ILIKE $5)
-                )
-            )
-            AND ($8::float8 IS NULL OR (f.distance_km IS NOT NULL AND f.distance_km <= $8))
+            m.distance_km AS "distance_km?"
+        FROM listed_farms($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14) m
+        JOIN farms f ON f.id = m.farm_id
         ORDER BY
-            CASE WHEN $9 = 'nearest' THEN f.distance_km END ASC NULLS LAST,
-            CASE WHEN $9 = 'name' THEN f.name END ASC,
-            CASE WHEN $9 = 'canton' THEN f.canton END ASC,
+            CASE WHEN $15 = 'nearest' THEN m.distance_km END ASC NULLS LAST,
+            CASE WHEN $15 = 'name' THEN f.name END ASC,
+            CASE WHEN $15 = 'canton' THEN f.canton END ASC,
             f.created_at DESC, f.id DESC
-        LIMIT $10 OFFSET $11
+        LIMIT $16 OFFSET $17
         "#,
         params.category_ids,
         params.product_ids,
@@ -248,17 +659,31 @@ async fn list_farms(pool: &PgPool, params: ListParams<'_>) -> Result<Vec<FarmRes
         params.lat,
         params.lng,
         params.radius_km,
+        params.postal_code as Option<PostalCode>,
+        params.certifications as &[Certification],
+        params.incomplete,
+        params.default_category_ids,
+        params.created_after,
+        params.created_before,
         params.sort,
-        params.limit,
+        limit,
         params.offset,
     )
-    .fetch_all(pool)
+    .fetch_all(statements.on(pool))
     .await
     .context("Failed to page farms.")?;
 
     let farm_ids: Vec<Uuid> = farm_rows.iter().map(|f| f.id).collect();
-    let direct_categories_by_farm = load_direct_categories(pool, &farm_ids).await?;
-    let mut products_by_farm = load_products(pool, &farm_ids).await?;
+    let direct_categories_by_farm = if relations.direct_categories {
+        load_direct_categories(pool, statements, &farm_ids).await?
+    } else {
+        HashMap::new()
+    };
+    let mut products_by_farm = if relations.products {
+        load_products(pool, statements, &farm_ids).await?
+    } else {
+        HashMap::new()
+    };
 
     let mut responses = Vec::with_capacity(farm_rows.len());
     for farm in farm_rows {
@@ -272,10 +697,16 @@ async fn list_farms(pool: &PgPool, params: ListParams<'_>) -> Result<Vec<FarmRes
             id: farm.id,
             name: farm.name,
             address: farm.address,
+            postal_code: farm.postal_code,
+            canton_name: farm
+                .canton
+                .name_in(canton_names, params.language)
+                .to_string(),
             canton: farm.canton,
             coordinates: farm.coordinates,
             categories,
             products,
+            certifications: farm.certifications,
             distance_km: farm.distance_km,
             created_at: farm.created_at,
             updated_at: farm.updated_at,
@@ -285,9 +716,123 @@ async fn list_farms(pool: &PgPool, params: ListParams<'_>) -> Result<Vec<FarmRes
     Ok(responses)
 }
 
+/// A page of farms as `FarmSummary`s: the page `list_farms` returns, without
+/// the coordinates or the per-farm lists.
+#[tracing::instrument(
+    name = "Query farm summaries page",
+    skip(pool, statements, canton_names, params),
+    fields(sort = params.sort)
+)]
+async fn list_farm_summaries(
+    pool: &PgPool,
+    statements: &StatementCounter,
+    canton_names: &CantonNames,
+    params: &ListParams<'_>,
+) -> Result<Vec<FarmSummary>, FarmError> {
+    let limit = capped_limit(params.limit);
+    // The ordering of `list_farms`.
+    let rows = sqlx::query!(
+        r#"
+        SELECT
+            f.id,
+            f.name        AS "name: Name",
+            f.address     AS "address: Address",
+            f.postal_code AS "postal_code: PostalCode",
+            f.canton      AS "canton: Canton",
+            COALESCE(f.certifications, '{}') AS "certifications!: Vec<Certification>",
+            f.created_at,
+            f.updated_at,
+            m.distance_km AS "distance_km?"
+        FROM listed_farms($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14) m
+        JOIN farms f ON f.id = m.farm_id
+        ORDER BY
+            CASE WHEN $15 = 'nearest' THEN m.distance_km END ASC NULLS LAST,
+            CASE WHEN $15 = 'name' THEN f.name END ASC,
+            CASE WHEN $15 = 'canton' THEN f.canton END ASC,
+            f.created_at DESC, f.id DESC
+        LIMIT $16 OFFSET $17
+        "#,
+        params.category_ids,
+        params.product_ids,
+        params.match_all,
+        params.canton_codes,
+        params.q_pattern,
+        params.lat,
+        params.lng,
+        params.radius_km,
+        params.postal_code as Option<PostalCode>,
+        params.certifications as &[Certification],
+        params.incomplete,
+        params.default_category_ids,
+        params.created_after,
+        params.created_before,
+        params.sort,
+        limit,
+        params.offset,
+    )
+    .fetch_all(statements.on(pool))
+    .await
+    .context("Failed to page farm summaries.")?;
+
+    Ok(rows
+        .into_iter()
+        .map(|farm| FarmSummary {
+            id: farm.id,
+            name: farm.name,
+            address: farm.address,
+            postal_code: farm.postal_code,
+            canton_name: farm
+                .canton
+                .name_in(canton_names, params.language)
+                .to_string(),
+            canton: farm.canton,
+            certifications: farm.certifications,
+            distance_km: farm.distance_km,
+            created_at: farm.created_at,
+            updated_at: farm.updated_at,
+        })
+        .collect())
+}
+
+/// How many farms `listed_farms` lets through across all pages, for
+/// `?envelope=true`. A query of its own, so plain pages don't pay for it.
+#[tracing::instrument(name = "Count farms pages", skip(pool, statements, params))]
+async fn count_listed_farms(
+    pool: &PgPool,
+    statements: &StatementCounter,
+    params: &ListParams<'_>,
+) -> Result<i64, FarmError> {
+    let total = sqlx::query_scalar!(
+        r#"
+        SELECT count(*) AS "count!"
+        FROM listed_farms($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
+        "#,
+        params.category_ids,
+        params.product_ids,
+        params.match_all,
+        params.canton_codes,
+        params.q_pattern,
+        params.lat,
+        params.lng,
+        params.radius_km,
+        params.postal_code as Option<PostalCode>,
+        params.certifications as &[Certification],
+        params.incomplete,
+        params.default_category_ids,
+        params.created_after,
+        params.created_before,
+    )
+    .fetch_one(statements.on(pool))
+    .await
+    .context("Failed to count farms.")?;
+
+    Ok(total)
+}
+
 /// Direct group-level memberships for a page of farms (no N+1).
 async fn load_direct_categories(
     pool: &PgPool,
+    statements: &StatementCounter,
     farm_ids: &[Uuid],
 ) -> Result<HashMap<Uuid, Vec<String>>, FarmError> {
     let rows = sqlx::query!(
@@ -299,7 +844,7 @@ async fn load_direct_categories(
         "#,
         farm_ids,
     )
-    .fetch_all(pool)
+    .fetch_all(statements.on(pool))
     .await
     .context("Failed to load farm categories.")?;
 
@@ -313,6 +858,7 @@ async fn load_direct_categories(
 /// Products for a page of farms (no N+1).
 async fn load_products(
     pool: &PgPool,
+    statements: &StatementCounter,
     farm_ids: &[Uuid],
 ) -> Result<HashMap<Uuid, Vec<ProductDto>>, FarmError> {
     let rows = sqlx::query!(
@@ -333,7 +879,7 @@ async fn load_products(
         "#,
         farm_ids,
     )
-    .fetch_all(pool)
+    .fetch_all(statements.on(pool))
     .await
     .context("Failed to load farm products.")?;
 
@@ -351,22 +897,46 @@ async fn load_products(
     Ok(by_farm)
 }
 
-#[tracing::instrument(name = "Get farm by id", skip(pool))]
+#[tracing::instrument(name = "Get farm by id", skip(statements, pool, canton_names))]
 pub async fn get_by_id(
     path: web::Path<FarmPath>,
+    query: web::Query<FarmDetailQuery>,
+    language: Result<PreferredLanguage, LanguageError>,
+    statements: StatementCounter,
     pool: web::Data<PgPool>,
+    canton_names: web::Data<CantonNames>,
 ) -> Result<HttpResponse, FarmError> {
+    let language = language?;
     let farm_id = Uuid::parse_str(&path.id)
         .map_err(|_| FarmError::ValidationError("Invalid farm id.".to_string()))?;
+    let fields = parse_fields(query.fields.as_deref())?;
+    let coord_precision = parse_coord_precision(query.coord_precision)?;
 
-    match get_farm_by_id(farm_id, &pool).await? {
-        Some(farm) => Ok(HttpResponse::Ok().json(farm)),
+    match get_farm_by_id(farm_id, language.0, &pool, &statements, &canton_names).await? {
+        Some(mut farm) => {
+            if query.obfuscate {
+                farm.obfuscate_location();
+            }
+            if let Some(digits) = coord_precision {
+                farm.round_coordinates(digits);
+            }
+            match fields {
+                Some(fields) => Ok(HttpResponse::Ok().json(select_fields(&farm, &fields)?)),
+                None => Ok(HttpResponse::Ok().json(farm)),
+            }
+        }
         None => Err(FarmError::NotFound),
     }
 }
 
-#[tracing::instrument(name = "Query single farm", skip(pool))]
-async fn get_farm_by_id(farm_id: Uuid, pool: &PgPool) -> Result<Option<FarmResponse>, FarmError> {
+#[tracing::instrument(name = "Query single farm", skip(pool, statements, canton_names))]
+async fn get_farm_by_id(
+    farm_id: Uuid,
+    language: Language,
+    pool: &PgPool,
+    statements: &StatementCounter,
+    canton_names: &CantonNames,
+) -> Result<Option<FarmResponse>, FarmError> {
     let farm = sqlx::query_as!(
         FarmRow,
         r#"
@@ -374,8 +944,10 @@ async fn get_farm_by_id(farm_id: Uuid, pool: &PgPool) -> Result<Option<FarmRespo
             f.id,
             f.name        AS "name: Name",
             f.address     AS "address: Address",
+            f.postal_code AS "postal_code: PostalCode",
             f.canton      AS "canton: Canton",
             f.coordinates AS "coordinates: Point",
+            COALESCE(f.certifications, '{}') AS "certifications!: Vec<Certification>",
             f.created_at,
             f.updated_at
         FROM farms f
@@ -383,7 +955,7 @@ async fn get_farm_by_id(farm_id: Uuid, pool: &PgPool) -> Result<Option<FarmRespo
         "#,
         farm_id,
     )
-    .fetch_optional(pool)
+    .fetch_optional(statements.on(pool))
     .await
     .context("Failed to fetch farm.")?;
 
@@ -391,9 +963,9 @@ async fn get_farm_by_id(farm_id: Uuid, pool: &PgPool) -> Result<Option<FarmRespo
         return Ok(None);
     };
 
-    let mut products_by_farm = load_products(pool, &[farm.id]).await?;
+    let mut products_by_farm = load_products(pool, statements, &[farm.id]).await?;
     let products = products_by_farm.remove(&farm.id).unwrap_or_default();
-    let direct_categories_by_farm = load_direct_categories(pool, &[farm.id]).await?;
+    let direct_categories_by_farm = load_direct_categories(pool, statements, &[farm.id]).await?;
     let direct = direct_categories_by_farm
         .get(&farm.id)
         .cloned()
@@ -404,16 +976,140 @@ async fn get_farm_by_id(farm_id: Uuid, pool: &PgPool) -> Result<Option<FarmRespo
         id: farm.id,
         name: farm.name,
         address: farm.address,
+        postal_code: farm.postal_code,
+        canton_name: farm.canton.name_in(canton_names, language).to_string(),
         canton: farm.canton,
         coordinates: farm.coordinates,
         categories,
         products,
+        certifications: farm.certifications,
         distance_km: None,
         created_at: farm.created_at,
         updated_at: farm.updated_at,
     }))
 }
 
+#[tracing::instrument(name = "Get farm neighbors", skip(statements, pool, canton_names))]
+pub async fn get_neighbors(
+    path: web::Path<FarmPath>,
+    query: web::Query<FarmNeighborsQuery>,
+    language: Result<PreferredLanguage, LanguageError>,
+    statements: StatementCounter,
+    pool: web::Data<PgPool>,
+    canton_names: web::Data<CantonNames>,
+) -> Result<HttpResponse, FarmError> {
+    let language = language?;
+    let farm_id = Uuid::parse_str(&path.id)
+        .map_err(|_| FarmError::ValidationError("Invalid farm id.".to_string()))?;
+    let limit = query.limit.clamp(1, MAX_NEIGHBORS);
+
+    let origin = sqlx::query_scalar!(
+        r#"SELECT coordinates AS "coordinates: Point" FROM farms WHERE id = $1"#,
+        farm_id,
+    )
+    .fetch_optional(statements.on(pool.get_ref()))
+    .await
+    .context("Failed to fetch farm coordinates.")?
+    .ok_or(FarmError::NotFound)?;
+
+    let farms = list_neighbors(
+        &pool,
+        &statements,
+        &canton_names,
+        farm_id,
+        origin,
+        limit,
+        language.0,
+    )
+    .await?;
+    Ok(HttpResponse::Ok().json(FarmNeighborsResponse { farms }))
+}
+
+#[tracing::instrument(name = "Query farm neighbors", skip(pool, statements, canton_names))]
+async fn list_neighbors(
+    pool: &PgPool,
+    statements: &StatementCounter,
+    canton_names: &CantonNames,
+    farm_id: Uuid,
+    origin: Point,
+    limit: i64,
+    language: Language,
+) -> Result<Vec<FarmWithDistance>, FarmError> {
+    // Same great-circle distance as `list_farms`, measured from the farm's
+    // own coordinates. Ties (e.g. farms sharing a location) break by id.
+    let farm_rows = sqlx::query!(
+        r#"
+        WITH base AS (
+            SELECT
+                f.id, f.name, f.address, f.postal_code, f.canton, f.coordinates,
+                f.certifications, f.created_at, f.updated_at,
+                6371.0 * acos(least(1, greatest(-1,
+                    sin(radians($2)) * sin(radians(f.coordinates[1]))
+                  + cos(radians($2)) * cos(radians(f.coordinates[1]))
+                    * cos(radians(f.coordinates[0] - $3))
+                ))) AS distance_km
+            FROM farms f
+            WHERE f.id <> $1
+        )
+        SELECT
+            f.id,
+            f.name        AS "name: Name",
+            f.address     AS "address: Address",
+            f.postal_code AS "postal_code: PostalCode",
+            f.canton      AS "canton: Canton",
+            f.coordinates AS "coordinates: Point",
+            COALESCE(f.certifications, '{}') AS "certifications!: Vec<Certification>",
+            f.created_at,
+            f.updated_at,
+            f.distance_km AS "distance_km!"
+        FROM base f
+        ORDER BY f.distance_km ASC, f.id ASC
+        LIMIT $4
+        "#,
+        farm_id,
+        origin.latitude(),
+        origin.longitude(),
+        limit,
+    )
+    .fetch_all(statements.on(pool))
+    .await
+    .context("Failed to fetch farm neighbors.")?;
+
+    let farm_ids: Vec<Uuid> = farm_rows.iter().map(|f| f.id).collect();
+    let direct_categories_by_farm = load_direct_categories(pool, statements, &farm_ids).await?;
+    let mut products_by_farm = load_products(pool, statements, &farm_ids).await?;
+
+    let mut responses = Vec::with_capacity(farm_rows.len());
+    for farm in farm_rows {
+        let products = products_by_farm.remove(&farm.id).unwrap_or_default();
+        let direct = direct_categories_by_farm
+            .get(&farm.id)
+            .cloned()
+            .unwrap_or_default();
+        let categories = derive_categories(&direct, &products);
+        responses.push(FarmWithDistance {
+            farm: FarmResponse {
+                id: farm.id,
+                name: farm.name,
+                address: farm.address,
+                postal_code: farm.postal_code,
+                canton_name: farm.canton.name_in(canton_names, language).to_string(),
+                canton: farm.canton,
+                coordinates: farm.coordinates,
+                categories,
+                products,
+                certifications: farm.certifications,
+                distance_km: None,
+                created_at: farm.created_at,
+                updated_at: farm.updated_at,
+            },
+            distance_km: farm.distance_km,
+        });
+    }
+
+    Ok(responses)
+}
+
 /// Distinct, sorted category (group) slugs for a farm: the union of its direct
 /// group memberships and the groups of the products it lists. This is what lets
 /// a farm surface under a category whether its data is coarse (group only) or
@@ -0,0 +1,110 @@
+use crate::{authentication::CurrentUser, routes::farms::FarmError};
+use actix_web::{HttpResponse, web};
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::get::FarmPath;
+
+/// Most history entries returned per page.
+const MAX_HISTORY_PAGE_SIZE: i64 = 100;
+
+#[derive(Debug, serde::Deserialize)]
+pub struct FarmHistoryQuery {
+    #[serde(default = "default_history_limit")]
+    pub limit: i64,
+    #[serde(default)]
+    pub offset: i64,
+}
+
+fn default_history_limit() -> i64 {
+    20
+}
+
+/// One audit entry for a farm.
+#[derive(Debug, serde::Serialize)]
+pub struct FarmHistoryEntry {
+    /// What happened, e.g. `create` or `import`.
+    pub action: String,
+    /// The request body that asked for the change, without its
+    /// `idempotency_key`.
+    pub payload: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A page of a farm's history, oldest first, plus the offset of the next
+/// page as a string, or null when this is the last page.
+#[derive(Debug, serde::Serialize)]
+pub struct FarmHistoryResponse {
+    pub entries: Vec<FarmHistoryEntry>,
+    pub next_cursor: Option<String>,
+}
+
+/// `GET /farms/{id}/history` — the farm's audit trail in the order it
+/// happened. A `404` only when the farm never existed: audit entries outlive
+/// the farm, and a farm written outside the API has an empty history.
+#[tracing::instrument(name = "Get farm history", skip(pool))]
+pub async fn get_history(
+    _current_user: CurrentUser,
+    path: web::Path<FarmPath>,
+    query: web::Query<FarmHistoryQuery>,
+    pool: web::Data<PgPool>,
+) -> Result<HttpResponse, FarmError> {
+    let farm_id = Uuid::parse_str(&path.id)
+        .map_err(|_| FarmError::ValidationError("Invalid farm id.".to_string()))?;
+    let limit = query.limit.clamp(1, MAX_HISTORY_PAGE_SIZE);
+    let offset = query.offset.max(0);
+
+    let known = sqlx::query_scalar!(
+        r#"
+        SELECT EXISTS (SELECT 1 FROM farms WHERE id = $1)
+            OR EXISTS (SELECT 1 FROM audit_log WHERE farm_id = $1) AS "known!"
+        "#,
+        farm_id,
+    )
+    .fetch_one(pool.get_ref())
+    .await
+    .context("Failed to look up the farm.")?;
+    if !known {
+        return Err(FarmError::NotFound);
+    }
+
+    let rows = sqlx::query!(
+        r#"
+        SELECT action, payload, created_at
+        FROM audit_log
+        WHERE farm_id = $1
+        ORDER BY created_at, id
+        LIMIT $2 OFFSET $3
+        "#,
+        farm_id,
+        limit,
+        offset,
+    )
+    .fetch_all(pool.get_ref())
+    .await
+    .context("Failed to load the farm history.")?;
+
+    // A full page implies there may be more; hand back the next offset.
+    let next_cursor = (rows.len() as i64 == limit).then(|| (offset + limit).to_string());
+    let entries = rows
+        .into_iter()
+        .map(|row| {
+            let mut payload = row.payload;
+            if let Some(fields) = payload.as_object_mut() {
+                fields.remove("idempotency_key");
+            }
+            FarmHistoryEntry {
+                action: row.action,
+                payload,
+                created_at: row.created_at,
+            }
+        })
+        .collect();
+
+    Ok(HttpResponse::Ok().json(FarmHistoryResponse {
+        entries,
+        next_cursor,
+    }))
+}
@@ -0,0 +1,316 @@
+use crate::{
+    audit::AuditAction,
+    authentication::CurrentUser,
+    configuration::{FarmsSettings, SharedSettings},
+    db::{
+        MAX_TRANSACTION_ATTEMPTS, StatementCounter, in_transaction, retry_serialization_failures,
+    },
+    routes::farms::{
+        FarmError, FarmErrorBody, FarmEvents,
+        cache::ListCache,
+        post::{FarmFields, ValidatedFarm, insert_validated_farm},
+    },
+    taxonomy::TaxonomySnapshot,
+};
+use actix_web::{HttpMessage, HttpRequest, HttpResponse, web};
+use deadpool_redis::Pool;
+use sqlx::PgPool;
+use tracing_actix_web::RequestId;
+use uuid::Uuid;
+
+/// Most data rows a single import may hold.
+pub const MAX_IMPORT_ROWS: usize = 1000;
+
+/// Largest CSV body `POST /farms/import` accepts, in bytes.
+pub const MAX_IMPORT_BYTES: usize = 1024 * 1024;
+
+#[derive(Debug, serde::Deserialize)]
+pub struct FarmImportQuery {
+    /// Import nothing unless every row is valid.
+    #[serde(default)]
+    pub all_or_nothing: bool,
+}
+
+/// One CSV row, with the column names of `GET /farms/export.json`. List cells
+/// (`categories`, `products`, `certifications`) are `;`-separated. The
+/// server-assigned columns (`id`, `postal_code`, `created_at`, `updated_at`)
+/// and any others are ignored, so an export converted to CSV imports as is.
+#[derive(serde::Deserialize, serde::Serialize)]
+struct ImportRow {
+    name: String,
+    address: String,
+    canton: String,
+    coordinates: String,
+    #[serde(default)]
+    categories: String,
+    #[serde(default)]
+    products: String,
+    #[serde(default)]
+    certifications: String,
+}
+
+impl ImportRow {
+    fn into_fields(self) -> FarmFields {
+        let list = |cell: String| {
+            cell.split(';')
+                .map(str::trim)
+                .filter(|entry| !entry.is_empty())
+                .map(str::to_string)
+                .collect()
+        };
+        FarmFields {
+            name: self.name,
+            address: self.address,
+            canton: self.canton,
+            coordinates: self.coordinates,
+            categories: list(self.categories),
+            products: list(self.products),
+            certifications: list(self.certifications),
+        }
+    }
+}
+
+/// A data row after validation: the farm and its audit payload, or why the
+/// row was rejected.
+struct ParsedRow {
+    /// 1-based line in the CSV, the header being line 1.
+    line: u64,
+    farm: Result<(ValidatedFarm, serde_json::Value), String>,
+}
+
+/// The body of `POST /farms/import`: one entry per data row, in CSV order.
+#[derive(serde::Serialize)]
+pub struct FarmImportReport {
+    pub imported: usize,
+    pub rejected: usize,
+    pub rows: Vec<FarmImportRowResult>,
+}
+
+impl FarmImportReport {
+    fn new(rows: Vec<FarmImportRowResult>) -> Self {
+        let imported = rows
+            .iter()
+            .filter(|row| matches!(row, FarmImportRowResult::Imported { .. }))
+            .count();
+        let rejected = rows
+            .iter()
+            .filter(|row| matches!(row, FarmImportRowResult::Rejected { .. }))
+            .count();
+        Self {
+            imported,
+            rejected,
+            rows,
+        }
+    }
+}
+
+/// The `400` of an all-or-nothing import with a rejected row: a
+/// `VALIDATION_ERROR` body like any `FarmError`'s, with the report alongside.
+#[derive(serde::Serialize)]
+struct RejectedImport {
+    #[serde(flatten)]
+    error: FarmErrorBody,
+    #[serde(flatten)]
+    report: FarmImportReport,
+}
+
+#[derive(serde::Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum FarmImportRowResult {
+    Imported {
+        row: u64,
+        id: Uuid,
+    },
+    Rejected {
+        row: u64,
+        reason: String,
+    },
+    /// Valid, but not imported because another row was rejected and the
+    /// import was all-or-nothing.
+    Skipped {
+        row: u64,
+    },
+}
+
+/// `POST /farms/import` — creates farms from a `text/csv` body.
+///
+/// Every row is validated like a `POST /farms` body. Valid rows are inserted
+/// in one transaction and invalid ones reported with their line and reason;
+/// with `?all_or_nothing=true` a single invalid row imports nothing. Rows
+/// carry no idempotency key, so re-sending a CSV imports its farms again.
+#[allow(clippy::too_many_arguments)]
+#[tracing::instrument(
+    name = "Importing farms",
+    skip(
+        request,
+        statements,
+        body,
+        pool,
+        redis_pool,
+        taxonomy,
+        configuration,
+        farm_events
+    ),
+    fields(rows = tracing::field::Empty)
+)]
+pub async fn import(
+    current_user: CurrentUser,
+    request_id: RequestId,
+    statements: StatementCounter,
+    request: HttpRequest,
+    query: web::Query<FarmImportQuery>,
+    body: web::Bytes,
+    pool: web::Data<PgPool>,
+    redis_pool: web::Data<Pool>,
+    taxonomy: web::Data<TaxonomySnapshot>,
+    configuration: web::Data<SharedSettings>,
+    farm_events: web::Data<FarmEvents>,
+) -> Result<HttpResponse, FarmError> {
+    let configuration = configuration.load_full();
+    if !request.content_type().eq_ignore_ascii_case("text/csv") {
+        return Err(FarmError::ValidationError(
+            "Farms must be imported as text/csv.".to_string(),
+        ));
+    }
+
+    let rows = parse_rows(&body, &configuration.farms, &taxonomy)?;
+    tracing::Span::current().record("rows", rows.len());
+
+    if query.all_or_nothing && rows.iter().any(|row| row.farm.is_err()) {
+        let results = rows
+            .into_iter()
+            .map(|row| match row.farm {
+                Ok(_) => FarmImportRowResult::Skipped { row: row.line },
+                Err(reason) => FarmImportRowResult::Rejected {
+                    row: row.line,
+                    reason,
+                },
+            })
+            .collect();
+        let report = FarmImportReport::new(results);
+        let error = FarmError::ValidationError(format!(
+            "{} of the rows were rejected, so none were imported.",
+            report.rejected
+        ))
+        .error_body(
+            configuration.application.error_verbosity,
+            Some(request_id.to_string()),
+        );
+        return Ok(HttpResponse::BadRequest().json(RejectedImport { error, report }));
+    }
+
+    let request_id: Uuid = request_id.into();
+    // All rows go in one transaction, retried as a whole if it loses a race.
+    let (results, inserted) = retry_serialization_failures(MAX_TRANSACTION_ATTEMPTS, async || {
+        in_transaction(&pool, &statements, async |transaction| {
+            let mut results = Vec::with_capacity(rows.len());
+            let mut inserted = Vec::new();
+            for row in &rows {
+                match &row.farm {
+                    Ok((farm, audit_payload)) => {
+                        let stored = insert_validated_farm(
+                            transaction,
+                            &statements,
+                            farm,
+                            AuditAction::Import,
+                            current_user.id,
+                            Some(request_id),
+                            audit_payload,
+                        )
+                        .await?;
+                        results.push(FarmImportRowResult::Imported {
+                            row: row.line,
+                            id: stored.id,
+                        });
+                        inserted.push(stored);
+                    }
+                    Err(reason) => results.push(FarmImportRowResult::Rejected {
+                        row: row.line,
+                        reason: reason.clone(),
+                    }),
+                }
+            }
+            Ok::<_, FarmError>((results, inserted))
+        })
+        .await
+    })
+    .await?;
+    let imported: Vec<_> = rows
+        .into_iter()
+        .filter_map(|row| row.farm.ok())
+        .map(|(farm, _)| farm)
+        .zip(inserted)
+        .collect();
+
+    if !imported.is_empty()
+        && let Some(cache) = ListCache::new(&redis_pool, &configuration.farms.list_cache)
+    {
+        cache.invalidate().await;
+    }
+    for (farm, inserted) in imported {
+        farm_events.publish(farm.into_event(&inserted));
+    }
+
+    Ok(HttpResponse::Ok().json(FarmImportReport::new(results)))
+}
+
+/// Reads and validates every data row of `csv`. Only a CSV that can't be
+/// imported at all (no header, no rows, too many rows) is an error; a bad
+/// row is reported in its `ParsedRow`.
+fn parse_rows(
+    csv: &[u8],
+    settings: &FarmsSettings,
+    taxonomy: &TaxonomySnapshot,
+) -> Result<Vec<ParsedRow>, FarmError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(csv);
+    let headers = reader
+        .headers()
+        .map_err(|e| FarmError::ValidationError(format!("Invalid CSV header: {e}.")))?
+        .clone();
+
+    let mut rows = Vec::new();
+    let mut record = csv::StringRecord::new();
+    loop {
+        let (line, farm) = match reader.read_record(&mut record) {
+            Ok(false) => break,
+            Ok(true) => (
+                record.position().map_or(0, |position| position.line()),
+                validate_row(&record, &headers, settings, taxonomy),
+            ),
+            Err(e) => (
+                e.position().map_or(0, |position| position.line()),
+                Err(e.to_string()),
+            ),
+        };
+        if rows.len() == MAX_IMPORT_ROWS {
+            return Err(FarmError::ValidationError(format!(
+                "At most {MAX_IMPORT_ROWS} farms can be imported at once."
+            )));
+        }
+        rows.push(ParsedRow { line, farm });
+    }
+
+    if rows.is_empty() {
+        return Err(FarmError::ValidationError(
+            "The CSV has no rows to import.".to_string(),
+        ));
+    }
+    Ok(rows)
+}
+
+fn validate_row(
+    record: &csv::StringRecord,
+    headers: &csv::StringRecord,
+    settings: &FarmsSettings,
+    taxonomy: &TaxonomySnapshot,
+) -> Result<(ValidatedFarm, serde_json::Value), String> {
+    let row = record
+        .deserialize::<ImportRow>(Some(headers))
+        .map_err(|e| e.to_string())?;
+    let audit_payload = serde_json::to_value(&row).map_err(|e| e.to_string())?;
+    let farm =
+        ValidatedFarm::parse(row.into_fields(), settings, taxonomy).map_err(|e| e.to_string())?;
+    Ok((farm, audit_payload))
+}
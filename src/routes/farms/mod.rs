@@ -1,14 +1,30 @@
-use crate::domain::farm::{Address, Canton, Name, Point, StockStatus};
+use crate::domain::farm::{
+    Address, Canton, Certification, Located, Name, Point, PointObj, PostalCode, StockStatus,
+};
 use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
+pub(crate) mod cache;
 mod error;
+mod export;
 mod get;
+mod history;
+mod import;
 mod post;
+mod schema;
+mod stream;
 
-pub use error::FarmError;
-pub use get::{get_all, get_by_id};
+pub use error::{FarmError, FarmErrorBody, IN_FLIGHT_RETRY_AFTER_SECONDS, RETRY_AFTER_SECONDS};
+pub use export::{FarmExportRow, export};
+pub use get::{count, get_all, get_by_id, get_neighbors};
+pub use history::{FarmHistoryEntry, FarmHistoryQuery, FarmHistoryResponse, get_history};
+pub use import::{
+    FarmImportQuery, FarmImportReport, FarmImportRowResult, MAX_IMPORT_BYTES, MAX_IMPORT_ROWS,
+    import,
+};
 pub use post::create;
+pub use schema::{CategoriesSchema, CategorySchema, categories_schema};
+pub use stream::{FarmCreatedEvent, FarmEvents, stream};
 
 /// A product as returned to API clients.
 ///
@@ -34,32 +50,149 @@ pub struct FarmResponse {
     pub id: Uuid,
     pub name: Name,
     pub address: Address,
+    pub postal_code: Option<PostalCode>,
     pub canton: Canton,
+    /// The canton's name in the negotiated response language.
+    pub canton_name: String,
+    /// Serialized twice: as the `"lat,lon"` string and, under
+    /// `coordinates_components`, as `{"lat": .., "lon": ..}` numbers.
+    #[serde(flatten, serialize_with = "serialize_coordinates")]
     pub coordinates: Point,
     pub categories: Vec<String>,
     pub products: Vec<ProductDto>,
-    /// Straight-line distance in km from the request's `lat`/`lng`, when given.
+    /// Certification codes, e.g. `["bio_suisse"]`; empty when none recorded.
+    pub certifications: Vec<Certification>,
+    /// Straight-line distance in km from the request's `lat`/`lng`, when
+    /// given. Results that are always measured from somewhere use
+    /// `FarmWithDistance` instead.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub distance_km: Option<f64>,
     pub created_at: DateTime<Utc>,
+    /// `null` until the farm is first edited; always present, never omitted.
     pub updated_at: Option<DateTime<Utc>>,
 }
 
-/// A page of farms plus the offset to fetch the next page (if any).
+impl FarmResponse {
+    /// The names `?fields=` may select, as serialized.
+    pub const FIELDS: [&'static str; 14] = [
+        "id",
+        "name",
+        "address",
+        "postal_code",
+        "canton",
+        "canton_name",
+        "coordinates",
+        "coordinates_components",
+        "categories",
+        "products",
+        "certifications",
+        "distance_km",
+        "created_at",
+        "updated_at",
+    ];
+
+    /// Decimal places kept for `?obfuscate=true` (roughly 1 km).
+    pub const OBFUSCATED_PRECISION_DIGITS: u8 = 2;
+
+    /// Coarsens the farm's location so its exact position isn't disclosed.
+    /// The distance is rounded to whole km so it can't be used to recover it.
+    pub fn obfuscate_location(&mut self) {
+        self.coordinates = self
+            .coordinates
+            .snap_to_grid(Self::OBFUSCATED_PRECISION_DIGITS);
+        self.distance_km = self.distance_km.map(f64::round);
+    }
+
+    /// Rounds the returned coordinates to `digits` decimal places, for
+    /// display. The stored location keeps its full precision.
+    pub fn round_coordinates(&mut self, digits: u8) {
+        self.coordinates = self.coordinates.snap_to_grid(digits);
+    }
+}
+
+/// Writes a farm's `coordinates` in both of its response forms, so clients
+/// can skip parsing the string.
+fn serialize_coordinates<S>(coordinates: &Point, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    #[derive(serde::Serialize)]
+    struct Coordinates {
+        coordinates: Point,
+        coordinates_components: PointObj,
+    }
+
+    serde::Serialize::serialize(
+        &Coordinates {
+            coordinates: *coordinates,
+            coordinates_components: PointObj(*coordinates),
+        },
+        serializer,
+    )
+}
+
+impl Located for FarmResponse {
+    fn location(&self) -> Point {
+        self.coordinates
+    }
+}
+
+/// A farm from a geo query, which always has a distance: the farm's fields
+/// with `distance_km`, in km, alongside.
+#[derive(serde::Serialize)]
+pub struct FarmWithDistance {
+    #[serde(flatten)]
+    pub farm: FarmResponse,
+    pub distance_km: f64,
+}
+
+/// A page of farms plus the offset to fetch the next page (if any). `T` is a
+/// `serde_json::Value` when `?fields=` trims the farms.
 #[derive(serde::Serialize)]
-pub struct FarmListResponse {
-    pub farms: Vec<FarmResponse>,
+pub struct FarmListResponse<T = FarmResponse> {
+    pub farms: Vec<T>,
     /// Offset for the next page as a string, or null when this is the last page.
     pub next_cursor: Option<String>,
 }
 
+/// The `?envelope=true` form of a page of farms, for clients that prefer
+/// page metadata over a cursor.
+#[derive(serde::Serialize)]
+pub struct FarmPageEnvelope<T = FarmResponse> {
+    pub data: Vec<T>,
+    pub meta: PageMeta,
+}
+
+#[derive(serde::Serialize)]
+pub struct PageMeta {
+    /// 1-based page number, derived from `offset` and `limit`.
+    pub page: i64,
+    pub per_page: i64,
+    /// Farms matching the filters across all pages.
+    pub total: i64,
+}
+
+/// The number of farms matching the `GET /farms/count` filters.
+#[derive(serde::Serialize)]
+pub struct FarmCountResponse {
+    pub count: i64,
+}
+
+/// The farms closest to a given farm, nearest first.
+#[derive(serde::Serialize)]
+pub struct FarmNeighborsResponse {
+    pub farms: Vec<FarmWithDistance>,
+}
+
 /// The raw farm row loaded from the database, before products are attached.
 pub(crate) struct FarmRow {
     pub id: Uuid,
     pub name: Name,
     pub address: Address,
+    pub postal_code: Option<PostalCode>,
     pub canton: Canton,
     pub coordinates: Point,
+    pub certifications: Vec<Certification>,
     pub created_at: DateTime<Utc>,
     pub updated_at: Option<DateTime<Utc>>,
 }
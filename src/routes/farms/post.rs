@@ -1,19 +1,38 @@
 use crate::{
+    audit::{self, AuditAction, AuditEntry},
     authentication::CurrentUser,
-    configuration::Settings,
-    domain::farm::{Address, Canton, Name, Point, ProductSlug},
-    idempotency::{IdempotencyError, IdempotencyNextAction, save_response, try_processing},
-    routes::farms::FarmError,
+    configuration::{
+        CantonMismatchAction, FarmsSettings, IdempotencyEngine, IdempotentEndpoint, SharedSettings,
+    },
+    db::{
+        MAX_TRANSACTION_ATTEMPTS, StatementCounter, in_transaction, retry_serialization_failures,
+    },
+    domain::farm::{
+        Address, Canton, Categories, Certification, Name, Point, PostalCode, ProductSlug,
+    },
+    idempotency::{
+        IdempotencyError, IdempotencyNextAction, MemoryIdempotencyStore, hash_request_payload,
+        save_response, try_processing,
+    },
+    routes::{
+        JSON_CONTENT_TYPE,
+        farms::{FarmCreatedEvent, FarmError, FarmEvents, cache::ListCache},
+    },
     taxonomy::TaxonomySnapshot,
 };
-use actix_web::{HttpResponse, web};
+use actix_web::{
+    FromRequest, HttpMessage, HttpRequest, HttpResponse, dev::Payload, http::header, web,
+};
 use anyhow::Context;
 use chrono::{DateTime, Utc};
 use deadpool_redis::Pool;
-use sqlx::{Executor, PgPool, Postgres, Transaction};
+use sqlx::{Executor, PgConnection, PgPool, Postgres};
+use std::{future::Future, pin::Pin};
+use tracing_actix_web::RequestId;
 use uuid::Uuid;
 
-#[derive(serde::Deserialize)]
+#[derive(serde::Deserialize, serde::Serialize)]
+#[serde(deny_unknown_fields)]
 pub struct FormData {
     name: String,
     address: String,
@@ -26,152 +45,639 @@ pub struct FormData {
     /// Product slugs the farm offers, e.g. ["strawberries", "cherries"].
     #[serde(default)]
     products: Vec<String>,
-    idempotency_key: String,
+    /// Certification codes, e.g. ["bio_suisse", "demeter"].
+    #[serde(default)]
+    certifications: Vec<String>,
+    /// Required unless `create_farm` is in `idempotency.exempt_endpoints`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    idempotency_key: Option<String>,
+}
+
+/// Names at least this similar (pg_trgm, 0 to 1) in the same canton are
+/// reported as possible duplicates.
+const DUPLICATE_NAME_SIMILARITY: f32 = 0.6;
+
+/// The `201 Created` body.
+#[derive(serde::Serialize)]
+pub struct CreateFarmResponse {
+    /// The id of the created farm, also in the `Location` header.
+    pub id: Uuid,
+    /// Non-blocking notes about the created farm; empty when there are none.
+    pub warnings: Vec<CreateFarmWarning>,
+}
+
+#[derive(serde::Serialize)]
+#[serde(tag = "code", rename_all = "snake_case")]
+pub enum CreateFarmWarning {
+    /// An existing farm in the same canton has a very similar name.
+    PossibleDuplicate {
+        message: String,
+        farm_id: Uuid,
+        name: String,
+    },
+    /// The coordinates point to another canton than the submitted one (see
+    /// `farms.canton_check`).
+    CantonMismatch {
+        message: String,
+        nearest_capital_canton: Canton,
+    },
+}
+
+impl FormData {
+    /// Trims surrounding whitespace from every string field, list entries
+    /// included, so validation, hashing, logs and the audit trail all see the
+    /// same values.
+    fn trimmed(self) -> Self {
+        let trim = |value: String| value.trim().to_string();
+        Self {
+            name: trim(self.name),
+            address: trim(self.address),
+            canton: trim(self.canton),
+            coordinates: trim(self.coordinates),
+            categories: self.categories.into_iter().map(trim).collect(),
+            products: self.products.into_iter().map(trim).collect(),
+            certifications: self.certifications.into_iter().map(trim).collect(),
+            idempotency_key: self.idempotency_key.map(trim),
+        }
+    }
+
+    /// The farm's own fields and the idempotency key, if one was sent.
+    fn into_parts(self) -> (FarmFields, Option<String>) {
+        let fields = FarmFields {
+            name: self.name,
+            address: self.address,
+            canton: self.canton,
+            coordinates: self.coordinates,
+            categories: self.categories,
+            products: self.products,
+            certifications: self.certifications,
+        };
+        (fields, self.idempotency_key)
+    }
+
+    /// Builds the form from `application/x-www-form-urlencoded` pairs. List
+    /// fields may repeat, with or without a `[]` suffix
+    /// (`products[]=apples&products[]=eggs`). Unknown fields, such as the
+    /// name of an HTML form's submit button, are ignored.
+    fn from_form_pairs(pairs: Vec<(String, String)>) -> Result<Self, FarmError> {
+        let (mut name, mut address, mut canton, mut coordinates, mut idempotency_key) =
+            (None, None, None, None, None);
+        let (mut categories, mut products, mut certifications) = (vec![], vec![], vec![]);
+        for (key, value) in pairs {
+            match key.strip_suffix("[]").unwrap_or(&key) {
+                "name" => name = Some(value),
+                "address" => address = Some(value),
+                "canton" => canton = Some(value),
+                "coordinates" => coordinates = Some(value),
+                "idempotency_key" => idempotency_key = Some(value),
+                "categories" => categories.push(value),
+                "products" => products.push(value),
+                "certifications" => certifications.push(value),
+                _ => {}
+            }
+        }
+
+        let required = |value: Option<String>, field: &'static str| {
+            value.ok_or_else(|| FarmError::InvalidField {
+                field: field.into(),
+                message: format!("Missing field '{field}'."),
+            })
+        };
+        Ok(Self {
+            name: required(name, "name")?,
+            address: required(address, "address")?,
+            canton: required(canton, "canton")?,
+            coordinates: required(coordinates, "coordinates")?,
+            categories,
+            products,
+            certifications,
+            idempotency_key,
+        })
+    }
+}
+
+/// A farm's own fields as submitted, before validation.
+pub(super) struct FarmFields {
+    pub name: String,
+    pub address: String,
+    pub canton: String,
+    pub coordinates: String,
+    pub categories: Vec<String>,
+    pub products: Vec<String>,
+    pub certifications: Vec<String>,
+}
+
+/// A farm whose fields passed validation, its classification resolved to
+/// taxonomy ids.
+pub(super) struct ValidatedFarm {
+    pub name: Name,
+    pub address: Address,
+    pub canton: Canton,
+    pub coordinates: Point,
+    pub certifications: Vec<Certification>,
+    pub category_ids: Vec<i16>,
+    pub product_ids: Vec<i32>,
+    /// The canton whose capital lies closest to `coordinates`.
+    pub nearest_capital_canton: Canton,
+    /// Whether `canton` disagrees with the coordinates beyond
+    /// `farms.canton_check.tolerance_km`.
+    pub canton_mismatch: bool,
+}
+
+impl ValidatedFarm {
+    /// Validates `fields` through the domain types and resolves its slugs
+    /// against `taxonomy`, failing on the first invalid field.
+    pub(super) fn parse(
+        fields: FarmFields,
+        settings: &FarmsSettings,
+        taxonomy: &TaxonomySnapshot,
+    ) -> Result<Self, FarmError> {
+        let name = Name::parse_with_blocklist(fields.name, &settings.name_blocklist)
+            .map_err(|e| unprocessable("name", e))?;
+        let address = Address::parse(fields.address).map_err(|e| unprocessable("address", e))?;
+        let canton = Canton::parse(fields.canton).map_err(|e| unprocessable("canton", e))?;
+        let coordinates = Point::parse_within(&fields.coordinates, settings.swiss_boundary)
+            .map_err(|e| unprocessable("coordinates", e))?;
+        let mismatch = canton.mismatch_at(&coordinates, settings.canton_check.tolerance_km);
+        if let Some(located) = &mismatch
+            && settings.canton_check.on_mismatch == CantonMismatchAction::Reject
+        {
+            return Err(unprocessable(
+                "canton",
+                canton_mismatch_message(&canton, located),
+            ));
+        }
+        let canton_mismatch = mismatch.is_some();
+        let nearest_capital_canton =
+            mismatch.unwrap_or_else(|| Canton::nearest_capital(&coordinates));
+
+        let mut certifications = fields
+            .certifications
+            .into_iter()
+            .map(Certification::parse)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| unprocessable("certifications", e))?;
+        certifications.sort_unstable();
+        certifications.dedup();
+
+        // An unclassified farm falls back to the configured default categories,
+        // which are validated like submitted ones. A list is held to the
+        // `Categories` limits, as `GET /farms/schema/categories` publishes
+        // them, before its slugs are resolved.
+        let categories = if fields.categories.is_empty() && fields.products.is_empty() {
+            settings.default_categories.clone()
+        } else {
+            fields.categories
+        };
+        if !categories.is_empty() {
+            Categories::parse_with_options(categories.clone(), settings.categories_parse_options())
+                .map_err(|e| unprocessable("categories", e))?;
+        }
+
+        // Resolve product slugs (shape via ProductSlug, existence via the snapshot).
+        let mut product_ids = Vec::with_capacity(fields.products.len());
+        for raw in fields.products {
+            let slug = ProductSlug::parse(raw).map_err(|e| unprocessable("products", e))?;
+            let id = taxonomy.id_for_slug(slug.as_str()).ok_or_else(|| {
+                unprocessable("products", format!("Unknown product '{}'.", slug.as_str()))
+            })?;
+            product_ids.push(id);
+        }
+        product_ids.sort_unstable();
+        product_ids.dedup();
+
+        // Resolve category slugs (ProductSlug validates slug shape for either kind).
+        let mut category_ids = Vec::with_capacity(categories.len());
+        for raw in categories {
+            let slug = ProductSlug::parse(raw).map_err(|e| unprocessable("categories", e))?;
+            let id = taxonomy
+                .category_id_for_slug(slug.as_str())
+                .ok_or_else(|| {
+                    unprocessable(
+                        "categories",
+                        format!("Unknown category '{}'.", slug.as_str()),
+                    )
+                })?;
+            category_ids.push(id);
+        }
+        category_ids.sort_unstable();
+        category_ids.dedup();
+
+        // A farm needs at least one classification — coarse (group) or granular
+        // (product). The source data has both kinds, so accept either.
+        if category_ids.is_empty() && product_ids.is_empty() {
+            return Err(FarmError::Unprocessable(
+                "At least one category or product is required.".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            name,
+            address,
+            canton,
+            coordinates,
+            certifications,
+            category_ids,
+            product_ids,
+            nearest_capital_canton,
+            canton_mismatch,
+        })
+    }
+
+    /// The event announcing this farm once it is stored as `inserted`.
+    pub(super) fn into_event(self, inserted: &InsertedFarm) -> FarmCreatedEvent {
+        FarmCreatedEvent {
+            id: inserted.id,
+            postal_code: self.address.postal_code(),
+            name: self.name,
+            address: self.address,
+            canton: self.canton,
+            coordinates: self.coordinates,
+            created_at: inserted.created_at,
+        }
+    }
+}
+
+/// A `422` for the submitted `field`.
+fn unprocessable(field: &'static str, message: impl ToString) -> FarmError {
+    FarmError::UnprocessableField {
+        field: field.into(),
+        message: message.to_string(),
+    }
+}
+
+/// Why a farm's canton was found at odds with its coordinates.
+fn canton_mismatch_message(canton: &Canton, located: &Canton) -> String {
+    format!("The coordinates point to canton {located}, not {canton}.")
+}
+
+/// The columns Postgres reports back for a newly inserted farm.
+pub(super) struct InsertedFarm {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+}
+
+/// The `create` body, sent either as JSON or as an
+/// `application/x-www-form-urlencoded` form (picked by `Content-Type`).
+pub struct FarmPayload(FormData);
+
+impl FromRequest for FarmPayload {
+    type Error = actix_web::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self, Self::Error>>>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        if req.content_type() == "application/x-www-form-urlencoded" {
+            let form = web::Form::<Vec<(String, String)>>::from_request(req, payload);
+            Box::pin(async move {
+                let pairs = form.await?.into_inner();
+                FormData::from_form_pairs(pairs)
+                    .map(Self)
+                    .map_err(Into::into)
+            })
+        } else {
+            // Parsed as a `Value` first, so a value of the wrong type is
+            // reported with the path of its field.
+            let json = web::Json::<serde_json::Value>::from_request(req, payload);
+            Box::pin(async move {
+                let value = json.await?.into_inner();
+                serde_path_to_error::deserialize(value)
+                    .map(Self)
+                    .map_err(|e| {
+                        let path = e.path().to_string();
+                        let path = (path != ".").then_some(path.as_str());
+                        FarmError::invalid_json(e.inner(), path).into()
+                    })
+            })
+        }
+    }
 }
 
-#[allow(clippy::async_yields_async)]
+#[allow(clippy::async_yields_async, clippy::too_many_arguments)]
 #[tracing::instrument(
     name = "Adding a new farm",
-    skip(body, pool, redis_pool, taxonomy, configuration)
+    skip(
+        body,
+        statements,
+        pool,
+        redis_pool,
+        memory_store,
+        taxonomy,
+        configuration,
+        farm_events
+    ),
+    fields(
+        create_name = tracing::field::Empty,
+        create_address = tracing::field::Empty,
+        create_canton = tracing::field::Empty,
+        create_coordinates = tracing::field::Empty,
+        idempotency_key = tracing::field::Empty,
+    )
 )]
 pub async fn create(
     current_user: CurrentUser,
-    body: web::Json<FormData>,
+    request_id: RequestId,
+    statements: StatementCounter,
+    body: FarmPayload,
     pool: web::Data<PgPool>,
     redis_pool: web::Data<Pool>,
+    memory_store: web::Data<MemoryIdempotencyStore>,
     taxonomy: web::Data<TaxonomySnapshot>,
-    configuration: web::Data<Settings>,
+    configuration: web::Data<SharedSettings>,
+    farm_events: web::Data<FarmEvents>,
 ) -> Result<HttpResponse, FarmError> {
-    let body = body.into_inner();
-
-    // Validate the farm's own fields.
-    let name = Name::parse(body.name).map_err(|e| FarmError::ValidationError(e.to_string()))?;
-    let address =
-        Address::parse(body.address).map_err(|e| FarmError::ValidationError(e.to_string()))?;
-    let canton =
-        Canton::parse(body.canton).map_err(|e| FarmError::ValidationError(e.to_string()))?;
-    let coordinates =
-        Point::parse(&body.coordinates).map_err(|e| FarmError::ValidationError(e.to_string()))?;
-
-    // Resolve product slugs (shape via ProductSlug, existence via the snapshot).
-    let mut product_ids = Vec::with_capacity(body.products.len());
-    for raw in body.products {
-        let slug =
-            ProductSlug::parse(raw).map_err(|e| FarmError::ValidationError(e.to_string()))?;
-        let id = taxonomy.id_for_slug(slug.as_str()).ok_or_else(|| {
-            FarmError::ValidationError(format!("Unknown product '{}'.", slug.as_str()))
-        })?;
-        product_ids.push(id);
-    }
-    product_ids.sort_unstable();
-    product_ids.dedup();
-
-    // Resolve category slugs (ProductSlug validates slug shape for either kind).
-    let mut category_ids = Vec::with_capacity(body.categories.len());
-    for raw in body.categories {
-        let slug =
-            ProductSlug::parse(raw).map_err(|e| FarmError::ValidationError(e.to_string()))?;
-        let id = taxonomy
-            .category_id_for_slug(slug.as_str())
-            .ok_or_else(|| {
-                FarmError::ValidationError(format!("Unknown category '{}'.", slug.as_str()))
-            })?;
-        category_ids.push(id);
-    }
-    category_ids.sort_unstable();
-    category_ids.dedup();
-
-    // A farm needs at least one classification — coarse (group) or granular
-    // (product). The source data has both kinds, so accept either.
-    if category_ids.is_empty() && product_ids.is_empty() {
-        return Err(FarmError::ValidationError(
-            "At least one category or product is required.".to_string(),
-        ));
-    }
+    // One snapshot for the whole request, even if settings reload meanwhile.
+    let configuration = configuration.load_full();
+    let body = body.0.trimmed();
+    let request_hash = hash_request_payload(&body)?;
+    let audit_payload =
+        serde_json::to_value(&body).context("Failed to serialize the farm for the audit log.")?;
+
+    let (fields, idempotency_key) = body.into_parts();
+    let idempotency_key = if configuration
+        .idempotency
+        .enforced_for(IdempotentEndpoint::CreateFarm)
+    {
+        Some(idempotency_key.ok_or_else(|| FarmError::InvalidField {
+            field: "idempotency_key".into(),
+            message: "Missing field 'idempotency_key'.".to_string(),
+        })?)
+    } else {
+        None
+    };
+    let farm = ValidatedFarm::parse(fields, &configuration.farms, &taxonomy)?;
 
     // Record form fields in the tracing span.
     let span = tracing::Span::current();
-    span.record("create_name", name.as_str());
-    span.record("create_address", address.as_str());
-    span.record("create_canton", canton.as_str());
-    span.record("create_coordinates", coordinates.as_str());
-    span.record("idempotency_key", body.idempotency_key.as_str());
-
-    let mut transaction = match try_processing(
-        &redis_pool,
-        &pool,
-        body.idempotency_key.as_str(),
-        current_user.id,
-        &configuration.idempotency,
-    )
-    .await
-    .map_err(|e| match e {
-        IdempotencyError::ExpectedResponseNotFoundError => FarmError::DuplicateRequestConflict(e),
-        _ => FarmError::UnexpectedError(e.into()),
-    })? {
-        IdempotencyNextAction::ReturnSavedResponse(saved_response) => {
+    span.record("create_name", farm.name.as_str());
+    span.record("create_address", farm.address.as_str());
+    span.record("create_canton", farm.canton.as_str());
+    span.record("create_coordinates", farm.coordinates.as_str());
+    if let Some(idempotency_key) = &idempotency_key {
+        span.record("idempotency_key", idempotency_key.as_str());
+    }
+
+    // Keys kept in Postgres are claimed, and later saved, in the transaction,
+    // so every attempt claims the key again. Other engines claim it once.
+    let idempotency_in_postgres = idempotency_key.is_some()
+        && matches!(
+            configuration.idempotency.engine,
+            IdempotencyEngine::Postgres
+        );
+    let mut claimed = false;
+    let outcome = retry_serialization_failures(MAX_TRANSACTION_ATTEMPTS, async || {
+        in_transaction(&pool, &statements, async |transaction| {
+            if let Some(idempotency_key) = &idempotency_key
+                && (!claimed || idempotency_in_postgres)
+            {
+                let next_action = try_processing(
+                    &redis_pool,
+                    &memory_store,
+                    &statements,
+                    transaction,
+                    idempotency_key.as_str(),
+                    current_user.id,
+                    &request_hash,
+                    &configuration.idempotency,
+                )
+                .await
+                .map_err(|e| match e {
+                    IdempotencyError::ExpectedResponseNotFoundError
+                    | IdempotencyError::KeyInFlight { .. } => {
+                        FarmError::DuplicateRequestConflict(e)
+                    }
+                    IdempotencyError::PayloadMismatch => FarmError::IdempotencyKeyReused(e),
+                    IdempotencyError::KeyValidation(_) => FarmError::ValidationError(e.to_string()),
+                    _ => anyhow::Error::from(e).into(),
+                })?;
+                if let IdempotencyNextAction::ReturnSavedResponse(saved_response) = next_action {
+                    return Ok(CreateOutcome::Replayed(saved_response));
+                }
+                claimed = true;
+            }
+
+            let mut warnings: Vec<_> =
+                find_possible_duplicates(transaction, &statements, &farm.name, &farm.canton)
+                    .await?
+                    .into_iter()
+                    .map(|(farm_id, existing)| CreateFarmWarning::PossibleDuplicate {
+                        message: format!(
+                            "A farm named '{existing}' already exists in {}; is this a duplicate?",
+                            farm.canton
+                        ),
+                        farm_id,
+                        name: existing,
+                    })
+                    .collect();
+            if farm.canton_mismatch {
+                warnings.push(CreateFarmWarning::CantonMismatch {
+                    message: canton_mismatch_message(&farm.canton, &farm.nearest_capital_canton),
+                    nearest_capital_canton: farm.nearest_capital_canton.clone(),
+                });
+            }
+
+            let inserted = insert_validated_farm(
+                transaction,
+                &statements,
+                &farm,
+                AuditAction::Create,
+                current_user.id,
+                Some(request_id.into()),
+                &audit_payload,
+            )
+            .await?;
+
+            let response = HttpResponse::Created()
+                .insert_header((header::LOCATION, format!("/farms/{}", inserted.id)))
+                // Set before saving: a replayed response must match this one exactly.
+                .content_type(JSON_CONTENT_TYPE)
+                .json(CreateFarmResponse {
+                    id: inserted.id,
+                    warnings,
+                });
+            let response = match &idempotency_key {
+                Some(idempotency_key) => save_response(
+                    &redis_pool,
+                    &memory_store,
+                    &statements,
+                    transaction,
+                    idempotency_key.as_str(),
+                    current_user.id,
+                    &request_hash,
+                    &configuration.idempotency,
+                    response,
+                )
+                .await
+                .map_err(anyhow::Error::from)?,
+                None => response,
+            };
+            Ok::<_, FarmError>(CreateOutcome::Stored { response, inserted })
+        })
+        .await
+    })
+    .await?;
+    let (mut response, inserted) = match outcome {
+        CreateOutcome::Replayed(mut saved_response) => {
+            mark_replayed(&mut saved_response, true);
             return Ok(saved_response);
         }
-        IdempotencyNextAction::StartProcessing(transaction) => transaction,
+        CreateOutcome::Stored { response, inserted } => (response, inserted),
     };
+    // Set after saving, so the stored copy never carries it. An exempt
+    // request is never replayed, so it goes without.
+    if idempotency_key.is_some() {
+        mark_replayed(&mut response, false);
+    }
+
+    if let Some(cache) = ListCache::new(&redis_pool, &configuration.farms.list_cache) {
+        cache.invalidate().await;
+    }
+
+    // Only announce the farm once it is committed.
+    farm_events.publish(farm.into_event(&inserted));
+
+    Ok(response)
+}
+
+/// How the transaction of a `create` ended.
+enum CreateOutcome {
+    /// The key had a saved response, replayed instead of storing the farm.
+    Replayed(HttpResponse),
+    Stored {
+        response: HttpResponse,
+        inserted: InsertedFarm,
+    },
+}
+
+/// Sets `Idempotency-Replayed`, telling clients whether the response was
+/// produced now or replayed from an earlier request with the same key.
+fn mark_replayed(response: &mut HttpResponse, replayed: bool) {
+    response.headers_mut().insert(
+        header::HeaderName::from_static("idempotency-replayed"),
+        header::HeaderValue::from_static(if replayed { "true" } else { "false" }),
+    );
+}
 
-    let farm_id = insert_farm(&mut transaction, &name, &address, &canton, &coordinates).await?;
-    insert_farm_categories(&mut transaction, farm_id, &category_ids).await?;
-    insert_farm_products(&mut transaction, farm_id, &product_ids).await?;
-
-    let response = HttpResponse::Created().finish();
-    let (response, transaction) = save_response(
-        &redis_pool,
-        transaction,
-        body.idempotency_key.as_str(),
-        current_user.id,
-        &configuration.idempotency,
-        response,
+/// Existing farms in `canton` whose name is close to `name`, most similar
+/// first. `%` narrows the search through the trigram index.
+#[tracing::instrument(
+    name = "Looking for possible duplicate farms",
+    skip(connection, statements)
+)]
+async fn find_possible_duplicates(
+    connection: &mut PgConnection,
+    statements: &StatementCounter,
+    name: &Name,
+    canton: &Canton,
+) -> Result<Vec<(Uuid, String)>, FarmError> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT id, name
+        FROM farms
+        WHERE canton = $2 AND name % $1 AND similarity(name, $1) >= $3
+        ORDER BY similarity(name, $1) DESC, id
+        LIMIT 3
+        "#,
+        name.as_str(),
+        canton.as_str(),
+        DUPLICATE_NAME_SIMILARITY,
     )
+    .fetch_all(statements.on(connection))
     .await
-    .map_err(|e| FarmError::UnexpectedError(e.into()))?;
+    .context("Failed to look for farms with a similar name.")?;
 
-    transaction
-        .commit()
-        .await
-        .map_err(|e| FarmError::UnexpectedError(e.into()))?;
+    Ok(rows.into_iter().map(|row| (row.id, row.name)).collect())
+}
 
-    Ok(response)
+/// Stores `farm` with its category and product links, and records `action`
+/// by `user_id` in the audit log, all on `connection`.
+pub(super) async fn insert_validated_farm(
+    connection: &mut PgConnection,
+    statements: &StatementCounter,
+    farm: &ValidatedFarm,
+    action: AuditAction,
+    user_id: Uuid,
+    request_id: Option<Uuid>,
+    audit_payload: &serde_json::Value,
+) -> Result<InsertedFarm, FarmError> {
+    let inserted = insert_farm(
+        statements.on(&mut *connection),
+        &farm.name,
+        &farm.address,
+        &farm.canton,
+        &farm.coordinates,
+        &farm.nearest_capital_canton,
+        &farm.certifications,
+    )
+    .await?;
+    insert_farm_categories(
+        statements.on(&mut *connection),
+        inserted.id,
+        &farm.category_ids,
+    )
+    .await?;
+    insert_farm_products(
+        statements.on(&mut *connection),
+        inserted.id,
+        &farm.product_ids,
+    )
+    .await?;
+    audit::record(
+        statements.on(&mut *connection),
+        AuditEntry {
+            action,
+            farm_id: inserted.id,
+            user_id,
+            request_id,
+            payload: audit_payload,
+        },
+    )
+    .await?;
+
+    Ok(inserted)
 }
 
-#[tracing::instrument(name = "Saving new farm details in the database", skip(transaction))]
+#[tracing::instrument(name = "Saving new farm details in the database", skip(executor))]
 async fn insert_farm(
-    transaction: &mut Transaction<'_, Postgres>,
+    executor: impl Executor<'_, Database = Postgres>,
     name: &Name,
     address: &Address,
     canton: &Canton,
     coordinates: &Point,
-) -> Result<Uuid, FarmError> {
-    let farm_id = Uuid::new_v4();
-    let query = sqlx::query!(
+    nearest_capital_canton: &Canton,
+    certifications: &[Certification],
+) -> Result<InsertedFarm, FarmError> {
+    let farm = sqlx::query_as!(
+        InsertedFarm,
         r#"
-        INSERT INTO farms (id, name, address, canton, coordinates, created_at, updated_at)
-        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        INSERT INTO farms (
+            id, name, address, postal_code, canton, coordinates, nearest_capital_canton,
+            certifications, created_at, updated_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+        RETURNING id, created_at
         "#,
-        farm_id,
+        Uuid::new_v4(),
         name as &Name,
         address as &Address,
+        address.postal_code() as Option<PostalCode>,
         canton as &Canton,
         coordinates as &Point,
+        nearest_capital_canton as &Canton,
+        // No certifications recorded is stored as NULL, not an empty array.
+        (!certifications.is_empty()).then_some(certifications) as Option<&[Certification]>,
         Utc::now(),
         Option::<DateTime<Utc>>::None,
-    );
-    transaction
-        .execute(query)
-        .await
-        .context("Failed to insert new farm in the database.")?;
+    )
+    .fetch_one(executor)
+    .await
+    .context("Failed to insert new farm in the database.")?;
 
-    Ok(farm_id)
+    Ok(farm)
 }
 
-#[tracing::instrument(name = "Linking farm to categories", skip(transaction))]
+#[tracing::instrument(name = "Linking farm to categories", skip(executor))]
 async fn insert_farm_categories(
-    transaction: &mut Transaction<'_, Postgres>,
+    executor: impl Executor<'_, Database = Postgres>,
     farm_id: Uuid,
     category_ids: &[i16],
 ) -> Result<(), FarmError> {
@@ -185,7 +691,7 @@ async fn insert_farm_categories(
         farm_id,
         category_ids,
     );
-    transaction
+    executor
         .execute(query)
         .await
         .context("Failed to link farm to categories.")?;
@@ -193,9 +699,9 @@ async fn insert_farm_categories(
     Ok(())
 }
 
-#[tracing::instrument(name = "Linking farm to products", skip(transaction))]
+#[tracing::instrument(name = "Linking farm to products", skip(executor))]
 async fn insert_farm_products(
-    transaction: &mut Transaction<'_, Postgres>,
+    executor: impl Executor<'_, Database = Postgres>,
     farm_id: Uuid,
     product_ids: &[i32],
 ) -> Result<(), FarmError> {
@@ -209,7 +715,7 @@ async fn insert_farm_products(
         farm_id,
         product_ids,
     );
-    transaction
+    executor
         .execute(query)
         .await
         .context("Failed to link farm to products.")?;
@@ -0,0 +1,65 @@
+use crate::configuration::SharedSettings;
+use crate::domain::farm::{Categories, CategoriesParseOptions};
+use actix_web::{HttpResponse, web};
+
+/// The rules `POST /farms` holds `categories` to, for clients to validate
+/// against before submitting: the `Categories` limits, then the category slug
+/// shape. Each slug must also name a category of the product taxonomy.
+/// Modelled on JSON Schema's array and string keywords.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct CategoriesSchema {
+    /// Always `"array"`.
+    pub r#type: String,
+    /// `0`: the list may be empty when products are given, or to take
+    /// `farms.default_categories`.
+    pub min_items: usize,
+    pub max_items: usize,
+    /// Names must be unique ignoring case; `false` with
+    /// `farms.categories_case_sensitive`.
+    pub unique_items_case_insensitive: bool,
+    pub items: CategorySchema,
+    /// Cap on the names' combined length, in bytes once trimmed.
+    pub max_total_bytes: usize,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct CategorySchema {
+    /// Always `"string"`.
+    pub r#type: String,
+    /// Names are trimmed before any other check.
+    pub trimmed: bool,
+    /// Names are lowercased before `pattern` is checked.
+    pub lowercased: bool,
+    pub min_length: usize,
+    /// In UTF-8 bytes, not characters; slugs are ASCII, so the same here.
+    pub max_name_bytes: usize,
+    /// What a name must match once trimmed and lowercased.
+    pub pattern: String,
+}
+
+impl CategoriesSchema {
+    pub fn for_options(options: &CategoriesParseOptions) -> Self {
+        Self {
+            r#type: "array".to_string(),
+            min_items: 0,
+            max_items: Categories::MAX_CATEGORIES,
+            unique_items_case_insensitive: !options.case_sensitive,
+            items: CategorySchema {
+                r#type: "string".to_string(),
+                trimmed: true,
+                lowercased: true,
+                min_length: 1,
+                max_name_bytes: Categories::MAX_CATEGORY_NAME_LENGTH,
+                pattern: "^[a-z0-9-]+$".to_string(),
+            },
+            max_total_bytes: options.max_total_bytes,
+        }
+    }
+}
+
+/// `GET /farms/schema/categories` — the category validation rules, so a
+/// frontend can check input with the server's own limits.
+pub async fn categories_schema(configuration: web::Data<SharedSettings>) -> HttpResponse {
+    let options = configuration.load().farms.categories_parse_options();
+    HttpResponse::Ok().json(CategoriesSchema::for_options(&options))
+}
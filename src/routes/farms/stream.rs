@@ -0,0 +1,87 @@
+use crate::domain::farm::{Address, Canton, Name, Point, PostalCode};
+use actix_web::{HttpResponse, http::header, web, web::Bytes};
+use chrono::{DateTime, Utc};
+use std::convert::Infallible;
+use tokio::sync::broadcast;
+use tokio_stream::{
+    StreamExt,
+    wrappers::{BroadcastStream, errors::BroadcastStreamRecvError},
+};
+use uuid::Uuid;
+
+/// A newly created farm, as pushed to `GET /farms/stream` subscribers.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct FarmCreatedEvent {
+    pub id: Uuid,
+    pub name: Name,
+    pub address: Address,
+    pub postal_code: Option<PostalCode>,
+    pub canton: Canton,
+    pub coordinates: Point,
+    pub created_at: DateTime<Utc>,
+}
+
+impl FarmCreatedEvent {
+    /// The event as a Server-Sent Events frame.
+    fn to_sse_frame(&self) -> Bytes {
+        let data = serde_json::to_string(self).expect("Farm events always serialize to JSON.");
+        Bytes::from(format!("event: farm\ndata: {data}\n\n"))
+    }
+}
+
+/// In-process fan-out of farm events to every open stream.
+///
+/// Slow subscribers that fall more than `capacity` events behind skip the
+/// events they missed instead of holding back the publisher.
+pub struct FarmEvents {
+    sender: broadcast::Sender<FarmCreatedEvent>,
+}
+
+impl FarmEvents {
+    const DEFAULT_CAPACITY: usize = 64;
+
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        Self { sender }
+    }
+
+    /// Publish an event to the current subscribers, if any.
+    pub fn publish(&self, event: FarmCreatedEvent) {
+        // An error only means nobody is listening right now.
+        let _ = self.sender.send(event);
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<FarmCreatedEvent> {
+        self.sender.subscribe()
+    }
+}
+
+impl Default for FarmEvents {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_CAPACITY)
+    }
+}
+
+/// `GET /farms/stream` — Server-Sent Events feed of newly created farms.
+///
+/// The subscription lives as long as the response body: when the client
+/// disconnects, actix drops the body stream and with it the receiver.
+#[tracing::instrument(name = "Stream farm events", skip(events))]
+pub async fn stream(events: web::Data<FarmEvents>) -> HttpResponse {
+    let receiver = events.subscribe();
+
+    let farm_events = BroadcastStream::new(receiver).filter_map(|message| match message {
+        Ok(event) => Some(Ok::<_, Infallible>(event.to_sse_frame())),
+        Err(BroadcastStreamRecvError::Lagged(skipped)) => {
+            tracing::warn!(skipped, "Farm event subscriber lagged behind.");
+            None
+        }
+    });
+    // An initial comment flushes the headers so clients know they're subscribed.
+    let body = tokio_stream::once(Ok(Bytes::from_static(b": connected\n\n"))).chain(farm_events);
+
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header((header::CACHE_CONTROL, "no-cache"))
+        .streaming(body)
+}
@@ -0,0 +1,54 @@
+use crate::configuration::SharedSettings;
+use crate::domain::farm::{Canton, Point, PointError};
+use crate::errors::error_chain_fmt;
+use actix_web::{HttpResponse, ResponseError, http::StatusCode, web};
+use std::fmt::Formatter;
+
+#[derive(Debug, serde::Deserialize)]
+pub struct ValidateCoordinatesRequest {
+    /// `"latitude,longitude"`, as `POST /farms` takes it.
+    coordinates: String,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct ValidateCoordinatesResponse {
+    pub valid: bool,
+    /// The canton whose capital is closest: a hint, not the containing canton.
+    pub nearest_capital_canton: Canton,
+}
+
+#[derive(thiserror::Error)]
+pub enum GeoError {
+    #[error(transparent)]
+    InvalidCoordinates(#[from] PointError),
+}
+
+impl ResponseError for GeoError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            Self::InvalidCoordinates(_) => StatusCode::BAD_REQUEST,
+        }
+    }
+}
+
+impl std::fmt::Debug for GeoError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        error_chain_fmt(self, f)
+    }
+}
+
+/// Checks a coordinate the way `POST /farms` would, so a map picker can
+/// validate it before the whole farm is submitted.
+#[tracing::instrument(name = "Validate coordinates", skip(body, configuration))]
+pub async fn validate_coordinates(
+    body: web::Json<ValidateCoordinatesRequest>,
+    configuration: web::Data<SharedSettings>,
+) -> Result<HttpResponse, GeoError> {
+    let boundary = configuration.load().farms.swiss_boundary;
+    let point = Point::parse_within(&body.coordinates, boundary)?;
+
+    Ok(HttpResponse::Ok().json(ValidateCoordinatesResponse {
+        valid: true,
+        nearest_capital_canton: Canton::nearest_capital(&point),
+    }))
+}
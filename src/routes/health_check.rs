@@ -1,5 +1,28 @@
-use actix_web::HttpResponse;
+use actix_web::{HttpResponse, web};
+use std::time::Instant;
 
 pub async fn health_check() -> HttpResponse {
     HttpResponse::Ok().finish()
 }
+
+/// When the server started, for the uptime `GET /version` reports.
+#[derive(Debug, Clone, Copy)]
+pub struct StartedAt(pub Instant);
+
+/// `{"version": "0.3.0", "commit": "1a2b3c4d5e6f", "uptime_seconds": 42}`
+#[derive(Debug, serde::Serialize)]
+pub struct VersionInfo {
+    pub version: &'static str,
+    /// The git commit the binary was built from, or `unknown` (see `build.rs`).
+    pub commit: &'static str,
+    pub uptime_seconds: u64,
+}
+
+/// `GET /version` — which build is running and for how long.
+pub async fn version(started_at: web::Data<StartedAt>) -> HttpResponse {
+    HttpResponse::Ok().json(VersionInfo {
+        version: env!("CARGO_PKG_VERSION"),
+        commit: env!("GIT_COMMIT_HASH"),
+        uptime_seconds: started_at.0.elapsed().as_secs(),
+    })
+}
@@ -0,0 +1,9 @@
+use crate::metrics::PoolMetrics;
+use actix_web::{HttpResponse, web};
+
+/// `GET /metrics` — Prometheus scrape endpoint.
+pub async fn metrics(pool_metrics: web::Data<PoolMetrics>) -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type("text/plain; version=0.0.4; charset=utf-8")
+        .body(pool_metrics.render())
+}
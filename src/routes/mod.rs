@@ -1,7 +1,64 @@
 pub mod admin;
 pub mod authentication;
 pub mod farms;
+pub mod geo;
 mod health_check;
+mod metrics;
 pub mod suggestions;
 
+/// The `Content-Type` of every JSON body the API sends.
+pub const JSON_CONTENT_TYPE: &str = "application/json; charset=utf-8";
+
 pub use health_check::*;
+pub use metrics::*;
+
+/// Tells a missing or unknown field apart from a value of the wrong type or
+/// invalid JSON, returning the field, if named, and the message.
+///
+/// serde_json only names the field for missing and unknown ones; the field of
+/// a wrongly typed value is `path`, when the caller tracked it (see
+/// `serde_path_to_error`), otherwise its position is the best there is.
+pub fn describe_json_error(e: &serde_json::Error, path: Option<&str>) -> (Option<String>, String) {
+    let position = format!(" at line {} column {}", e.line(), e.column());
+    let message = e.to_string();
+    let detail = message.strip_suffix(&position).unwrap_or(&message);
+
+    if let Some(field) = detail
+        .strip_prefix("missing field `")
+        .and_then(|rest| rest.strip_suffix('`'))
+    {
+        return (Some(field.to_string()), format!("Missing field '{field}'."));
+    }
+    if let Some(rest) = detail.strip_prefix("unknown field `") {
+        let (field, expected) = rest.split_once("`, expected ").unwrap_or((rest, ""));
+        let field = field.trim_end_matches('`');
+        let message = if expected.is_empty() {
+            format!("Unknown field '{field}'.")
+        } else {
+            format!(
+                "Unknown field '{field}', expected {}.",
+                expected.replace('`', "'")
+            )
+        };
+        return (Some(field.to_string()), message);
+    }
+    let message = match (e.classify(), path) {
+        (serde_json::error::Category::Data, Some(path)) => {
+            return (
+                Some(path.to_string()),
+                format!("Invalid value for field '{path}': {detail}."),
+            );
+        }
+        (serde_json::error::Category::Data, None) => format!(
+            "Invalid value at line {}, column {}: {detail}.",
+            e.line(),
+            e.column()
+        ),
+        _ => format!(
+            "Malformed JSON at line {}, column {}: {detail}.",
+            e.line(),
+            e.column()
+        ),
+    };
+    (None, message)
+}
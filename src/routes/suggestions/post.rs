@@ -1,6 +1,6 @@
 use crate::{
     authentication::CurrentUser,
-    configuration::Settings,
+    configuration::{Settings, SharedSettings},
     domain::suggestion::SuggestionAction,
     rate_limit::{RateLimitDecision, check_rate_limit},
     routes::suggestions::error::SuggestionError,
@@ -35,9 +35,10 @@ pub async fn submit_suggestion(
     pool: web::Data<PgPool>,
     redis_pool: web::Data<Pool>,
     taxonomy: web::Data<TaxonomySnapshot>,
-    configuration: web::Data<Settings>,
+    configuration: web::Data<SharedSettings>,
     request: HttpRequest,
 ) -> Result<HttpResponse, SuggestionError> {
+    let configuration = configuration.load_full();
     let farm_id = path.into_inner();
     let body = body.into_inner();
 
@@ -1,29 +1,48 @@
 use crate::configuration::{
-    DatabaseSettings, RedisSettings, SessionSameSite, SessionSettings, Settings,
+    DatabaseSettings, RedisSettings, SessionSameSite, SessionSettings, Settings, SharedSettings,
 };
+use crate::domain::farm::CantonNames;
 use crate::email_client::EmailClient;
-use crate::routes::{admin, authentication, farms, health_check, suggestions};
+use crate::idempotency::MemoryIdempotencyStore;
+use crate::metrics::{POOL_METRICS_SAMPLE_INTERVAL, PoolMetrics, run_pool_metrics_sampler};
+use crate::rate_limit::ConcurrencyLimiter;
+use crate::routes::{
+    JSON_CONTENT_TYPE, StartedAt, admin, authentication, describe_json_error, farms, geo,
+    health_check, metrics, suggestions, version,
+};
+use crate::telemetry::FarmsRootSpanBuilder;
 use actix_session::{
     SessionMiddleware,
     config::{CookieContentSecurity, PersistentSession, TtlExtensionPolicy},
     storage::RedisSessionStore,
 };
 use actix_web::{
-    App, HttpServer,
+    App, HttpMessage, HttpRequest, HttpResponse, HttpServer, ResponseError,
+    body::{BoxBody, EitherBody, MessageBody},
     cookie::{Key, SameSite, time::Duration},
-    dev::Server,
+    dev::{Server, ServiceRequest, ServiceResponse},
+    error::{InternalError, JsonPayloadError},
+    http::{
+        StatusCode,
+        header::{self, ContentType, HeaderValue},
+    },
+    middleware::{ErrorHandlerResponse, ErrorHandlers, Next, from_fn},
     web,
     web::Data,
 };
 use anyhow::Context;
-use deadpool_redis::{Config, Pool, Runtime};
+use deadpool_redis::{
+    Manager, Pool, Runtime,
+    redis::{Client, TlsCertificates},
+};
 use secrecy::ExposeSecret;
 use sqlx::{PgPool, postgres::PgPoolOptions};
-use std::net::TcpListener;
-use tracing_actix_web::TracingLogger;
+use std::{net::TcpListener, num::NonZeroUsize};
+use tracing_actix_web::{RequestId, TracingLogger};
 
 pub struct Application {
     port: u16,
+    workers: usize,
     server: Server,
 }
 
@@ -33,6 +52,7 @@ impl Application {
         let connection_pool = get_connection_pool(&configuration.database);
         let redis_pool = get_redis_connection_pool(&configuration.redis)
             .expect("Failed to create Redis connection pool");
+        warm_up_redis(&redis_pool, &configuration.redis).await?;
 
         let address = format!(
             "{}:{}",
@@ -41,15 +61,37 @@ impl Application {
         let listener = TcpListener::bind(address).expect("Failed to bind port");
         let port = listener.local_addr()?.port();
 
-        let server = run(listener, configuration, connection_pool, redis_pool).await?;
+        let workers = match configuration.application.workers {
+            Some(0) => anyhow::bail!("application.workers must be at least 1."),
+            Some(workers) => workers,
+            None => std::thread::available_parallelism().map_or(1, NonZeroUsize::get),
+        };
 
-        Ok(Self { port, server })
+        let server = run(
+            listener,
+            workers,
+            configuration,
+            connection_pool,
+            redis_pool,
+        )
+        .await?;
+
+        Ok(Self {
+            port,
+            workers,
+            server,
+        })
     }
 
     pub fn port(&self) -> u16 {
         self.port
     }
 
+    /// The number of HTTP worker threads serving requests.
+    pub fn workers(&self) -> usize {
+        self.workers
+    }
+
     pub async fn run_until_stopped(self) -> Result<(), std::io::Error> {
         self.server.await
     }
@@ -67,9 +109,9 @@ pub fn get_connection_pool(configuration: &DatabaseSettings) -> PgPool {
 
 pub fn get_redis_connection_pool(configuration: &RedisSettings) -> Result<Pool, anyhow::Error> {
     let max_connections = configuration.pool_max_size.unwrap_or(10);
-    let config = Config::from_url(configuration.uri.expose_secret());
-    let pool = config
-        .builder()?
+    let client = get_redis_client(configuration)?;
+    let manager = Manager::new(client.get_connection_info().clone())?;
+    let pool = Pool::builder(manager)
         .max_size(max_connections)
         .runtime(Runtime::Tokio1)
         .build()?;
@@ -77,6 +119,86 @@ pub fn get_redis_connection_pool(configuration: &RedisSettings) -> Result<Pool,
     Ok(pool)
 }
 
+/// Opens and pings a Redis connection before the server starts, so the first
+/// request doesn't pay for the connection or find Redis not ready yet. Tries
+/// `warmup_attempts` times, doubling the pause in between; if Redis is still
+/// unreachable, startup fails unless `fail_open` is set, in which case it only
+/// logs a warning.
+pub async fn warm_up_redis(
+    pool: &Pool,
+    configuration: &RedisSettings,
+) -> Result<(), anyhow::Error> {
+    let attempts = configuration.warmup_attempts.unwrap_or(3).max(1);
+    let timeout = std::time::Duration::from_secs(configuration.timeout_seconds.unwrap_or(5));
+    let mut pause = std::time::Duration::from_millis(100);
+
+    let mut attempt = 1;
+    let error = loop {
+        let error = match tokio::time::timeout(timeout, ping_redis(pool)).await {
+            Ok(Ok(())) => return Ok(()),
+            Ok(Err(e)) => e,
+            Err(_) => anyhow::anyhow!("Timed out after {}s.", timeout.as_secs()),
+        };
+        if attempt == attempts {
+            break error;
+        }
+        tracing::warn!(error = %error, attempt, "Redis not ready yet; retrying.");
+        tokio::time::sleep(pause).await;
+        pause *= 2;
+        attempt += 1;
+    };
+
+    if configuration.fail_open {
+        tracing::warn!(
+            error = %error,
+            "Redis unreachable at startup; starting anyway (redis.fail_open)."
+        );
+        return Ok(());
+    }
+    Err(error.context(format!(
+        "Redis unreachable after {attempts} attempt(s); set redis.fail_open to start without it."
+    )))
+}
+
+async fn ping_redis(pool: &Pool) -> Result<(), anyhow::Error> {
+    let mut connection = pool
+        .get()
+        .await
+        .context("Failed to get a Redis connection.")?;
+    deadpool_redis::redis::cmd("PING")
+        .query_async::<()>(&mut connection)
+        .await
+        .context("Redis PING failed.")
+}
+
+/// The Redis client for `configuration.uri`. A `rediss://` URI connects over
+/// TLS and verifies the server certificate, against `ca_certificate_path`
+/// when set and the system roots otherwise.
+pub fn get_redis_client(configuration: &RedisSettings) -> Result<Client, anyhow::Error> {
+    let uri = configuration.uri.expose_secret();
+    let Some(ca_certificate_path) = &configuration.ca_certificate_path else {
+        return Client::open(uri).context("Invalid Redis URI.");
+    };
+
+    if !uri.starts_with("rediss://") {
+        anyhow::bail!("redis.ca_certificate_path requires a rediss:// URI.");
+    }
+    let root_cert = std::fs::read(ca_certificate_path).with_context(|| {
+        format!(
+            "Failed to read the Redis CA certificate at {}.",
+            ca_certificate_path.display()
+        )
+    })?;
+    Client::build_with_tls(
+        uri,
+        TlsCertificates {
+            client_tls: None,
+            root_cert: Some(root_cert),
+        },
+    )
+    .context("Failed to configure TLS for Redis.")
+}
+
 /// Convert the session cookie policy from our application configuration
 /// into the `SameSite` type expected by Actix.
 fn to_same_site(value: &SessionSameSite) -> SameSite {
@@ -137,9 +259,220 @@ fn build_session_middleware(
         .build()
 }
 
+/// Turns a rejected JSON body into a plain-text 400 that says what was wrong,
+/// instead of actix's generic "Json deserialize error: …".
+fn json_error_handler(err: JsonPayloadError, _req: &HttpRequest) -> actix_web::Error {
+    let message = match &err {
+        JsonPayloadError::Deserialize(e) => describe_json_error(e, None).1,
+        other => other.to_string(),
+    };
+    let response = HttpResponse::build(err.status_code())
+        .insert_header(ContentType::plaintext())
+        .body(message);
+    InternalError::from_response(err, response).into()
+}
+
+/// `json_error_handler` for `/farms`, whose errors are all `FarmError`s: a
+/// body that doesn't deserialize is a `VALIDATION_ERROR`, naming the field
+/// when serde does, and one that can't be read at all (wrong content type,
+/// too large, cut short) is one too, with the status actix gives it.
+fn farm_json_error_handler(err: JsonPayloadError, _req: &HttpRequest) -> actix_web::Error {
+    match &err {
+        JsonPayloadError::Deserialize(e) => farms::FarmError::invalid_json(e, None),
+        other => farms::FarmError::RejectedPayload {
+            status: other.status_code(),
+            message: other.to_string(),
+        },
+    }
+    .into()
+}
+
+/// Finishes error responses on their way out. A `FarmError` is answered with
+/// its JSON body (see `FarmError::error_body`) at
+/// `application.error_verbosity`, the verbose one carrying the request id. With
+/// `application.hide_internal_errors` set, a `500` names only the request id,
+/// whatever the verbosity, so the underlying error ends up in the logs alone.
+/// A `503` is told when to retry from `application.retry_after_seconds`, a
+/// `409` for a key in flight at most `idempotency.in_flight_retry_after_seconds`
+/// from now. The error stays attached to the response for the logger.
+fn render_error<B>(mut response: ServiceResponse<B>) -> actix_web::Result<ErrorHandlerResponse<B>> {
+    if response.status() == StatusCode::SERVICE_UNAVAILABLE {
+        let base = response
+            .request()
+            .app_data::<Data<SharedSettings>>()
+            .and_then(|settings| settings.load().application.retry_after_seconds)
+            .unwrap_or(farms::RETRY_AFTER_SECONDS);
+        response.headers_mut().insert(
+            header::RETRY_AFTER,
+            HeaderValue::from(service_unavailable_retry_after(base)),
+        );
+    }
+    let request_id = response
+        .request()
+        .extensions()
+        .get::<RequestId>()
+        .map(ToString::to_string);
+    let (hide_internal_errors, verbosity) = response
+        .request()
+        .app_data::<Data<SharedSettings>>()
+        .map(|settings| {
+            let application = &settings.load().application;
+            (
+                application.hide_internal_errors,
+                application.error_verbosity,
+            )
+        })
+        .unwrap_or_default();
+    let hide = response.status() == StatusCode::INTERNAL_SERVER_ERROR && hide_internal_errors;
+    let in_flight_cap = response
+        .request()
+        .app_data::<Data<SharedSettings>>()
+        .and_then(|settings| settings.load().idempotency.in_flight_retry_after_seconds)
+        .unwrap_or(farms::IN_FLIGHT_RETRY_AFTER_SECONDS);
+    let retry_after = response
+        .response()
+        .error()
+        .and_then(|e| e.as_error::<farms::FarmError>())
+        .and_then(|error| error.retry_after_seconds(in_flight_cap));
+    if let Some(seconds) = retry_after {
+        response
+            .headers_mut()
+            .insert(header::RETRY_AFTER, HeaderValue::from(seconds));
+    }
+    let farm_error = response
+        .response()
+        .error()
+        .and_then(|e| e.as_error::<farms::FarmError>());
+
+    let (content_type, body) = match (farm_error, hide) {
+        (Some(error), hide) => {
+            let body = if hide {
+                farms::FarmErrorBody::hidden(error.error_code(), request_id)
+            } else {
+                error.error_body(verbosity, request_id)
+            };
+            let body = serde_json::to_string(&body).expect("Error bodies always serialize.");
+            (JSON_CONTENT_TYPE, body)
+        }
+        (None, true) => {
+            let body = match request_id {
+                Some(request_id) => format!("Internal error, reference {request_id}."),
+                None => "Internal error.".to_string(),
+            };
+            ("text/plain; charset=utf-8", body)
+        }
+        (None, false) => {
+            return Ok(ErrorHandlerResponse::Response(
+                response.map_into_left_body(),
+            ));
+        }
+    };
+
+    let mut response = response.map_body(|_, _| EitherBody::right(BoxBody::new(body)));
+    response
+        .headers_mut()
+        .insert(header::CONTENT_TYPE, HeaderValue::from_static(content_type));
+    Ok(ErrorHandlerResponse::Response(response))
+}
+
+/// `base` seconds plus up to as many again at random, so clients turned away
+/// by the same outage don't all come back at the same moment.
+fn service_unavailable_retry_after(base: u64) -> u64 {
+    base + rand::random_range(0..=base)
+}
+
+/// Adds the charset to every bare `application/json` response, including
+/// those built with `HttpResponse::json` and actix's own error bodies, so the
+/// API answers with one JSON content type throughout.
+async fn json_charset(
+    request: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> actix_web::Result<ServiceResponse<impl MessageBody>> {
+    let mut response = next.call(request).await?;
+    let headers = response.headers_mut();
+    if headers
+        .get(header::CONTENT_TYPE)
+        .is_some_and(|value| value == "application/json")
+    {
+        headers.insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static(JSON_CONTENT_TYPE),
+        );
+    }
+    Ok(response)
+}
+
+/// Turns a write away with `429` while its client IP already has
+/// `application.max_concurrent_writes_per_ip` writes in flight. The slot is
+/// held until the handler returns or is dropped.
+async fn limit_concurrent_writes(
+    request: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> actix_web::Result<ServiceResponse<impl MessageBody>> {
+    let max_in_flight = request
+        .app_data::<Data<SharedSettings>>()
+        .and_then(|settings| settings.load().application.max_concurrent_writes_per_ip);
+    let permit = match (
+        request.app_data::<Data<ConcurrencyLimiter>>(),
+        max_in_flight,
+    ) {
+        (Some(limiter), Some(max_in_flight)) if !request.method().is_safe() => {
+            let client_ip = request
+                .connection_info()
+                .realip_remote_addr()
+                .unwrap_or("unknown")
+                .to_string();
+            Some(limiter.try_acquire(&client_ip, max_in_flight)?)
+        }
+        _ => None,
+    };
+    let response = next.call(request).await;
+    drop(permit);
+    response
+}
+
+/// Re-reads the configuration on every `SIGHUP` and swaps in its reloadable
+/// options. A configuration that fails to load, or whose default categories
+/// aren't in `taxonomy`, is logged and ignored.
+#[cfg(unix)]
+async fn reload_settings_on_sighup(
+    settings: Data<SharedSettings>,
+    taxonomy: Data<crate::taxonomy::TaxonomySnapshot>,
+) {
+    use crate::configuration::{get_configuration, reload_settings};
+    use tokio::signal::unix::{SignalKind, signal};
+
+    let mut hangups = match signal(SignalKind::hangup()) {
+        Ok(hangups) => hangups,
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to listen for SIGHUP; settings won't reload.");
+            return;
+        }
+    };
+    while hangups.recv().await.is_some() {
+        let fresh = get_configuration()
+            .map_err(|e| e.to_string())
+            .and_then(|fresh| fresh.farms.validate_against(&taxonomy).map(|()| fresh));
+        match fresh {
+            Ok(fresh) => {
+                reload_settings(&settings, &fresh);
+                tracing::info!("Reloaded settings on SIGHUP.");
+            }
+            Err(e) => {
+                tracing::error!(error = %e, "Failed to reload settings; keeping the current ones.")
+            }
+        }
+    }
+}
+
+/// Largest JSON or form request body accepted, in bytes. A body declaring a
+/// larger `Content-Length` is answered with `413` before any of it is read.
+pub const MAX_BODY_BYTES: usize = 64 * 1024;
+
 /// Build and run the Actix HTTP server.
 pub async fn run(
     listener: TcpListener,
+    workers: usize,
     configuration: Settings,
     db_pool: PgPool,
     redis_pool: Pool,
@@ -162,29 +495,100 @@ pub async fn run(
         products = taxonomy.len(),
         "Loaded product taxonomy snapshot."
     );
+    configuration
+        .farms
+        .validate_against(&taxonomy)
+        .map_err(anyhow::Error::msg)?;
     let taxonomy = Data::new(taxonomy);
 
+    // Canton names as the `cantons` table has them, for `canton_name`.
+    let canton_names = CantonNames::load(&db_pool)
+        .await
+        .context("Failed to load the canton names.")?;
+    let canton_names = Data::new(canton_names);
+
+    // Sample once up front so the first scrape already has real values.
+    let pool_metrics = Data::new(PoolMetrics::default());
+    pool_metrics.sample(&db_pool, &redis_pool);
+    tokio::spawn(run_pool_metrics_sampler(
+        pool_metrics.clone(),
+        db_pool.clone(),
+        redis_pool.clone(),
+        POOL_METRICS_SAMPLE_INTERVAL,
+    ));
+
+    let email_client = EmailClient::from_settings(&configuration.email_client)?;
+    let email_client = Data::new(email_client);
+
+    // Only consulted when the idempotency engine is `memory`.
+    let memory_idempotency_store = Data::new(MemoryIdempotencyStore::new(
+        std::time::Duration::from_secs(configuration.idempotency.ttl_seconds),
+    ));
+
     // Wrap the connection in a smart pointer
     let db_pool = Data::new(db_pool);
     let redis_pool = Data::new(redis_pool);
-    let configuration = Data::new(configuration);
+    let configuration = Data::new(SharedSettings::from_pointee(configuration));
+    #[cfg(unix)]
+    tokio::spawn(reload_settings_on_sighup(
+        configuration.clone(),
+        taxonomy.clone(),
+    ));
 
-    let email_client = EmailClient::from_settings(&configuration.email_client)?;
-    let email_client = Data::new(email_client);
+    // Shared by every worker, so the cap holds per IP across the server.
+    let write_limiter = Data::new(ConcurrencyLimiter::default());
+
+    // Fan-out for `GET /farms/stream`, fed by farm creation.
+    let farm_events = Data::new(farms::FarmEvents::default());
+
+    let started_at = Data::new(StartedAt(std::time::Instant::now()));
 
     // Capture the `connection` from the surrounding environment
     let server = HttpServer::new(move || {
         App::new()
             // Middlewares are added using the `wrap` method on `App`
+            .wrap(from_fn(limit_concurrent_writes))
             .wrap(build_session_middleware(
                 session_store.clone(),
                 &session_settings,
             ))
-            .wrap(TracingLogger::default())
+            .wrap(ErrorHandlers::new().default_handler(render_error))
+            .wrap(from_fn(json_charset))
+            .wrap(TracingLogger::<FarmsRootSpanBuilder>::new())
             .route("/health_check", web::get().to(health_check))
-            .route("/farms", web::post().to(farms::create))
-            .route("/farms", web::get().to(farms::get_all))
+            .route("/version", web::get().to(version))
+            .route("/metrics", web::get().to(metrics))
+            .service(
+                web::resource("/farms")
+                    .app_data(
+                        web::JsonConfig::default()
+                            .limit(MAX_BODY_BYTES)
+                            .error_handler(farm_json_error_handler),
+                    )
+                    .route(web::post().to(farms::create))
+                    .route(web::get().to(farms::get_all)),
+            )
+            // The directory again: every `GET /farms` filter, ANDed together.
+            .route("/farms/search", web::get().to(farms::get_all))
+            .route("/farms/stream", web::get().to(farms::stream))
+            .route("/farms/export.json", web::get().to(farms::export))
+            .service(
+                web::resource("/farms/import")
+                    .app_data(web::PayloadConfig::new(farms::MAX_IMPORT_BYTES))
+                    .route(web::post().to(farms::import)),
+            )
+            .route("/farms/count", web::get().to(farms::count))
+            .route(
+                "/farms/reindex-coordinates",
+                web::post().to(admin::reindex_coordinates),
+            )
+            .route(
+                "/farms/schema/categories",
+                web::get().to(farms::categories_schema),
+            )
             .route("/farms/{id}", web::get().to(farms::get_by_id))
+            .route("/farms/{id}/neighbors", web::get().to(farms::get_neighbors))
+            .route("/farms/{id}/history", web::get().to(farms::get_history))
             .route(
                 "/farms/{id}/product-suggestions",
                 web::post().to(suggestions::submit_suggestion),
@@ -201,6 +605,7 @@ pub async fn run(
                 "/admin/product-suggestions/{id}/reject",
                 web::post().to(admin::reject),
             )
+            .route("/geo/validate", web::post().to(geo::validate_coordinates))
             .route("/login", web::post().to(authentication::log_in))
             .route("/logout", web::post().to(authentication::log_out))
             .route("/me", web::get().to(authentication::get_me))
@@ -209,13 +614,26 @@ pub async fn run(
                 "/verify-email",
                 web::post().to(authentication::verify_email),
             )
+            .app_data(
+                web::JsonConfig::default()
+                    .limit(MAX_BODY_BYTES)
+                    .error_handler(json_error_handler),
+            )
+            .app_data(web::FormConfig::default().limit(MAX_BODY_BYTES))
             .app_data(email_client.clone())
             // Get pointer copy and attach it to the application state
             .app_data(db_pool.clone())
             .app_data(configuration.clone())
             .app_data(redis_pool.clone())
+            .app_data(memory_idempotency_store.clone())
             .app_data(taxonomy.clone())
+            .app_data(canton_names.clone())
+            .app_data(farm_events.clone())
+            .app_data(write_limiter.clone())
+            .app_data(pool_metrics.clone())
+            .app_data(started_at.clone())
     })
+    .workers(workers)
     .listen(listener)?
     .run();
 
@@ -1,4 +1,10 @@
 use crate::configuration::{LogFormat, LoggingSettings, TelemetrySettings};
+use crate::db::StatementCounter;
+use actix_web::{
+    HttpMessage,
+    body::MessageBody,
+    dev::{ServiceRequest, ServiceResponse},
+};
 #[cfg(feature = "opentelemetry")]
 use opentelemetry::{KeyValue, global, trace::TracerProvider};
 #[cfg(feature = "opentelemetry")]
@@ -10,7 +16,8 @@ use opentelemetry_sdk::{
     trace::{self, SdkTracerProvider, Tracer},
 };
 use tokio::task::JoinHandle;
-use tracing::{Subscriber, subscriber::set_global_default};
+use tracing::{Span, Subscriber, subscriber::set_global_default};
+use tracing_actix_web::{DefaultRootSpanBuilder, RootSpanBuilder};
 use tracing_bunyan_formatter::{BunyanFormattingLayer, JsonStorageLayer};
 use tracing_log::LogTracer;
 #[cfg(feature = "opentelemetry")]
@@ -191,6 +198,29 @@ fn init_opentelemetry(settings: &TelemetrySettings) -> Result<Tracer, anyhow::Er
     Ok(tracer)
 }
 
+/// The default request span, plus `db_statements`: how many SQL statements
+/// the handler recorded on the request's `StatementCounter`.
+pub struct FarmsRootSpanBuilder;
+
+impl RootSpanBuilder for FarmsRootSpanBuilder {
+    fn on_request_start(request: &ServiceRequest) -> Span {
+        request.extensions_mut().insert(StatementCounter::default());
+        tracing_actix_web::root_span!(request, db_statements = tracing::field::Empty)
+    }
+
+    fn on_request_end<B: MessageBody>(
+        span: Span,
+        outcome: &Result<ServiceResponse<B>, actix_web::Error>,
+    ) {
+        if let Ok(response) = outcome
+            && let Some(statements) = response.request().extensions().get::<StatementCounter>()
+        {
+            span.record("db_statements", statements.count());
+        }
+        DefaultRootSpanBuilder::on_request_end(span, outcome);
+    }
+}
+
 // Just copied trait bounds and signature from `spawn_blocking`
 pub fn spawn_blocking_with_tracing<F, R>(f: F) -> JoinHandle<R>
 where
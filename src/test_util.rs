@@ -0,0 +1,154 @@
+//! Helpers for building valid farms in tests, for this crate's integration
+//! tests and for downstream crates alike.
+//!
+//! Only compiled with the `test-util` feature, so production builds don't
+//! carry them (or their `fake` dependency).
+
+use crate::domain::farm::{Address, Canton, Name, Point};
+use chrono::{DateTime, Utc};
+use fake::{
+    Fake,
+    faker::{address::de_de::StreetName, name::de_de::Name as FakerName},
+};
+use rand::RngExt;
+use uuid::Uuid;
+
+/// A farm's own fields, each already parsed by its domain type. The taxonomy
+/// (categories/products) is a separate dimension, linked explicitly per test.
+#[derive(Debug, Clone)]
+pub struct TestFarm {
+    pub id: Uuid,
+    pub name: Name,
+    pub address: Address,
+    pub canton: Canton,
+    pub coordinates: Point,
+    pub created_at: DateTime<Utc>,
+}
+
+impl TestFarm {
+    pub fn with_name(mut self, name: Name) -> Self {
+        self.name = name;
+        self
+    }
+
+    pub fn with_address(mut self, address: Address) -> Self {
+        self.address = address;
+        self
+    }
+
+    pub fn with_canton(mut self, canton: Canton) -> Self {
+        self.canton = canton;
+        self
+    }
+
+    pub fn with_coordinates(mut self, coordinates: Point) -> Self {
+        self.coordinates = coordinates;
+        self
+    }
+
+    /// The `POST /farms` body for this farm. Every farm needs at least one
+    /// classification; this one lists the `strawberries` product, so the
+    /// taxonomy must be seeded first.
+    pub fn to_json(&self, idempotency_key: Uuid) -> serde_json::Value {
+        serde_json::json!({
+            "name": self.name,
+            "address": self.address,
+            "canton": self.canton,
+            "coordinates": self.coordinates,
+            "products": ["strawberries"],
+            "idempotency_key": idempotency_key.to_string(),
+        })
+    }
+}
+
+/// A random coordinate within Switzerland's bounding box, as `"lat,lon"`.
+pub fn generate_swiss_coordinates() -> String {
+    let lat = 45.8 + (rand::random::<f64>() * (47.9 - 45.8));
+    let lon = 5.9 + (rand::random::<f64>() * (10.6 - 5.9));
+    format!("{:.4},{:.4}", lat, lon)
+}
+
+/// A random one of the 26 cantons.
+pub fn generate_swiss_canton() -> Canton {
+    let cantons = [
+        "ZH", "BE", "LU", "UR", "SZ", "OW", "NW", "GL", "ZG", "FR", "SO", "BS", "BL", "SH", "AR",
+        "AI", "SG", "GR", "AG", "TG", "TI", "VD", "VS", "NE", "GE", "JU",
+    ];
+
+    let mut rng = rand::rng();
+    let index = rng.random_range(0..cantons.len());
+    Canton::parse(cantons[index].to_string()).expect("Generated invalid canton")
+}
+
+/// A random point within about a kilometre of `canton`'s capital, so it
+/// passes the canton cross-check.
+pub fn generate_coordinates_in(canton: &Canton) -> Point {
+    let capital = canton.capital_point();
+    let mut rng = rand::rng();
+    Point::new(
+        capital.latitude() + rng.random_range(-0.01..0.01),
+        capital.longitude() + rng.random_range(-0.01..0.01),
+    )
+}
+
+/// A random farm that passes every domain validation, with coordinates
+/// matching its canton.
+pub fn generate_valid_farm() -> TestFarm {
+    let name = Name::parse(FakerName().fake()).expect("Generated invalid farm name");
+    let address = Address::parse(StreetName().fake()).expect("Generated invalid address");
+    let canton = generate_swiss_canton();
+    let coordinates = generate_coordinates_in(&canton);
+
+    TestFarm {
+        id: Uuid::new_v4(),
+        name,
+        address,
+        canton,
+        coordinates,
+        created_at: Utc::now(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::generate_valid_farm;
+    use crate::domain::farm::{Address, Canton, Name, Point};
+    use claims::assert_ok;
+    use uuid::Uuid;
+
+    #[test]
+    fn generated_farms_pass_every_domain_validation() {
+        for _ in 0..100 {
+            let farm = generate_valid_farm();
+
+            assert_ok!(Name::parse(farm.name.as_ref().to_string()));
+            assert_ok!(Address::parse(farm.address.as_ref().to_string()));
+            assert_ok!(Canton::parse(farm.canton.as_ref().to_string()));
+            assert_ok!(Point::parse(&farm.coordinates.to_string()));
+        }
+    }
+
+    #[test]
+    fn builders_replace_a_single_field() {
+        let farm = generate_valid_farm();
+        let geneva = Canton::parse("GE".to_string()).unwrap();
+
+        let changed = farm.clone().with_canton(geneva.clone());
+
+        assert_eq!(changed.canton, geneva);
+        assert_eq!(changed.name, farm.name);
+        assert_eq!(changed.id, farm.id);
+    }
+
+    #[test]
+    fn json_body_round_trips_through_the_domain_types() {
+        let farm = generate_valid_farm();
+
+        let body = farm.to_json(Uuid::new_v4());
+
+        let name: Name = serde_json::from_value(body["name"].clone()).unwrap();
+        let canton: Canton = serde_json::from_value(body["canton"].clone()).unwrap();
+        assert_eq!(name, farm.name);
+        assert_eq!(canton, farm.canton);
+    }
+}
@@ -0,0 +1,91 @@
+use crate::helpers::spawn_app_with;
+use actix_web::http::StatusCode;
+use farms::{configuration::IdempotencyEngine, test_util::generate_valid_farm};
+use sqlx::Executor;
+use std::time::Duration;
+use uuid::Uuid;
+
+#[tokio::test]
+async fn writes_over_the_per_ip_cap_are_rejected_while_others_are_in_flight() {
+    // Arrange
+    let app = spawn_app_with(IdempotencyEngine::None, |c| {
+        c.application.max_concurrent_writes_per_ip = Some(2)
+    })
+    .await;
+    app.log_in_active_user().await;
+    // Hold every insert until the requests over the cap have been answered.
+    let mut lock = app.db_pool.begin().await.unwrap();
+    lock.execute("LOCK TABLE farms IN ACCESS EXCLUSIVE MODE")
+        .await
+        .unwrap();
+
+    // Act
+    let requests: Vec<_> = (0..5)
+        .map(|_| {
+            let request = app
+                .api_client
+                .post(format!("{}/farms", app.address))
+                .json(&generate_valid_farm().to_json(Uuid::new_v4()));
+            tokio::spawn(async move { request.send().await.unwrap().status().as_u16() })
+        })
+        .collect();
+    tokio::time::timeout(Duration::from_secs(10), async {
+        while requests.iter().filter(|r| r.is_finished()).count() < 3 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("The writes over the cap were not turned away.");
+    lock.rollback().await.unwrap();
+
+    // Assert
+    let mut statuses = Vec::new();
+    for request in requests {
+        statuses.push(request.await.unwrap());
+    }
+    statuses.sort_unstable();
+    assert_eq!(
+        vec![
+            StatusCode::CREATED.as_u16(),
+            StatusCode::CREATED.as_u16(),
+            StatusCode::TOO_MANY_REQUESTS.as_u16(),
+            StatusCode::TOO_MANY_REQUESTS.as_u16(),
+            StatusCode::TOO_MANY_REQUESTS.as_u16(),
+        ],
+        statuses
+    );
+
+    // The slots were given back once the writes finished.
+    let response = app
+        .post_farm(&generate_valid_farm().to_json(Uuid::new_v4()))
+        .await;
+    assert_eq!(StatusCode::CREATED.as_u16(), response.status().as_u16());
+}
+
+#[tokio::test]
+async fn reads_are_not_capped() {
+    // Arrange
+    let app = spawn_app_with(IdempotencyEngine::None, |c| {
+        c.application.max_concurrent_writes_per_ip = Some(1)
+    })
+    .await;
+    let mut lock = app.db_pool.begin().await.unwrap();
+    lock.execute("LOCK TABLE farms IN ACCESS EXCLUSIVE MODE")
+        .await
+        .unwrap();
+
+    // Act
+    let requests: Vec<_> = (0..3)
+        .map(|_| {
+            let request = app.api_client.get(format!("{}/farms", app.address));
+            tokio::spawn(async move { request.send().await.unwrap().status().as_u16() })
+        })
+        .collect();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    lock.rollback().await.unwrap();
+
+    // Assert
+    for request in requests {
+        assert_eq!(StatusCode::OK.as_u16(), request.await.unwrap());
+    }
+}
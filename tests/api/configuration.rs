@@ -0,0 +1,433 @@
+use crate::helpers::{configure_database, spawn_app, spawn_app_with};
+use deadpool_redis::redis::ConnectionAddr;
+use farms::{
+    configuration::{
+        Environment, ErrorVerbosity, FarmsSettings, IdempotencyEngine, RedisSettings,
+        SharedSettings, get_configuration, get_configuration_from, reload_settings,
+    },
+    i18n::Language,
+    startup::{Application, get_redis_client, get_redis_connection_pool, warm_up_redis},
+    taxonomy::TaxonomySnapshot,
+};
+use secrecy::SecretString;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+/// A fresh copy of the repository's `configuration/` directory.
+fn copy_configuration_directory() -> PathBuf {
+    let source = Path::new(env!("CARGO_MANIFEST_DIR")).join("configuration");
+    let directory = std::env::temp_dir().join(format!("farms-config-{}", Uuid::new_v4()));
+    std::fs::create_dir_all(&directory).expect("Failed to create the configuration directory.");
+    for file in ["base.yaml", "local.yaml", "production.yaml"] {
+        std::fs::copy(source.join(file), directory.join(file))
+            .expect("Failed to copy a configuration file.");
+    }
+    directory
+}
+
+#[test]
+fn settings_load_from_a_custom_configuration_directory() {
+    // Arrange
+    let directory = copy_configuration_directory();
+
+    // Act
+    let settings = get_configuration_from(&directory);
+    let _ = std::fs::remove_dir_all(&directory);
+
+    // Assert
+    let settings = settings.expect("Failed to load the settings.");
+    assert!(!settings.database.database_name.is_empty());
+}
+
+#[test]
+fn get_configuration_reads_the_directory_in_app_config_dir() {
+    // Environment variables are process-wide, so the assertion runs in a
+    // child process of this test binary and leaves the other tests alone.
+    if let Some(expected) = std::env::var_os("FARMS_TEST_EXPECTED_DATABASE_NAME") {
+        let settings = get_configuration().expect("Failed to load the settings.");
+        assert_eq!(
+            expected.to_str(),
+            Some(settings.database.database_name.as_str())
+        );
+        return;
+    }
+
+    // Arrange
+    let directory = copy_configuration_directory();
+    let database_name = format!("farms_{}", Uuid::new_v4().simple());
+    let base = directory.join("base.yaml");
+    let contents = std::fs::read_to_string(&base).expect("Failed to read base.yaml.");
+    let contents = contents.replace(
+        "database_name: \"farms\"",
+        &format!("database_name: \"{}\"", database_name),
+    );
+    std::fs::write(&base, contents).expect("Failed to write base.yaml.");
+
+    // Act
+    let status = std::process::Command::new(std::env::current_exe().unwrap())
+        .args([
+            "--exact",
+            "configuration::get_configuration_reads_the_directory_in_app_config_dir",
+        ])
+        .env("APP_CONFIG_DIR", &directory)
+        .env("FARMS_TEST_EXPECTED_DATABASE_NAME", &database_name)
+        .env_remove("APP_DATABASE__DATABASE_NAME")
+        .status()
+        .expect("Failed to run the test binary.");
+    let _ = std::fs::remove_dir_all(&directory);
+
+    // Assert
+    assert!(status.success());
+}
+
+#[test]
+fn a_directory_without_configuration_files_is_rejected() {
+    let directory = std::env::temp_dir().join(format!("farms-config-{}", Uuid::new_v4()));
+
+    assert!(get_configuration_from(&directory).is_err());
+}
+
+#[test]
+fn production_settings_without_database_ssl_are_rejected() {
+    // Arrange
+    let directory = copy_configuration_directory();
+    let mut settings = get_configuration_from(&directory).expect("Failed to load the settings.");
+    let _ = std::fs::remove_dir_all(&directory);
+    settings.database.require_ssl = false;
+
+    // Act
+    let result = settings.validate(&Environment::Production);
+
+    // Assert
+    let error = result.expect_err("Production accepted a database without SSL.");
+    assert!(error.contains("require_ssl"));
+}
+
+#[test]
+fn local_settings_may_disable_database_ssl() {
+    // Arrange
+    let directory = copy_configuration_directory();
+    let mut settings = get_configuration_from(&directory).expect("Failed to load the settings.");
+    let _ = std::fs::remove_dir_all(&directory);
+    settings.database.require_ssl = false;
+
+    // Act & Assert
+    assert!(settings.validate(&Environment::Local).is_ok());
+    settings.database.require_ssl = true;
+    assert!(settings.validate(&Environment::Production).is_ok());
+}
+
+#[test]
+fn a_default_language_that_is_not_supported_is_rejected() {
+    // Arrange
+    let directory = copy_configuration_directory();
+    let mut settings = get_configuration_from(&directory).expect("Failed to load the settings.");
+    let _ = std::fs::remove_dir_all(&directory);
+    settings.languages.supported = vec![Language::De, Language::Fr];
+    settings.languages.default = Language::En;
+
+    // Act
+    let result = settings.validate(&Environment::Local);
+
+    // Assert
+    let error = result.expect_err("A default outside the supported languages was accepted.");
+    assert!(error.contains("languages.default"));
+}
+
+#[test]
+fn the_redis_engine_without_a_redis_uri_is_rejected() {
+    // Arrange
+    let directory = copy_configuration_directory();
+    let mut settings = get_configuration_from(&directory).expect("Failed to load the settings.");
+    let _ = std::fs::remove_dir_all(&directory);
+    settings.idempotency.engine = IdempotencyEngine::Redis;
+    settings.redis.uri = SecretString::from(" ");
+
+    // Act
+    let result = settings.validate(&Environment::Local);
+
+    // Assert
+    let error = result.expect_err("The redis engine was accepted without Redis.");
+    assert!(error.contains("redis.uri"), "{error}");
+}
+
+#[test]
+fn an_idempotency_engine_that_stores_keys_needs_a_ttl() {
+    // Arrange
+    let directory = copy_configuration_directory();
+    let mut settings = get_configuration_from(&directory).expect("Failed to load the settings.");
+    let _ = std::fs::remove_dir_all(&directory);
+    settings.idempotency.engine = IdempotencyEngine::Memory;
+    settings.idempotency.ttl_seconds = 0;
+
+    // Act
+    let result = settings.validate(&Environment::Local);
+
+    // Assert
+    let error = result.expect_err("Keys that expire at once were accepted.");
+    assert!(error.contains("idempotency.ttl_seconds"), "{error}");
+}
+
+#[test]
+fn a_zero_in_flight_retry_after_is_rejected() {
+    // Arrange
+    let directory = copy_configuration_directory();
+    let mut settings = get_configuration_from(&directory).expect("Failed to load the settings.");
+    let _ = std::fs::remove_dir_all(&directory);
+    settings.idempotency.in_flight_retry_after_seconds = Some(0);
+
+    // Act
+    let result = settings.validate(&Environment::Local);
+
+    // Assert
+    let error = result.expect_err("A Retry-After of 0 seconds was accepted.");
+    assert!(
+        error.contains("idempotency.in_flight_retry_after_seconds"),
+        "{error}"
+    );
+}
+
+#[test]
+fn a_negative_or_nan_canton_tolerance_is_rejected() {
+    // Arrange
+    let directory = copy_configuration_directory();
+    let mut settings = get_configuration_from(&directory).expect("Failed to load the settings.");
+    let _ = std::fs::remove_dir_all(&directory);
+
+    for tolerance_km in [-1.0, f64::NAN] {
+        settings.farms.canton_check.tolerance_km = tolerance_km;
+
+        // Act
+        let result = settings.validate(&Environment::Local);
+
+        // Assert
+        let error = result.expect_err("An invalid canton tolerance was accepted.");
+        assert!(error.contains("farms.canton_check.tolerance_km"), "{error}");
+    }
+}
+
+#[test]
+fn stray_settings_for_the_none_engine_only_warn() {
+    // Arrange
+    let directory = copy_configuration_directory();
+    let mut settings = get_configuration_from(&directory).expect("Failed to load the settings.");
+    let _ = std::fs::remove_dir_all(&directory);
+    assert!(settings.warnings().is_empty());
+    settings.idempotency.engine = IdempotencyEngine::None;
+    settings.idempotency.redis_key_prefix = "custom".to_string();
+    settings.idempotency.ttl_seconds = 60;
+
+    // Act & Assert
+    assert!(settings.validate(&Environment::Local).is_ok());
+    let warnings = settings.warnings();
+    assert_eq!(2, warnings.len(), "{warnings:?}");
+    assert!(warnings[0].contains("idempotency.redis_key_prefix"));
+    assert!(warnings[1].contains("idempotency.ttl_seconds"));
+}
+
+#[test]
+fn reloading_changes_reloadable_settings_only() {
+    // Arrange
+    let directory = copy_configuration_directory();
+    let initial = get_configuration_from(&directory).expect("Failed to load the settings.");
+    let shared = SharedSettings::from_pointee(initial.clone());
+    let mut local = std::fs::read_to_string(directory.join("local.yaml")).unwrap();
+    local.push_str(
+        "application:\n  port: 9123\n  hide_internal_errors: true\n  error_verbosity: minimal\n\
+         registration:\n  rate_limit:\n    max_requests: 42\n    window_seconds: 60\n",
+    );
+    std::fs::write(directory.join("local.yaml"), local).unwrap();
+
+    // Act
+    let fresh = get_configuration_from(&directory);
+    let _ = std::fs::remove_dir_all(&directory);
+    reload_settings(&shared, &fresh.expect("Failed to reload the settings."));
+
+    // Assert
+    let reloaded = shared.load();
+    assert_eq!(reloaded.registration.rate_limit.max_requests, 42);
+    assert_eq!(reloaded.registration.rate_limit.window_seconds, 60);
+    assert!(reloaded.application.hide_internal_errors);
+    assert_eq!(
+        ErrorVerbosity::Minimal,
+        reloaded.application.error_verbosity
+    );
+    assert_eq!(reloaded.application.port, initial.application.port);
+}
+
+fn redis_settings(uri: &str, ca_certificate_path: Option<&str>) -> RedisSettings {
+    RedisSettings {
+        uri: SecretString::from(uri),
+        ca_certificate_path: ca_certificate_path
+            .map(|path| Path::new(env!("CARGO_MANIFEST_DIR")).join(path)),
+        pool_max_size: None,
+        timeout_seconds: None,
+        session_key_prefix: "sess".to_string(),
+        fail_open: false,
+        warmup_attempts: None,
+    }
+}
+
+#[test]
+fn a_redis_uri_connects_in_plaintext() {
+    let client = get_redis_client(&redis_settings("redis://127.0.0.1:6379", None)).unwrap();
+
+    assert!(matches!(
+        client.get_connection_info().addr,
+        ConnectionAddr::Tcp(..)
+    ));
+}
+
+#[test]
+fn a_rediss_uri_connects_over_verified_tls() {
+    let settings = redis_settings("rediss://cache.example.com:6380", None);
+
+    let client = get_redis_client(&settings).unwrap();
+
+    assert!(matches!(
+        &client.get_connection_info().addr,
+        ConnectionAddr::TcpTls { host, port: 6380, insecure: false, tls_params: None }
+            if host == "cache.example.com"
+    ));
+    assert!(get_redis_connection_pool(&settings).is_ok());
+}
+
+#[test]
+fn a_custom_ca_is_used_to_verify_the_server() {
+    let settings = redis_settings(
+        "rediss://cache.example.com:6380",
+        Some("tests/fixtures/redis-test-ca.pem"),
+    );
+
+    let client = get_redis_client(&settings).unwrap();
+
+    assert!(matches!(
+        client.get_connection_info().addr,
+        ConnectionAddr::TcpTls {
+            insecure: false,
+            tls_params: Some(_),
+            ..
+        }
+    ));
+    assert!(get_redis_connection_pool(&settings).is_ok());
+}
+
+#[test]
+fn a_custom_ca_needs_a_rediss_uri_and_a_readable_file() {
+    let plaintext = redis_settings(
+        "redis://127.0.0.1:6379",
+        Some("tests/fixtures/redis-test-ca.pem"),
+    );
+    let missing = redis_settings(
+        "rediss://cache.example.com:6380",
+        Some("tests/fixtures/no-such-ca.pem"),
+    );
+
+    assert!(get_redis_client(&plaintext).is_err());
+    assert!(get_redis_client(&missing).is_err());
+}
+
+#[tokio::test]
+async fn the_configured_worker_count_is_used() {
+    let app = spawn_app_with(IdempotencyEngine::None, |c| {
+        c.application.workers = Some(3);
+    })
+    .await;
+
+    assert_eq!(3, app.workers);
+}
+
+#[tokio::test]
+async fn workers_default_to_the_number_of_logical_cpus() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+
+    let cpus = std::thread::available_parallelism().unwrap().get();
+    assert_eq!(cpus, app.workers);
+}
+
+/// Nothing listens on port 1, so connecting is refused straight away.
+const UNREACHABLE_REDIS: &str = "redis://127.0.0.1:1";
+
+#[tokio::test]
+async fn warm_up_pings_a_reachable_redis() {
+    let settings = redis_settings("redis://127.0.0.1:6379", None);
+    let pool = get_redis_connection_pool(&settings).unwrap();
+
+    warm_up_redis(&pool, &settings).await.unwrap();
+
+    assert_eq!(1, pool.status().size);
+}
+
+#[tokio::test]
+async fn boot_fails_clearly_against_an_unreachable_redis() {
+    let mut configuration = get_configuration().expect("Failed to read configuration.");
+    configuration.application.port = 0;
+    configuration.redis.uri = SecretString::from(UNREACHABLE_REDIS);
+    configuration.redis.fail_open = false;
+    configuration.redis.warmup_attempts = Some(2);
+
+    let Err(error) = Application::build(configuration).await else {
+        panic!("The application started without Redis.");
+    };
+
+    assert!(
+        error
+            .to_string()
+            .contains("Redis unreachable after 2 attempt(s)"),
+        "{error}"
+    );
+}
+
+#[tokio::test]
+async fn boot_succeeds_without_redis_when_failing_open() {
+    let app = spawn_app_with(IdempotencyEngine::None, |c| {
+        c.redis.uri = SecretString::from(UNREACHABLE_REDIS);
+        c.redis.fail_open = true;
+        c.redis.warmup_attempts = Some(1);
+    })
+    .await;
+
+    let response = app
+        .api_client
+        .get(format!("{}/health_check", &app.address))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert!(response.status().is_success());
+}
+
+#[tokio::test]
+async fn default_categories_are_checked_against_the_taxonomy() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    let taxonomy = TaxonomySnapshot::load(&app.db_pool).await.unwrap();
+    let farms = |slugs: &[&str]| FarmsSettings {
+        default_categories: slugs.iter().map(ToString::to_string).collect(),
+        ..FarmsSettings::default()
+    };
+
+    assert!(farms(&[]).validate_against(&taxonomy).is_ok());
+    assert!(farms(&["vegetables"]).validate_against(&taxonomy).is_ok());
+    assert_eq!(
+        Err("farms.default_categories names unknown categories: 'no-such-group'.".to_string()),
+        farms(&["vegetables", "no-such-group"]).validate_against(&taxonomy)
+    );
+}
+
+#[tokio::test]
+async fn boot_fails_on_an_unknown_default_category() {
+    let mut configuration = get_configuration().expect("Failed to read configuration.");
+    configuration.database.database_name = Uuid::new_v4().to_string();
+    configuration.application.port = 0;
+    configuration.farms.default_categories = vec!["no-such-group".to_string()];
+    configure_database(&configuration.database)
+        .await
+        .close()
+        .await;
+
+    let Err(error) = Application::build(configuration).await else {
+        panic!("The application started with an unknown default category.");
+    };
+
+    assert!(error.to_string().contains("'no-such-group'"), "{error}");
+}
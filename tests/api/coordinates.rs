@@ -0,0 +1,146 @@
+use crate::helpers::{insert_test_farm, spawn_app, spawn_app_with};
+use actix_web::http::StatusCode;
+use farms::{
+    configuration::IdempotencyEngine,
+    domain::farm::{Point, SwissBoundary},
+};
+use sqlx::Row;
+
+#[tokio::test]
+async fn native_point_decodes_in_binary_and_text_form() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+
+    // Prepared statements return binary values, the simple protocol text.
+    let binary: Point = sqlx::query_scalar("SELECT POINT(8.5417, 47.3769)")
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap();
+    let text: Point = sqlx::raw_sql("SELECT POINT(8.5417, 47.3769)")
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap()
+        .get(0);
+
+    assert_eq!(binary, Point::new(47.3769, 8.5417));
+    assert_eq!(text, Point::new(47.3769, 8.5417));
+}
+
+#[tokio::test]
+async fn legacy_string_coordinates_decode() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+
+    let rows: Vec<Point> = sqlx::query_scalar(
+        "SELECT c FROM (VALUES ('(8.5417,47.3769)'::text), ('47.3769,8.5417')) AS v (c)",
+    )
+    .fetch_all(&app.db_pool)
+    .await
+    .unwrap();
+
+    assert_eq!(rows, vec![Point::new(47.3769, 8.5417); 2]);
+}
+
+#[tokio::test]
+async fn mixed_format_column_can_be_read_during_migration() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    let mut connection = app.db_pool.acquire().await.unwrap();
+    sqlx::raw_sql(
+        "CREATE TEMP TABLE legacy_farms (coordinates TEXT NOT NULL);
+         INSERT INTO legacy_farms VALUES ('(8.5417,47.3769)'), ('46.9480,7.4474');
+         INSERT INTO legacy_farms SELECT POINT(6.1432, 46.2044)::text;",
+    )
+    .execute(&mut *connection)
+    .await
+    .unwrap();
+
+    let rows: Vec<Point> = sqlx::query_scalar("SELECT coordinates FROM legacy_farms")
+        .fetch_all(&mut *connection)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        rows,
+        vec![
+            Point::new(47.3769, 8.5417),
+            Point::new(46.9480, 7.4474),
+            Point::new(46.2044, 6.1432),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn unparseable_string_coordinates_fail_to_decode() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+
+    let result = sqlx::query_scalar::<_, Point>("SELECT 'somewhere'::text")
+        .fetch_one(&app.db_pool)
+        .await;
+
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn reindex_reports_and_flags_farms_outside_the_tightened_boundary() {
+    let app = spawn_app_with(IdempotencyEngine::None, |c| {
+        c.farms.swiss_boundary = SwissBoundary::Polygon
+    })
+    .await;
+    insert_test_farm(&app.db_pool, "Inside").await;
+    let outside = insert_test_farm(&app.db_pool, "On the lake").await;
+    // Lake Constance: inside the bounding box, outside the border polygon.
+    sqlx::query!(
+        "UPDATE farms SET coordinates = POINT(9.48, 47.65) WHERE id = $1",
+        outside,
+    )
+    .execute(&app.db_pool)
+    .await
+    .unwrap();
+    app.log_in_admin_user().await;
+    let reindex = async |query: &str| {
+        let response = app
+            .api_client
+            .post(format!("{}/farms/reindex-coordinates{query}", app.address))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(StatusCode::OK.as_u16(), response.status().as_u16());
+        response.json::<serde_json::Value>().await.unwrap()
+    };
+    let flagged = async || {
+        sqlx::query_scalar!("SELECT id FROM farms WHERE coordinates_flagged_at IS NOT NULL")
+            .fetch_all(&app.db_pool)
+            .await
+            .unwrap()
+    };
+
+    let dry_run = reindex("?dry_run=true").await;
+    assert!(flagged().await.is_empty());
+    let report = reindex("").await;
+
+    for body in [&dry_run, &report] {
+        assert_eq!(2, body["scanned"]);
+        let invalid = body["invalid"].as_array().unwrap();
+        assert_eq!(1, invalid.len());
+        assert_eq!(outside.to_string(), invalid[0]["id"]);
+        assert_eq!(
+            "Coordinates not within Switzerland boundaries.",
+            invalid[0]["reason"]
+        );
+    }
+    assert_eq!(true, dry_run["dry_run"]);
+    assert_eq!(vec![outside], flagged().await);
+}
+
+#[tokio::test]
+async fn reindex_is_admin_only() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    app.log_in_active_user().await;
+
+    let response = app
+        .api_client
+        .post(format!("{}/farms/reindex-coordinates", app.address))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(StatusCode::FORBIDDEN.as_u16(), response.status().as_u16());
+}
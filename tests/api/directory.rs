@@ -1,5 +1,6 @@
 use crate::helpers::{
     insert_test_farm, link_farm_category, link_farm_product, seed_test_taxonomy, spawn_app,
+    spawn_app_with,
 };
 use actix_web::http::StatusCode;
 use farms::configuration::IdempotencyEngine;
@@ -9,6 +10,22 @@ async fn farms_array(response: reqwest::Response) -> Vec<serde_json::Value> {
     body["farms"].as_array().unwrap().clone()
 }
 
+/// The names of the farms `path` lists for `query`, sorted by name.
+async fn farm_names(app: &crate::helpers::TestApp, path: &str, query: &str) -> Vec<String> {
+    let response = app
+        .api_client
+        .get(format!("{}{path}?sort=name&{query}", app.address))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::OK.as_u16(), response.status().as_u16());
+    farms_array(response)
+        .await
+        .iter()
+        .map(|f| f["name"].as_str().unwrap().to_string())
+        .collect()
+}
+
 /// Move a seeded farm to known coordinates (the fixtures insert at 8.5, 47.4).
 async fn set_coords(app: &crate::helpers::TestApp, farm: uuid::Uuid, lng: f64, lat: f64) {
     sqlx::query!(
@@ -30,6 +47,18 @@ async fn set_canton(app: &crate::helpers::TestApp, farm: uuid::Uuid, canton: &st
         .unwrap();
 }
 
+/// Set a farm's postal code (fixtures have none).
+async fn set_postal_code(app: &crate::helpers::TestApp, farm: uuid::Uuid, postal_code: i16) {
+    sqlx::query!(
+        "UPDATE farms SET postal_code = $1 WHERE id = $2",
+        postal_code,
+        farm
+    )
+    .execute(&app.db_pool)
+    .await
+    .unwrap();
+}
+
 #[tokio::test]
 async fn filters_by_canton() {
     let app = spawn_app(IdempotencyEngine::None).await;
@@ -49,6 +78,50 @@ async fn filters_by_canton() {
     assert_ne!(zh.to_string(), farms[0]["id"].as_str().unwrap());
 }
 
+#[tokio::test]
+async fn filters_by_several_cantons() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    let zh = insert_test_farm(&app.db_pool, "Zurich Farm").await;
+    let zg = insert_test_farm(&app.db_pool, "Zug Farm").await;
+    set_canton(&app, zg, "ZG").await;
+    let sz = insert_test_farm(&app.db_pool, "Schwyz Farm").await;
+    set_canton(&app, sz, "SZ").await;
+
+    let response = app
+        .api_client
+        .get(format!("{}/farms?canton=ZH,zg", app.address))
+        .send()
+        .await
+        .unwrap();
+    let mut ids: Vec<String> = farms_array(response)
+        .await
+        .iter()
+        .map(|f| f["id"].as_str().unwrap().to_string())
+        .collect();
+    ids.sort();
+    let mut expected = vec![zh.to_string(), zg.to_string()];
+    expected.sort();
+    assert_eq!(expected, ids);
+}
+
+#[tokio::test]
+async fn canton_filter_rejects_an_unknown_entry() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    insert_test_farm(&app.db_pool, "Zurich Farm").await;
+
+    for path in ["farms", "farms/count"] {
+        let response = app
+            .api_client
+            .get(format!("{}/{}?canton=ZH,XX", app.address, path))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(400, response.status().as_u16(), "{path}");
+        let body = response.text().await.unwrap();
+        assert!(body.contains("XX"), "{path}: {body}");
+    }
+}
+
 #[tokio::test]
 async fn free_text_q_matches_name_and_product() {
     let app = spawn_app(IdempotencyEngine::None).await;
@@ -200,6 +273,23 @@ async fn offset_pagination_pages_and_stops() {
     assert!(page2["next_cursor"].is_null(), "last page has no cursor");
 }
 
+#[tokio::test]
+async fn a_huge_limit_is_capped() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    for i in 0..101 {
+        insert_test_farm(&app.db_pool, &format!("Farm {i}")).await;
+    }
+
+    let response = app
+        .api_client
+        .get(format!("{}/farms?limit=1000000", app.address))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(100, farms_array(response).await.len());
+}
+
 #[tokio::test]
 async fn category_and_group_only_still_work_after_geo_changes() {
     // Regression guard: the sub-categories behavior survives the rewrite.
@@ -220,3 +310,860 @@ async fn category_and_group_only_still_work_after_geo_changes() {
     assert_eq!(1, farms.len());
     assert_eq!(group_only.to_string(), farms[0]["id"].as_str().unwrap());
 }
+
+#[tokio::test]
+async fn filters_by_postal_code() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    let zurich = insert_test_farm(&app.db_pool, "Zurich Farm").await;
+    set_postal_code(&app, zurich, 8001).await;
+    let bern = insert_test_farm(&app.db_pool, "Bern Farm").await;
+    set_postal_code(&app, bern, 3011).await;
+
+    let farms = farms_array(
+        app.api_client
+            .get(format!("{}/farms?postal_code=3011", app.address))
+            .send()
+            .await
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(1, farms.len());
+    assert_eq!(bern.to_string(), farms[0]["id"].as_str().unwrap());
+    assert_eq!(3011, farms[0]["postal_code"].as_i64().unwrap());
+}
+
+#[tokio::test]
+async fn invalid_postal_code_filter_is_rejected() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+
+    let response = app
+        .api_client
+        .get(format!("{}/farms?postal_code=80", app.address))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::BAD_REQUEST.as_u16(), response.status().as_u16());
+}
+
+#[tokio::test]
+async fn created_farm_carries_the_postal_code_from_its_address() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    app.log_in_active_user().await;
+
+    let response = app
+        .post_farm(&serde_json::json!({
+            "name": "Hof Sonnenberg",
+            "address": "Bahnhofstrasse 1, 8001 Zürich",
+            "canton": "ZH",
+            "coordinates": "47.37,8.54",
+            "products": ["strawberries"],
+            "idempotency_key": uuid::Uuid::new_v4().to_string(),
+        }))
+        .await;
+    assert_eq!(StatusCode::CREATED.as_u16(), response.status().as_u16());
+
+    let farms = farms_array(app.get_farms().await).await;
+    assert_eq!(1, farms.len());
+    assert_eq!(8001, farms[0]["postal_code"].as_i64().unwrap());
+}
+
+#[tokio::test]
+async fn coordinates_are_exact_unless_obfuscation_is_requested() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    let farm = insert_test_farm(&app.db_pool, "Hidden Farm").await;
+    set_coords(&app, farm, 8.541_694, 47.376_912).await;
+
+    let exact = farms_array(app.get_farms().await).await;
+    let obfuscated = farms_array(
+        app.api_client
+            .get(format!("{}/farms?obfuscate=true", app.address))
+            .send()
+            .await
+            .unwrap(),
+    )
+    .await;
+
+    assert_eq!(exact[0]["coordinates"], "47.376912,8.541694");
+    assert_eq!(obfuscated[0]["coordinates"], "47.38,8.54");
+}
+
+#[tokio::test]
+async fn obfuscation_rounds_the_reported_distance() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    let farm = insert_test_farm(&app.db_pool, "Hidden Farm").await;
+    set_coords(&app, farm, 8.541_694, 47.376_912).await;
+
+    let farms = farms_array(
+        app.api_client
+            .get(format!(
+                "{}/farms?lat=46.95&lng=7.45&obfuscate=true",
+                app.address
+            ))
+            .send()
+            .await
+            .unwrap(),
+    )
+    .await;
+
+    let distance = farms[0]["distance_km"].as_f64().unwrap();
+    assert_eq!(distance, distance.round());
+}
+
+#[tokio::test]
+async fn farm_detail_honours_obfuscation() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    let farm = insert_test_farm(&app.db_pool, "Hidden Farm").await;
+    set_coords(&app, farm, 8.541_694, 47.376_912).await;
+
+    let exact: serde_json::Value = app.get_farm(farm).await.json().await.unwrap();
+    let obfuscated: serde_json::Value = app
+        .api_client
+        .get(format!("{}/farms/{}?obfuscate=true", app.address, farm))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    assert_eq!(exact["coordinates"], "47.376912,8.541694");
+    assert_eq!(obfuscated["coordinates"], "47.38,8.54");
+}
+
+#[tokio::test]
+async fn coord_precision_rounds_the_output_but_not_the_stored_location() {
+    // Arrange
+    let app = spawn_app(IdempotencyEngine::None).await;
+    let farm = insert_test_farm(&app.db_pool, "Rounded Farm").await;
+    set_coords(&app, farm, 8.541_694, 47.376_912).await;
+
+    // Act
+    let list = farms_array(
+        app.api_client
+            .get(format!("{}/farms?coord_precision=2", app.address))
+            .send()
+            .await
+            .unwrap(),
+    )
+    .await;
+    let detail: serde_json::Value = app
+        .api_client
+        .get(format!("{}/farms/{}?coord_precision=3", app.address, farm))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    // Assert
+    assert_eq!(list[0]["coordinates"], "47.38,8.54");
+    assert_eq!(detail["coordinates"], "47.377,8.542");
+    let exact: serde_json::Value = app.get_farm(farm).await.json().await.unwrap();
+    assert_eq!(exact["coordinates"], "47.376912,8.541694");
+}
+
+#[tokio::test]
+async fn farms_carry_their_coordinates_as_a_string_and_as_numbers() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    let farm = insert_test_farm(&app.db_pool, "Farm").await;
+    set_coords(&app, farm, 8.541_694, 47.376_912).await;
+
+    let detail: serde_json::Value = app.get_farm(farm).await.json().await.unwrap();
+    let list = farms_array(
+        app.api_client
+            .get(format!("{}/farms?coord_precision=2", app.address))
+            .send()
+            .await
+            .unwrap(),
+    )
+    .await;
+
+    assert_eq!(detail["coordinates"], "47.376912,8.541694");
+    assert_eq!(
+        detail["coordinates_components"],
+        serde_json::json!({"lat": 47.376912, "lon": 8.541694})
+    );
+    // Rounding and obfuscation apply to both forms alike.
+    assert_eq!(list[0]["coordinates"], "47.38,8.54");
+    assert_eq!(
+        list[0]["coordinates_components"],
+        serde_json::json!({"lat": 47.38, "lon": 8.54})
+    );
+}
+
+#[tokio::test]
+async fn coord_precision_above_nine_is_rejected() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    let farm = insert_test_farm(&app.db_pool, "Rounded Farm").await;
+
+    for url in [
+        format!("{}/farms?coord_precision=10", app.address),
+        format!("{}/farms/{}?coord_precision=10", app.address, farm),
+        format!("{}/farms?coord_precision=-1", app.address),
+    ] {
+        let response = app.api_client.get(&url).send().await.unwrap();
+
+        assert_eq!(
+            StatusCode::BAD_REQUEST.as_u16(),
+            response.status().as_u16(),
+            "{url}"
+        );
+    }
+
+    let nine = app
+        .api_client
+        .get(format!("{}/farms?coord_precision=9", app.address))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::OK.as_u16(), nine.status().as_u16());
+}
+
+#[tokio::test]
+async fn neighbors_are_the_closest_other_farms_in_order() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+
+    // A cluster around Zurich, spaced ~1 km apart, plus Geneva far away.
+    let origin = insert_test_farm(&app.db_pool, "Origin Farm").await;
+    set_coords(&app, origin, 8.540, 47.370).await;
+    let near = insert_test_farm(&app.db_pool, "Near Farm").await;
+    set_coords(&app, near, 8.550, 47.370).await;
+    let nearer = insert_test_farm(&app.db_pool, "Nearer Farm").await;
+    set_coords(&app, nearer, 8.541, 47.371).await;
+    let farther = insert_test_farm(&app.db_pool, "Farther Farm").await;
+    set_coords(&app, farther, 8.560, 47.380).await;
+    let geneva = insert_test_farm(&app.db_pool, "Geneva Farm").await;
+    set_coords(&app, geneva, 6.143, 46.204).await;
+
+    let response = app
+        .api_client
+        .get(format!("{}/farms/{origin}/neighbors?limit=3", app.address))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::OK.as_u16(), response.status().as_u16());
+
+    let farms = farms_array(response).await;
+    let ids: Vec<&str> = farms.iter().map(|f| f["id"].as_str().unwrap()).collect();
+    assert_eq!(
+        vec![nearer.to_string(), near.to_string(), farther.to_string()],
+        ids
+    );
+    let distances: Vec<f64> = farms
+        .iter()
+        .map(|f| f["distance_km"].as_f64().unwrap())
+        .collect();
+    assert!(distances.is_sorted(), "not nearest first: {distances:?}");
+}
+
+#[tokio::test]
+async fn neighbors_carry_their_distance_next_to_the_farm_fields() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    let origin = insert_test_farm(&app.db_pool, "Origin Farm").await;
+    set_coords(&app, origin, 8.540, 47.370).await;
+    let far = insert_test_farm(&app.db_pool, "Far Farm").await;
+    set_coords(&app, far, 8.640, 47.470).await;
+    let close = insert_test_farm(&app.db_pool, "Close Farm").await;
+    set_coords(&app, close, 8.545, 47.372).await;
+
+    let response = app
+        .api_client
+        .get(format!("{}/farms/{origin}/neighbors", app.address))
+        .send()
+        .await
+        .unwrap();
+
+    let farms = farms_array(response).await;
+    assert_eq!(2, farms.len());
+    assert_eq!("Close Farm", farms[0]["name"]);
+    assert_eq!("Far Farm", farms[1]["name"]);
+    for farm in &farms {
+        assert!(farm.get("farm").is_none(), "{farm}");
+        assert!(farm["coordinates"].is_string(), "{farm}");
+    }
+    let (close_km, far_km) = (
+        farms[0]["distance_km"].as_f64().unwrap(),
+        farms[1]["distance_km"].as_f64().unwrap(),
+    );
+    assert!(0.0 < close_km && close_km < 1.0, "{close_km}");
+    assert!(close_km < far_km, "{close_km} >= {far_km}");
+}
+
+#[tokio::test]
+async fn neighbors_default_to_five_and_exclude_the_farm_itself() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+
+    // All fixtures share one location, so only the exclusion tells them apart.
+    let origin = insert_test_farm(&app.db_pool, "Origin Farm").await;
+    for i in 0..6 {
+        insert_test_farm(&app.db_pool, &format!("Farm {i}")).await;
+    }
+
+    let response = app
+        .api_client
+        .get(format!("{}/farms/{origin}/neighbors", app.address))
+        .send()
+        .await
+        .unwrap();
+
+    let farms = farms_array(response).await;
+    assert_eq!(5, farms.len());
+    assert!(farms.iter().all(|f| f["id"] != origin.to_string()));
+}
+
+#[tokio::test]
+async fn neighbors_limit_is_capped() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+
+    let origin = insert_test_farm(&app.db_pool, "Origin Farm").await;
+    for i in 0..21 {
+        insert_test_farm(&app.db_pool, &format!("Farm {i}")).await;
+    }
+
+    let response = app
+        .api_client
+        .get(format!("{}/farms/{origin}/neighbors?limit=50", app.address))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(20, farms_array(response).await.len());
+}
+
+#[tokio::test]
+async fn neighbors_of_an_unknown_farm_is_a_404() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+
+    let response = app
+        .api_client
+        .get(format!(
+            "{}/farms/{}/neighbors",
+            app.address,
+            uuid::Uuid::new_v4()
+        ))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(StatusCode::NOT_FOUND.as_u16(), response.status().as_u16());
+}
+
+#[tokio::test]
+async fn envelope_wraps_the_page_with_page_metadata() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    for i in 0..5 {
+        insert_test_farm(&app.db_pool, &format!("Farm {i}")).await;
+    }
+
+    let response = app
+        .api_client
+        .get(format!(
+            "{}/farms?envelope=true&limit=2&offset=2",
+            app.address
+        ))
+        .send()
+        .await
+        .unwrap();
+    let body: serde_json::Value = response.json().await.unwrap();
+
+    assert_eq!(2, body["data"].as_array().unwrap().len());
+    assert_eq!(
+        serde_json::json!({"page": 2, "per_page": 2, "total": 5}),
+        body["meta"]
+    );
+    assert!(body.get("farms").is_none());
+}
+
+#[tokio::test]
+async fn envelope_total_counts_matches_past_the_last_page() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    for i in 0..3 {
+        insert_test_farm(&app.db_pool, &format!("Farm {i}")).await;
+    }
+
+    let response = app
+        .api_client
+        .get(format!(
+            "{}/farms?envelope=true&limit=2&offset=4",
+            app.address
+        ))
+        .send()
+        .await
+        .unwrap();
+    let body: serde_json::Value = response.json().await.unwrap();
+
+    assert!(body["data"].as_array().unwrap().is_empty());
+    assert_eq!(
+        serde_json::json!({"page": 3, "per_page": 2, "total": 3}),
+        body["meta"]
+    );
+}
+
+#[tokio::test]
+async fn envelope_total_counts_only_the_filtered_farms() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    for i in 0..3 {
+        insert_test_farm(&app.db_pool, &format!("Apfelhof {i}")).await;
+    }
+    insert_test_farm(&app.db_pool, "Milchhof").await;
+
+    let response = app
+        .api_client
+        .get(format!(
+            "{}/farms?envelope=true&limit=1&q=apfel",
+            app.address
+        ))
+        .send()
+        .await
+        .unwrap();
+    let body: serde_json::Value = response.json().await.unwrap();
+
+    assert_eq!(1, body["data"].as_array().unwrap().len());
+    assert_eq!(
+        serde_json::json!({"page": 1, "per_page": 1, "total": 3}),
+        body["meta"]
+    );
+}
+
+#[tokio::test]
+async fn list_shape_is_unchanged_without_envelope() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    insert_test_farm(&app.db_pool, "Farm").await;
+
+    let response = app
+        .api_client
+        .get(format!("{}/farms", app.address))
+        .send()
+        .await
+        .unwrap();
+    let body: serde_json::Value = response.json().await.unwrap();
+
+    assert_eq!(1, body["farms"].as_array().unwrap().len());
+    assert!(body.get("next_cursor").is_some());
+    assert!(body.get("data").is_none());
+    assert!(body.get("meta").is_none());
+}
+
+/// Set a farm's certification codes (fixtures have none).
+async fn set_certifications(app: &crate::helpers::TestApp, farm: uuid::Uuid, codes: &[&str]) {
+    let codes: Vec<String> = codes.iter().map(|c| c.to_string()).collect();
+    sqlx::query!(
+        "UPDATE farms SET certifications = $1 WHERE id = $2",
+        &codes,
+        farm
+    )
+    .execute(&app.db_pool)
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn certification_filter_matches_any_listed_code() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    let bio = insert_test_farm(&app.db_pool, "Bio Farm").await;
+    set_certifications(&app, bio, &["bio_suisse"]).await;
+    let demeter = insert_test_farm(&app.db_pool, "Demeter Farm").await;
+    set_certifications(&app, demeter, &["demeter", "bio_suisse"]).await;
+    let ip = insert_test_farm(&app.db_pool, "IP Farm").await;
+    set_certifications(&app, ip, &["ip_suisse"]).await;
+    insert_test_farm(&app.db_pool, "Uncertified Farm").await;
+
+    let ids_for = async |query: &str| {
+        let response = app
+            .api_client
+            .get(format!("{}/farms?{query}", app.address))
+            .send()
+            .await
+            .unwrap();
+        let mut ids: Vec<String> = farms_array(response)
+            .await
+            .iter()
+            .map(|f| f["id"].as_str().unwrap().to_string())
+            .collect();
+        ids.sort();
+        ids
+    };
+    let sorted = |mut ids: Vec<String>| {
+        ids.sort();
+        ids
+    };
+
+    assert_eq!(
+        sorted(vec![bio.to_string(), demeter.to_string()]),
+        ids_for("certification=bio_suisse").await
+    );
+    assert_eq!(
+        sorted(vec![demeter.to_string(), ip.to_string()]),
+        ids_for("certification=demeter,IP_SUISSE").await
+    );
+    assert_eq!(4, ids_for("").await.len());
+}
+
+#[tokio::test]
+async fn unknown_certification_filter_is_rejected() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+
+    let response = app
+        .api_client
+        .get(format!("{}/farms?certification=organic", app.address))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(StatusCode::BAD_REQUEST.as_u16(), response.status().as_u16());
+}
+
+#[tokio::test]
+async fn incomplete_selects_farms_missing_optional_data() {
+    let app = spawn_app_with(IdempotencyEngine::None, |c| {
+        c.farms.default_categories = vec!["vegetables".to_string()]
+    })
+    .await;
+    let t = seed_test_taxonomy(&app.db_pool).await;
+    let complete = insert_test_farm(&app.db_pool, "Complete Farm").await;
+    set_postal_code(&app, complete, 8001).await;
+    link_farm_product(&app.db_pool, complete, t.strawberries_id).await;
+    let no_postal_code = insert_test_farm(&app.db_pool, "No Postal Code Farm").await;
+    link_farm_product(&app.db_pool, no_postal_code, t.cherries_id).await;
+    set_canton(&app, no_postal_code, "BE").await;
+    let no_products = insert_test_farm(&app.db_pool, "No Products Farm").await;
+    set_postal_code(&app, no_products, 8001).await;
+    link_farm_category(&app.db_pool, no_products, t.fruits_category_id).await;
+    let defaults_only = insert_test_farm(&app.db_pool, "Defaults Only Farm").await;
+    set_postal_code(&app, defaults_only, 8001).await;
+    link_farm_product(&app.db_pool, defaults_only, t.broccoli_id).await;
+    link_farm_category(&app.db_pool, defaults_only, t.vegetables_category_id).await;
+
+    assert_eq!(
+        vec![
+            "Defaults Only Farm",
+            "No Postal Code Farm",
+            "No Products Farm"
+        ],
+        farm_names(&app, "/farms", "incomplete=true").await
+    );
+    assert_eq!(
+        vec!["Complete Farm"],
+        farm_names(&app, "/farms", "incomplete=false").await
+    );
+    assert_eq!(
+        vec!["No Postal Code Farm"],
+        farm_names(&app, "/farms", "incomplete=true&canton=BE").await
+    );
+    assert_eq!(4, farm_names(&app, "/farms", "").await.len());
+}
+
+#[tokio::test]
+async fn search_returns_the_farms_matching_every_filter() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    let t = seed_test_taxonomy(&app.db_pool).await;
+    let matching = insert_test_farm(&app.db_pool, "Bern Berry Farm").await;
+    set_canton(&app, matching, "BE").await;
+    link_farm_product(&app.db_pool, matching, t.strawberries_id).await;
+    let other_category = insert_test_farm(&app.db_pool, "Bern Berry Greens").await;
+    set_canton(&app, other_category, "BE").await;
+    link_farm_product(&app.db_pool, other_category, t.broccoli_id).await;
+    let other_canton = insert_test_farm(&app.db_pool, "Zurich Berry Farm").await;
+    link_farm_product(&app.db_pool, other_canton, t.strawberries_id).await;
+    let other_name = insert_test_farm(&app.db_pool, "Bern Orchard").await;
+    set_canton(&app, other_name, "BE").await;
+    link_farm_product(&app.db_pool, other_name, t.cherries_id).await;
+
+    assert_eq!(
+        vec!["Bern Berry Farm"],
+        farm_names(&app, "/farms/search", "canton=BE&category=fruits&q=berry").await
+    );
+    assert_eq!(
+        vec!["Bern Berry Farm", "Bern Berry Greens"],
+        farm_names(&app, "/farms/search", "canton=BE&q=berry").await
+    );
+    assert_eq!(4, farm_names(&app, "/farms/search", "").await.len());
+}
+
+#[tokio::test]
+async fn search_filters_by_creation_date() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    let old = insert_test_farm(&app.db_pool, "Old Farm").await;
+    sqlx::query!(
+        "UPDATE farms SET created_at = '2020-06-01T00:00:00Z' WHERE id = $1",
+        old
+    )
+    .execute(&app.db_pool)
+    .await
+    .unwrap();
+    insert_test_farm(&app.db_pool, "New Farm").await;
+
+    assert_eq!(
+        vec!["New Farm"],
+        farm_names(&app, "/farms/search", "created_after=2021-01-01T00:00:00Z").await
+    );
+    assert_eq!(
+        vec!["Old Farm"],
+        farm_names(&app, "/farms/search", "created_before=2021-01-01T00:00:00Z").await
+    );
+
+    let reversed = app
+        .api_client
+        .get(format!(
+            "{}/farms/search?created_after=2021-01-01T00:00:00Z&created_before=2020-01-01T00:00:00Z",
+            app.address
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::BAD_REQUEST.as_u16(), reversed.status().as_u16());
+}
+
+#[tokio::test]
+async fn count_reports_all_farms_and_respects_filters() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    let taxonomy = seed_test_taxonomy(&app.db_pool).await;
+    insert_test_farm(&app.db_pool, "Zurich Farm").await;
+    let bern = insert_test_farm(&app.db_pool, "Bern Farm").await;
+    let thun = insert_test_farm(&app.db_pool, "Thun Farm").await;
+    set_canton(&app, bern, "BE").await;
+    set_canton(&app, thun, "BE").await;
+    link_farm_product(&app.db_pool, thun, taxonomy.broccoli_id).await;
+
+    let count = async |query: &str| -> serde_json::Value {
+        app.api_client
+            .get(format!("{}/farms/count{query}", app.address))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap()
+    };
+
+    assert_eq!(serde_json::json!({"count": 3}), count("").await);
+    assert_eq!(serde_json::json!({"count": 2}), count("?canton=be").await);
+    assert_eq!(
+        serde_json::json!({"count": 1}),
+        count("?canton=BE&category=vegetables").await
+    );
+    assert_eq!(
+        serde_json::json!({"count": 0}),
+        count("?canton=ZH&category=vegetables").await
+    );
+}
+
+#[tokio::test]
+async fn count_rejects_an_unknown_category() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+
+    let response = app
+        .api_client
+        .get(format!("{}/farms/count?category=nope", app.address))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(StatusCode::BAD_REQUEST.as_u16(), response.status().as_u16());
+}
+
+/// The keys of a JSON object, sorted.
+fn keys(object: &serde_json::Value) -> Vec<&str> {
+    let mut keys: Vec<&str> = object
+        .as_object()
+        .unwrap()
+        .keys()
+        .map(String::as_str)
+        .collect();
+    keys.sort_unstable();
+    keys
+}
+
+#[tokio::test]
+async fn fields_limits_the_listed_farms_to_the_selected_keys() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    insert_test_farm(&app.db_pool, "Farm A").await;
+    insert_test_farm(&app.db_pool, "Farm B").await;
+
+    let response = app
+        .api_client
+        .get(format!("{}/farms?fields=id,name", app.address))
+        .send()
+        .await
+        .unwrap();
+    let farms = farms_array(response).await;
+    assert_eq!(2, farms.len());
+    assert!(farms.iter().all(|farm| keys(farm) == ["id", "name"]));
+
+    // `id` is always included.
+    let envelope: serde_json::Value = app
+        .api_client
+        .get(format!("{}/farms?fields=canton&envelope=true", app.address))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let data = envelope["data"].as_array().unwrap();
+    assert_eq!(2, data.len());
+    assert!(data.iter().all(|farm| keys(farm) == ["canton", "id"]));
+}
+
+#[tokio::test]
+async fn fields_without_lists_skips_loading_products_and_categories() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    insert_test_farm(&app.db_pool, "Farm A").await;
+    // Only the product and category loaders read these columns.
+    sqlx::query!("ALTER TABLE farm_products DROP COLUMN last_confirmed_at")
+        .execute(&app.db_pool)
+        .await
+        .unwrap();
+    sqlx::query!("ALTER TABLE product_categories DROP COLUMN slug CASCADE")
+        .execute(&app.db_pool)
+        .await
+        .unwrap();
+
+    let narrow = app
+        .api_client
+        .get(format!("{}/farms?fields=id,name", app.address))
+        .send()
+        .await
+        .unwrap();
+    let full = app
+        .api_client
+        .get(format!("{}/farms", app.address))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(StatusCode::OK.as_u16(), narrow.status().as_u16());
+    let farms = farms_array(narrow).await;
+    assert_eq!(1, farms.len());
+    assert_eq!(["id", "name"], keys(&farms[0]).as_slice());
+    assert_eq!(
+        StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+        full.status().as_u16()
+    );
+}
+
+#[tokio::test]
+async fn fields_limits_a_single_farm_to_the_selected_keys() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    let farm = insert_test_farm(&app.db_pool, "Farm A").await;
+
+    let body: serde_json::Value = app
+        .api_client
+        .get(format!(
+            "{}/farms/{farm}?fields=name,coordinates,name",
+            app.address
+        ))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    assert_eq!(["coordinates", "id", "name"], keys(&body).as_slice());
+    assert_eq!(farm.to_string(), body["id"]);
+}
+
+#[tokio::test]
+async fn unknown_fields_are_rejected() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    let farm = insert_test_farm(&app.db_pool, "Farm A").await;
+
+    for path in [
+        "/farms?fields=id,secret",
+        &format!("/farms/{farm}?fields=secret"),
+    ] {
+        let response = app
+            .api_client
+            .get(format!("{}{path}", app.address))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(
+            StatusCode::BAD_REQUEST.as_u16(),
+            response.status().as_u16(),
+            "{path}"
+        );
+    }
+}
+
+async fn spawn_app_with_list_cache() -> crate::helpers::TestApp {
+    spawn_app_with(IdempotencyEngine::None, |c| {
+        c.farms.list_cache.enabled = true;
+        // Keep this app's entries (and generation counter) to itself.
+        c.farms.list_cache.key_prefix = format!("farms-list-test:{}", uuid::Uuid::new_v4());
+    })
+    .await
+}
+
+async fn get_listing(app: &crate::helpers::TestApp, query: &str) -> (String, serde_json::Value) {
+    let response = app
+        .api_client
+        .get(format!("{}/farms{query}", app.address))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::OK.as_u16(), response.status().as_u16());
+    let cache = response.headers()["x-cache"].to_str().unwrap().to_string();
+    (cache, response.json().await.unwrap())
+}
+
+#[tokio::test]
+async fn an_identical_listing_is_served_from_the_cache() {
+    let app = spawn_app_with_list_cache().await;
+    insert_test_farm(&app.db_pool, "Cached Farm").await;
+
+    let (first_cache, first) = get_listing(&app, "?canton=ZH").await;
+    // Written behind the API's back: only visible once the cache is bypassed.
+    insert_test_farm(&app.db_pool, "Uncached Farm").await;
+    let (second_cache, second) = get_listing(&app, "?canton=ZH").await;
+    // The same query once normalized.
+    let (normalized_cache, normalized) = get_listing(&app, "?canton=%20zh,").await;
+    let (other_cache, other) = get_listing(&app, "?canton=ZH&limit=5").await;
+
+    assert_eq!("MISS", first_cache);
+    assert_eq!("HIT", second_cache);
+    assert_eq!(first, second);
+    assert_eq!(1, second["farms"].as_array().unwrap().len());
+    assert_eq!("HIT", normalized_cache);
+    assert_eq!(first, normalized);
+    assert_eq!("MISS", other_cache);
+    assert_eq!(2, other["farms"].as_array().unwrap().len());
+}
+
+#[tokio::test]
+async fn creating_a_farm_invalidates_the_listing_cache() {
+    let app = spawn_app_with_list_cache().await;
+    app.log_in_active_user().await;
+
+    let (_, before) = get_listing(&app, "").await;
+    let (cached, _) = get_listing(&app, "").await;
+    let response = app
+        .post_farm(&serde_json::json!({
+            "name": "Hof Sonnenberg",
+            "address": "Bahnhofstrasse 1, 8001 Zürich",
+            "canton": "ZH",
+            "coordinates": "47.37,8.54",
+            "products": ["strawberries"],
+            "idempotency_key": uuid::Uuid::new_v4().to_string(),
+        }))
+        .await;
+    assert_eq!(StatusCode::CREATED.as_u16(), response.status().as_u16());
+    let (after_cache, after) = get_listing(&app, "").await;
+
+    assert_eq!(0, before["farms"].as_array().unwrap().len());
+    assert_eq!("HIT", cached);
+    assert_eq!("MISS", after_cache);
+    assert_eq!(1, after["farms"].as_array().unwrap().len());
+}
+
+#[tokio::test]
+async fn listings_are_not_cached_by_default() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+
+    let response = app.get_farms().await;
+
+    assert_eq!(StatusCode::OK.as_u16(), response.status().as_u16());
+    assert!(response.headers().get("x-cache").is_none());
+}
@@ -0,0 +1,70 @@
+use crate::helpers::{
+    insert_test_farm, link_farm_category, link_farm_product, seed_test_taxonomy, spawn_app,
+};
+use actix_web::http::StatusCode;
+use farms::configuration::IdempotencyEngine;
+use std::collections::HashSet;
+use uuid::Uuid;
+
+async fn get_export(app: &crate::helpers::TestApp) -> reqwest::Response {
+    app.api_client
+        .get(format!("{}/farms/export.json", app.address))
+        .send()
+        .await
+        .expect("Failed to execute request.")
+}
+
+#[tokio::test]
+async fn export_of_an_empty_directory_is_an_empty_array() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+
+    let response = get_export(&app).await;
+
+    assert_eq!(response.status().as_u16(), StatusCode::OK.as_u16());
+    let farms: Vec<serde_json::Value> = response.json().await.unwrap();
+    assert!(farms.is_empty());
+}
+
+#[tokio::test]
+async fn export_streams_every_farm() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    let mut inserted = HashSet::new();
+    for i in 0..25 {
+        inserted.insert(insert_test_farm(&app.db_pool, &format!("Farm {i}")).await);
+    }
+
+    let response = get_export(&app).await;
+
+    assert_eq!(response.status().as_u16(), StatusCode::OK.as_u16());
+    assert_eq!(
+        response.headers()["content-type"].to_str().unwrap(),
+        "application/json; charset=utf-8"
+    );
+    let farms: Vec<serde_json::Value> = response.json().await.unwrap();
+    let exported: HashSet<Uuid> = farms
+        .iter()
+        .map(|farm| farm["id"].as_str().unwrap().parse().unwrap())
+        .collect();
+    assert_eq!(farms.len(), inserted.len());
+    assert_eq!(exported, inserted);
+}
+
+#[tokio::test]
+async fn export_includes_category_and_product_slugs() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    let taxonomy = seed_test_taxonomy(&app.db_pool).await;
+    let farm = insert_test_farm(&app.db_pool, "Hof Linked").await;
+    link_farm_category(&app.db_pool, farm, taxonomy.vegetables_category_id).await;
+    link_farm_product(&app.db_pool, farm, taxonomy.strawberries_id).await;
+    link_farm_product(&app.db_pool, farm, taxonomy.cherries_id).await;
+
+    let farms: Vec<serde_json::Value> = get_export(&app).await.json().await.unwrap();
+
+    assert_eq!(farms.len(), 1);
+    assert_eq!(farms[0]["name"], "Hof Linked");
+    assert_eq!(farms[0]["categories"], serde_json::json!(["vegetables"]));
+    assert_eq!(
+        farms[0]["products"],
+        serde_json::json!(["cherries", "strawberries"])
+    );
+}
@@ -1,101 +1,25 @@
-use crate::helpers::{TestApp, TestUser, redis_exists_with_retry, seed_test_taxonomy, spawn_app};
+use crate::helpers::{
+    TestApp, TestUser, insert_test_farm, redis_exists_with_retry, seed_test_taxonomy, spawn_app,
+    spawn_app_with,
+};
+use actix_web::ResponseError;
 use actix_web::http::StatusCode;
-use chrono::{DateTime, Utc};
+use chrono::Utc;
 use deadpool_redis::redis::AsyncCommands;
-use fake::{
-    Fake,
-    faker::{address::de_de::StreetName, name::de_de::Name as FakerName},
-};
 use farms::{
-    configuration::IdempotencyEngine,
-    domain::farm::{Address, Canton, Name, Point},
-    idempotency::{ExpiryOutcome, HeaderPair, IdempotencyData, IdempotencyKey},
+    configuration::{CantonMismatchAction, ErrorVerbosity, IdempotencyEngine},
+    domain::farm::{Address, Canton, CantonNames, Categories, Name, Point},
+    i18n::Language,
+    idempotency::{
+        ExpiryOutcome, HeaderPair, IdempotencyData, IdempotencyError, MAX_STORED_KEY_LENGTH,
+    },
+    routes::farms::{CategoriesSchema, FarmError, IN_FLIGHT_RETRY_AFTER_SECONDS},
+    test_util::{TestFarm, generate_coordinates_in, generate_valid_farm},
 };
-use rand::RngExt;
 use std::ops::Sub;
 use std::{collections::HashSet, ops::Add, time::Duration};
 use uuid::Uuid;
 
-/// A farm's own fields for tests — the taxonomy (categories/products) is a
-/// separate dimension now, seeded and linked explicitly per test.
-struct TestFarm {
-    id: Uuid,
-    name: Name,
-    address: Address,
-    canton: Canton,
-    coordinates: Point,
-    created_at: DateTime<Utc>,
-}
-
-/// Generate a valid Swiss coordinate within Switzerland boundaries.
-fn generate_swiss_coordinates() -> String {
-    let lat = 45.8 + (rand::random::<f64>() * (47.9 - 45.8));
-    let lon = 5.9 + (rand::random::<f64>() * (10.6 - 5.9));
-    format!("{:.4},{:.4}", lat, lon)
-}
-
-fn generate_swiss_canton() -> Canton {
-    let cantons = vec![
-        "ZH", "BE", "LU", "UR", "SZ", "OW", "NW", "GL", "ZG", "FR", "SO", "BS", "BL", "SH", "AR",
-        "AI", "SG", "GR", "AG", "TG", "TI", "VD", "VS", "NE", "GE", "JU",
-    ];
-
-    let mut rng = rand::rng();
-    let index = rng.random_range(0..cantons.len());
-    Canton::parse(cantons[index].to_string()).expect("Generated invalid canton")
-}
-
-fn generate_farm() -> TestFarm {
-    let id = Uuid::new_v4();
-    let name = Name::parse(FakerName().fake()).expect("Generated invalid farm name");
-    let address = Address::parse(StreetName().fake()).expect("Generated invalid address");
-    let canton = generate_swiss_canton();
-    let coordinates_str = generate_swiss_coordinates();
-    let coordinates = Point::parse(&coordinates_str).expect("Generated invalid coordinates");
-    let created_at = Utc::now();
-
-    TestFarm {
-        id,
-        name,
-        address,
-        canton,
-        coordinates,
-        created_at,
-    }
-}
-
-/// The create body for a farm. Every farm needs at least one classification;
-/// these tests use the `strawberries` product (seed the taxonomy first).
-fn farm_to_json(farm: &TestFarm, idempotency_key: Uuid) -> serde_json::Value {
-    serde_json::json!({
-        "name": farm.name,
-        "address": farm.address,
-        "canton": farm.canton,
-        "coordinates": farm.coordinates,
-        "products": ["strawberries"],
-        "idempotency_key": idempotency_key.to_string(),
-    })
-}
-
-/// Insert a farm row directly (no taxonomy links) for read-path tests.
-async fn insert_farm_in_db(app: &TestApp, farm: &TestFarm) {
-    sqlx::query!(
-        r#"
-        INSERT INTO farms (id, name, address, canton, coordinates, created_at)
-        VALUES ($1, $2, $3, $4, $5, $6)
-        "#,
-        farm.id,
-        &farm.name as &Name,
-        &farm.address as &Address,
-        &farm.canton as &Canton,
-        &farm.coordinates as &Point,
-        farm.created_at,
-    )
-    .execute(&app.db_pool)
-    .await
-    .expect("Failed to execute query");
-}
-
 async fn break_farms_table(app: &TestApp) {
     sqlx::query!("ALTER TABLE farms DROP COLUMN name;")
         .execute(&app.db_pool)
@@ -116,19 +40,11 @@ async fn farms_array(response: reqwest::Response) -> Vec<serde_json::Value> {
 }
 
 async fn create_single_farm(app: &TestApp) -> TestFarm {
-    let farm = generate_farm();
-    insert_farm_in_db(app, &farm).await;
+    let farm = generate_valid_farm();
+    app.insert_farm(&farm).await;
     farm
 }
 
-async fn create_n_farms(app: &TestApp, n: usize) -> Vec<TestFarm> {
-    let mut farms = Vec::<TestFarm>::with_capacity(n);
-    for _ in 0..n {
-        farms.push(create_single_farm(app).await);
-    }
-    farms
-}
-
 async fn log_in_test_user(app: &TestApp, user: &TestUser) {
     user.store(&app.db_pool).await;
 
@@ -176,7 +92,7 @@ async fn get_farms_returns_200_and_a_single_farm() {
 #[tokio::test]
 async fn get_farms_returns_200_and_list_of_farms() {
     let app = spawn_app(IdempotencyEngine::None).await;
-    let created_farms = create_n_farms(&app, 10).await;
+    let created_farms = app.seed_farms(10).await;
 
     let response = app.get_farms().await;
 
@@ -190,6 +106,28 @@ async fn get_farms_returns_200_and_list_of_farms() {
     }
 }
 
+#[tokio::test]
+async fn get_farms_lists_the_newest_farms_first() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    // Stored oldest first, so listing them in insertion order fails.
+    let created_farms = app.seed_farms(5).await;
+
+    let response = app.get_farms().await;
+
+    assert_eq!(StatusCode::OK.as_u16(), response.status().as_u16());
+    let ids: Vec<String> = farms_array(response)
+        .await
+        .iter()
+        .map(|farm| farm["id"].as_str().unwrap().to_string())
+        .collect();
+    let expected: Vec<String> = created_farms
+        .iter()
+        .rev()
+        .map(|farm| farm.id.to_string())
+        .collect();
+    assert_eq!(expected, ids);
+}
+
 #[tokio::test]
 async fn get_farms_returns_500_when_unexpected_error_occurs() {
     let app = spawn_app(IdempotencyEngine::None).await;
@@ -263,6 +201,122 @@ async fn get_farm_returns_200_and_the_request_farm_when_it_exists() {
     assert_ne!(other_farm.id.to_string(), farm["id"].as_str().unwrap());
 }
 
+/// Fetch a Geneva farm with the given headers/query.
+async fn get_geneva_farm(
+    app: &TestApp,
+    accept_language: Option<&str>,
+    query: &str,
+) -> reqwest::Response {
+    let farm_id = crate::helpers::insert_test_farm(&app.db_pool, "Ferme du Lac").await;
+    sqlx::query!("UPDATE farms SET canton = 'GE' WHERE id = $1", farm_id)
+        .execute(&app.db_pool)
+        .await
+        .expect("Failed to update farm canton.");
+
+    let mut request = app
+        .api_client
+        .get(format!("{}/farms/{}{}", app.address, farm_id, query));
+    if let Some(accept_language) = accept_language {
+        request = request.header("Accept-Language", accept_language);
+    }
+    request.send().await.expect("Failed to execute request.")
+}
+
+/// Fetch a farm's `canton_name` for a Geneva farm with the given headers/query.
+async fn geneva_canton_name(accept_language: Option<&str>, query: &str) -> String {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    let response = get_geneva_farm(&app, accept_language, query).await;
+    assert_eq!(StatusCode::OK.as_u16(), response.status().as_u16());
+
+    let farm: serde_json::Value = response.json().await.expect("Failed to parse JSON.");
+    farm["canton_name"].as_str().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn get_farm_localizes_canton_name_from_accept_language() {
+    let name = geneva_canton_name(Some("fr-CH, fr;q=0.9, en;q=0.5"), "").await;
+
+    assert_eq!("Genève", name);
+}
+
+#[tokio::test]
+async fn get_farm_falls_back_to_german_for_unsupported_languages() {
+    let name = geneva_canton_name(Some("es-ES, ja;q=0.8"), "").await;
+
+    assert_eq!("Genf", name);
+}
+
+#[tokio::test]
+async fn get_farm_lang_query_parameter_overrides_accept_language() {
+    let name = geneva_canton_name(Some("fr-CH, fr;q=0.9"), "?lang=it").await;
+
+    assert_eq!("Ginevra", name);
+}
+
+#[tokio::test]
+async fn get_farm_rejects_an_unsupported_lang_query_parameter() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+
+    let response = get_geneva_farm(&app, None, "?lang=es").await;
+
+    assert_eq!(StatusCode::BAD_REQUEST.as_u16(), response.status().as_u16());
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!("VALIDATION_ERROR", body["error_code"], "{body}");
+    assert_eq!("lang", body["field"], "{body}");
+}
+
+#[tokio::test]
+async fn list_farms_rejects_an_unsupported_lang_with_a_farm_error() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+
+    let response = app
+        .api_client
+        .get(format!("{}/farms?lang=es", app.address))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(StatusCode::BAD_REQUEST.as_u16(), response.status().as_u16());
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!("VALIDATION_ERROR", body["error_code"], "{body}");
+    assert_eq!("lang", body["field"], "{body}");
+}
+
+#[tokio::test]
+async fn get_farm_rejects_a_lang_the_deployment_disabled() {
+    // Arrange
+    let app = spawn_app_with(IdempotencyEngine::None, |c| {
+        c.languages.supported = vec![Language::De, Language::En];
+    })
+    .await;
+
+    // Act
+    let disabled = get_geneva_farm(&app, None, "?lang=fr").await;
+    let negotiated = get_geneva_farm(&app, Some("fr-CH, en;q=0.5"), "").await;
+
+    // Assert
+    assert_eq!(StatusCode::BAD_REQUEST.as_u16(), disabled.status().as_u16());
+    assert!(disabled.text().await.unwrap().contains("de, en"));
+    let farm: serde_json::Value = negotiated.json().await.unwrap();
+    assert_eq!("Geneva", farm["canton_name"]);
+}
+
+#[tokio::test]
+async fn get_farm_applies_the_configured_default_language() {
+    // Arrange
+    let app = spawn_app_with(IdempotencyEngine::None, |c| {
+        c.languages.default = Language::Fr;
+    })
+    .await;
+
+    // Act
+    let response = get_geneva_farm(&app, None, "").await;
+
+    // Assert
+    let farm: serde_json::Value = response.json().await.unwrap();
+    assert_eq!("Genève", farm["canton_name"]);
+}
+
 #[tokio::test]
 async fn get_farm_returns_404_when_farm_does_not_exist() {
     let app = spawn_app(IdempotencyEngine::None).await;
@@ -272,6 +326,126 @@ async fn get_farm_returns_404_when_farm_does_not_exist() {
     assert_eq!(StatusCode::NOT_FOUND.as_u16(), response.status().as_u16());
 }
 
+#[tokio::test]
+async fn get_farm_answers_a_missing_farm_with_a_not_found_code() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+
+    let response = app.get_farm(Uuid::new_v4()).await;
+
+    assert_eq!(StatusCode::NOT_FOUND.as_u16(), response.status().as_u16());
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!("NOT_FOUND", body["error_code"]);
+    assert_eq!("Farm not found.", body["message"]);
+    assert!(Uuid::parse_str(body["request_id"].as_str().unwrap()).is_ok());
+}
+
+#[tokio::test]
+async fn farm_errors_are_json_with_a_utf8_charset() {
+    // Arrange
+    let app = spawn_app(IdempotencyEngine::None).await;
+    app.log_in_active_user().await;
+    let mut body = generate_valid_farm().to_json(Uuid::new_v4());
+    body["coordinates"] = "91.0,8.5".into();
+
+    // Act
+    let unprocessable = app.post_farm(&body).await;
+    let not_found = app.get_farm(Uuid::new_v4()).await;
+
+    // Assert
+    for (response, status) in [
+        (unprocessable, StatusCode::UNPROCESSABLE_ENTITY),
+        (not_found, StatusCode::NOT_FOUND),
+    ] {
+        assert_eq!(status.as_u16(), response.status().as_u16());
+        assert_eq!(
+            "application/json; charset=utf-8",
+            response.headers()["content-type"].to_str().unwrap()
+        );
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert!(body["error_code"].is_string());
+    }
+}
+
+#[tokio::test]
+async fn every_farm_error_has_a_stable_code() {
+    let test_cases = [
+        (
+            FarmError::ValidationError("Missing field 'name'.".to_string()),
+            "VALIDATION_ERROR",
+            StatusCode::BAD_REQUEST,
+        ),
+        (
+            FarmError::InvalidField {
+                field: "canton".into(),
+                message: "Invalid canton code: 'XX'.".to_string(),
+            },
+            "VALIDATION_ERROR",
+            StatusCode::BAD_REQUEST,
+        ),
+        (
+            FarmError::Unprocessable("Invalid canton code.".to_string()),
+            "UNPROCESSABLE",
+            StatusCode::UNPROCESSABLE_ENTITY,
+        ),
+        (
+            FarmError::UnprocessableField {
+                field: "products".into(),
+                message: "Unknown product 'truffles'.".to_string(),
+            },
+            "UNPROCESSABLE",
+            StatusCode::UNPROCESSABLE_ENTITY,
+        ),
+        (
+            FarmError::UnexpectedError(anyhow::anyhow!("boom")),
+            "INTERNAL_ERROR",
+            StatusCode::INTERNAL_SERVER_ERROR,
+        ),
+        (
+            FarmError::ServiceUnavailable(anyhow::anyhow!("pool timed out")),
+            "SERVICE_UNAVAILABLE",
+            StatusCode::SERVICE_UNAVAILABLE,
+        ),
+        (
+            FarmError::DuplicateRequestConflict(IdempotencyError::ExpectedResponseNotFoundError),
+            "IDEMPOTENCY_CONFLICT",
+            StatusCode::CONFLICT,
+        ),
+        (
+            FarmError::IdempotencyKeyReused(IdempotencyError::PayloadMismatch),
+            "IDEMPOTENCY_KEY_REUSED",
+            StatusCode::UNPROCESSABLE_ENTITY,
+        ),
+        (FarmError::NotFound, "NOT_FOUND", StatusCode::NOT_FOUND),
+    ];
+
+    for (error, error_code, status) in test_cases {
+        let response = error.error_response();
+        assert_eq!(status, response.status());
+        assert_eq!(
+            Some("application/json; charset=utf-8"),
+            response
+                .headers()
+                .get("content-type")
+                .and_then(|value| value.to_str().ok())
+        );
+        let body = actix_web::body::to_bytes(response.into_body())
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(error_code, error.error_code());
+        assert_eq!(
+            serde_json::json!({
+                "error_code": error_code,
+                "message": error.to_string(),
+                "field": error.field(),
+                "request_id": null,
+            }),
+            body
+        );
+    }
+}
+
 #[tokio::test]
 async fn get_farm_returns_400_when_farm_id_is_not_an_uuid() {
     let app = spawn_app(IdempotencyEngine::None).await;
@@ -300,13 +474,13 @@ async fn get_farm_returns_500_when_unexpected_error_occurs() {
 async fn create_farm_returns_201_for_valid_body_data() {
     let app = spawn_app(IdempotencyEngine::None).await;
     seed_test_taxonomy(&app.db_pool).await;
-    let farm = generate_farm();
+    let farm = generate_valid_farm();
     let idempotency_key = Uuid::new_v4();
 
     let user = TestUser::generate_user();
     log_in_test_user(&app, &user).await;
 
-    let body = farm_to_json(&farm, idempotency_key);
+    let body = farm.to_json(idempotency_key);
     let response = app.post_farm(&body).await;
 
     assert_eq!(StatusCode::CREATED.as_u16(), response.status().as_u16());
@@ -349,15 +523,37 @@ async fn create_farm_returns_201_for_authenticated_admins() {
     let user = TestUser::generate_admin();
     log_in_test_user(&app, &user).await;
 
-    let farm = generate_farm();
-    let body = farm_to_json(&farm, Uuid::new_v4());
+    let farm = generate_valid_farm();
+    let body = farm.to_json(Uuid::new_v4());
     let response = app.post_farm(&body).await;
 
     assert_eq!(StatusCode::CREATED.as_u16(), response.status().as_u16());
 }
 
 #[tokio::test]
-async fn create_farm_returns_400_for_unknown_product() {
+async fn create_farm_returns_the_id_of_the_inserted_farm() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    seed_test_taxonomy(&app.db_pool).await;
+    let user = TestUser::generate_user();
+    log_in_test_user(&app, &user).await;
+
+    let body = generate_valid_farm().to_json(Uuid::new_v4());
+    let response = app.post_farm(&body).await;
+
+    assert_eq!(StatusCode::CREATED.as_u16(), response.status().as_u16());
+    let location = response.headers()["location"].to_str().unwrap().to_owned();
+    let body = response.json::<serde_json::Value>().await.unwrap();
+    let id: Uuid = body["id"].as_str().unwrap().parse().unwrap();
+    let saved = sqlx::query_scalar!("SELECT id FROM farms")
+        .fetch_one(&app.db_pool)
+        .await
+        .expect("Failed to fetch saved farm.");
+    assert_eq!(saved, id);
+    assert_eq!(format!("/farms/{id}"), location);
+}
+
+#[tokio::test]
+async fn create_farm_returns_422_for_unknown_product() {
     let app = spawn_app(IdempotencyEngine::None).await;
     seed_test_taxonomy(&app.db_pool).await;
     let user = TestUser::generate_user();
@@ -373,11 +569,14 @@ async fn create_farm_returns_400_for_unknown_product() {
     });
     let response = app.post_farm(&body).await;
 
-    assert_eq!(StatusCode::BAD_REQUEST.as_u16(), response.status().as_u16());
+    assert_eq!(
+        StatusCode::UNPROCESSABLE_ENTITY.as_u16(),
+        response.status().as_u16()
+    );
 }
 
 #[tokio::test]
-async fn create_farm_returns_400_when_no_category_or_product_is_given() {
+async fn create_farm_returns_422_when_no_category_or_product_is_given() {
     let app = spawn_app(IdempotencyEngine::None).await;
     seed_test_taxonomy(&app.db_pool).await;
     let user = TestUser::generate_user();
@@ -392,7 +591,44 @@ async fn create_farm_returns_400_when_no_category_or_product_is_given() {
     });
     let response = app.post_farm(&body).await;
 
-    assert_eq!(StatusCode::BAD_REQUEST.as_u16(), response.status().as_u16());
+    assert_eq!(
+        StatusCode::UNPROCESSABLE_ENTITY.as_u16(),
+        response.status().as_u16()
+    );
+}
+
+#[tokio::test]
+async fn create_farm_uses_the_configured_default_categories_when_none_are_given() {
+    let app = spawn_app_with(IdempotencyEngine::None, |c| {
+        c.farms.default_categories = vec!["vegetables".to_string()];
+    })
+    .await;
+    seed_test_taxonomy(&app.db_pool).await;
+    let user = TestUser::generate_user();
+    log_in_test_user(&app, &user).await;
+
+    let body = serde_json::json!({
+        "name": "Unclassified Farm",
+        "address": "Road 1, 8000 Zürich",
+        "canton": "ZH",
+        "coordinates": "47.3769,8.5417",
+        "idempotency_key": Uuid::new_v4().to_string(),
+    });
+    let response = app.post_farm(&body).await;
+
+    assert_eq!(StatusCode::CREATED.as_u16(), response.status().as_u16());
+    let linked = sqlx::query!(
+        r#"
+        SELECT c.slug AS "slug!"
+        FROM farm_categories fc
+        JOIN product_categories c ON c.id = fc.category_id
+        "#
+    )
+    .fetch_all(&app.db_pool)
+    .await
+    .expect("Failed to fetch farm categories.");
+    let slugs: Vec<String> = linked.into_iter().map(|r| r.slug).collect();
+    assert_eq!(vec!["vegetables".to_string()], slugs);
 }
 
 #[tokio::test]
@@ -432,7 +668,7 @@ async fn create_farm_accepts_group_only_classification() {
 async fn create_farm_returns_a_500_when_unexpected_error_occurs() {
     let app = spawn_app(IdempotencyEngine::None).await;
     seed_test_taxonomy(&app.db_pool).await;
-    let farm = generate_farm();
+    let farm = generate_valid_farm();
     let idempotency_key = Uuid::new_v4();
 
     let user = TestUser::generate_user();
@@ -440,7 +676,7 @@ async fn create_farm_returns_a_500_when_unexpected_error_occurs() {
 
     break_farms_table(&app).await;
 
-    let body = farm_to_json(&farm, idempotency_key);
+    let body = farm.to_json(idempotency_key);
     let response = app.post_farm(&body).await;
 
     assert_eq!(
@@ -450,67 +686,210 @@ async fn create_farm_returns_a_500_when_unexpected_error_occurs() {
 }
 
 #[tokio::test]
-async fn create_farm_returns_401_for_unauthenticated_users() {
-    let app = spawn_app(IdempotencyEngine::None).await;
-    let farm = generate_farm();
-    let body = farm_to_json(&farm, Uuid::new_v4());
+async fn internal_errors_only_name_the_request_id_when_hidden() {
+    let app = spawn_app_with(IdempotencyEngine::None, |c| {
+        c.application.hide_internal_errors = true;
+    })
+    .await;
+    seed_test_taxonomy(&app.db_pool).await;
+    let user = TestUser::generate_user();
+    log_in_test_user(&app, &user).await;
+    break_farms_table(&app).await;
 
-    let response = app.post_farm(&body).await;
+    let response = app
+        .post_farm(&generate_valid_farm().to_json(Uuid::new_v4()))
+        .await;
 
     assert_eq!(
-        StatusCode::UNAUTHORIZED.as_u16(),
+        StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
         response.status().as_u16()
     );
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!("INTERNAL_ERROR", body["error_code"], "{body}");
+    assert_eq!("Internal error.", body["message"], "{body}");
+    assert!(
+        Uuid::parse_str(body["request_id"].as_str().unwrap()).is_ok(),
+        "{body}"
+    );
 }
 
 #[tokio::test]
-async fn create_farm_returns_a_400_for_invalid_body_data() {
-    let app = spawn_app(IdempotencyEngine::None).await;
-    let test_cases = vec![
-        (
-            serde_json::json!({
-                "address": "Bahnhofstrasse, 5401 Baden",
-                "canton": "ZH",
-                "coordinates": "47.3769,8.5417",
-                "products": ["strawberries"],
-                "idempotency_key": Uuid::new_v4(),
-            }),
-            "missing field 'name'",
-        ),
-        (
-            serde_json::json!({
-                "name": "Farmy",
-                "canton": "ZH",
-                "coordinates": "47.3769,8.5417",
-                "products": ["strawberries"],
-                "idempotency_key": Uuid::new_v4(),
-            }),
-            "missing field 'address'",
-        ),
-        (
-            serde_json::json!({
-                "name": "Farmy",
-                "address": "Bahnhofstrasse, 5401 Baden",
-                "coordinates": "47.3769,8.5417",
-                "products": ["strawberries"],
-                "idempotency_key": Uuid::new_v4(),
-            }),
-            "missing field 'canton'",
-        ),
-        (
-            serde_json::json!({
-                "name": "Farmy",
-                "address": "Bahnhofstrasse, 5401 Baden",
-                "canton": "ZH",
-                "products": ["strawberries"],
-                "idempotency_key": Uuid::new_v4(),
-            }),
-            "missing field 'coordinates'",
-        ),
-        (
-            serde_json::json!({
-                "name": "Farmy",
-                "address": "Bahnhofstrasse, 5401 Baden",
+async fn hidden_internal_errors_name_the_request_id_at_minimal_verbosity() {
+    let app = spawn_app_with(IdempotencyEngine::None, |c| {
+        c.application.hide_internal_errors = true;
+        c.application.error_verbosity = ErrorVerbosity::Minimal;
+    })
+    .await;
+    seed_test_taxonomy(&app.db_pool).await;
+    let user = TestUser::generate_user();
+    log_in_test_user(&app, &user).await;
+    break_farms_table(&app).await;
+
+    let response = app
+        .post_farm(&generate_valid_farm().to_json(Uuid::new_v4()))
+        .await;
+
+    assert_eq!(
+        StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+        response.status().as_u16()
+    );
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!("INTERNAL_ERROR", body["error_code"], "{body}");
+    assert!(
+        Uuid::parse_str(body["request_id"].as_str().unwrap()).is_ok(),
+        "{body}"
+    );
+}
+
+/// The body of `GET /farms?canton=ZH,XX` at `verbosity`.
+async fn invalid_canton_error_body(verbosity: ErrorVerbosity) -> serde_json::Value {
+    let app = spawn_app_with(IdempotencyEngine::None, |c| {
+        c.application.error_verbosity = verbosity;
+    })
+    .await;
+
+    let response = app
+        .api_client
+        .get(format!("{}/farms?canton=ZH,XX", app.address))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(StatusCode::BAD_REQUEST.as_u16(), response.status().as_u16());
+    response.json().await.unwrap()
+}
+
+#[tokio::test]
+async fn minimal_error_bodies_only_carry_the_code() {
+    let body = invalid_canton_error_body(ErrorVerbosity::Minimal).await;
+
+    assert_eq!(
+        serde_json::json!({ "error_code": "VALIDATION_ERROR" }),
+        body
+    );
+}
+
+#[tokio::test]
+async fn standard_error_bodies_carry_the_code_and_message() {
+    let body = invalid_canton_error_body(ErrorVerbosity::Standard).await;
+
+    let keys: HashSet<&str> = body
+        .as_object()
+        .unwrap()
+        .keys()
+        .map(String::as_str)
+        .collect();
+    assert_eq!(HashSet::from(["error_code", "message"]), keys, "{body}");
+    assert_eq!("VALIDATION_ERROR", body["error_code"]);
+    assert!(body["message"].as_str().unwrap().contains("XX"), "{body}");
+}
+
+#[tokio::test]
+async fn verbose_error_bodies_name_the_field_and_request_id() {
+    let body = invalid_canton_error_body(ErrorVerbosity::Verbose).await;
+
+    let keys: HashSet<&str> = body
+        .as_object()
+        .unwrap()
+        .keys()
+        .map(String::as_str)
+        .collect();
+    assert_eq!(
+        HashSet::from(["error_code", "message", "field", "request_id"]),
+        keys,
+        "{body}"
+    );
+    assert_eq!("VALIDATION_ERROR", body["error_code"]);
+    assert!(body["message"].as_str().unwrap().contains("XX"), "{body}");
+    assert_eq!("canton", body["field"]);
+    assert!(
+        Uuid::parse_str(body["request_id"].as_str().unwrap()).is_ok(),
+        "{body}"
+    );
+}
+
+#[tokio::test]
+async fn internal_errors_are_described_unless_hidden() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    seed_test_taxonomy(&app.db_pool).await;
+    let user = TestUser::generate_user();
+    log_in_test_user(&app, &user).await;
+    break_farms_table(&app).await;
+
+    let response = app
+        .post_farm(&generate_valid_farm().to_json(Uuid::new_v4()))
+        .await;
+
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!("INTERNAL_ERROR", body["error_code"], "{body}");
+    assert!(
+        body["message"].as_str().unwrap().starts_with("Failed"),
+        "{body}"
+    );
+}
+
+#[tokio::test]
+async fn create_farm_returns_401_for_unauthenticated_users() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    let farm = generate_valid_farm();
+    let body = farm.to_json(Uuid::new_v4());
+
+    let response = app.post_farm(&body).await;
+
+    assert_eq!(
+        StatusCode::UNAUTHORIZED.as_u16(),
+        response.status().as_u16()
+    );
+}
+
+#[tokio::test]
+async fn create_farm_returns_a_400_for_invalid_body_data() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    let test_cases = vec![
+        (
+            serde_json::json!({
+                "address": "Bahnhofstrasse, 5401 Baden",
+                "canton": "ZH",
+                "coordinates": "47.3769,8.5417",
+                "products": ["strawberries"],
+                "idempotency_key": Uuid::new_v4(),
+            }),
+            "missing field 'name'",
+        ),
+        (
+            serde_json::json!({
+                "name": "Farmy",
+                "canton": "ZH",
+                "coordinates": "47.3769,8.5417",
+                "products": ["strawberries"],
+                "idempotency_key": Uuid::new_v4(),
+            }),
+            "missing field 'address'",
+        ),
+        (
+            serde_json::json!({
+                "name": "Farmy",
+                "address": "Bahnhofstrasse, 5401 Baden",
+                "coordinates": "47.3769,8.5417",
+                "products": ["strawberries"],
+                "idempotency_key": Uuid::new_v4(),
+            }),
+            "missing field 'canton'",
+        ),
+        (
+            serde_json::json!({
+                "name": "Farmy",
+                "address": "Bahnhofstrasse, 5401 Baden",
+                "canton": "ZH",
+                "products": ["strawberries"],
+                "idempotency_key": Uuid::new_v4(),
+            }),
+            "missing field 'coordinates'",
+        ),
+        (
+            serde_json::json!({
+                "name": "Farmy",
+                "address": "Bahnhofstrasse, 5401 Baden",
                 "canton": "ZH",
                 "coordinates": "47.3769,8.5417",
                 "products": ["strawberries"],
@@ -534,7 +913,192 @@ async fn create_farm_returns_a_400_for_invalid_body_data() {
 }
 
 #[tokio::test]
-async fn create_farm_returns_400_for_invalid_coordinate_format() {
+async fn create_farm_names_the_missing_field() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    let user = TestUser::generate_user();
+    log_in_test_user(&app, &user).await;
+
+    let response = app
+        .post_farm(&serde_json::json!({
+            "address": "Bahnhofstrasse, 5401 Baden",
+            "canton": "ZH",
+            "coordinates": "47.3769,8.5417",
+            "idempotency_key": Uuid::new_v4(),
+        }))
+        .await;
+
+    assert_eq!(StatusCode::BAD_REQUEST.as_u16(), response.status().as_u16());
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!("VALIDATION_ERROR", body["error_code"]);
+    assert_eq!("Missing field 'name'.", body["message"]);
+    assert_eq!("name", body["field"]);
+}
+
+#[tokio::test]
+async fn create_farm_names_an_unknown_field() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    app.log_in_active_user().await;
+    let mut body = generate_valid_farm().to_json(Uuid::new_v4());
+    body["foo"] = serde_json::json!("bar");
+
+    let response = app.post_farm(&body).await;
+
+    assert_eq!(StatusCode::BAD_REQUEST.as_u16(), response.status().as_u16());
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!("VALIDATION_ERROR", body["error_code"]);
+    assert_eq!("foo", body["field"]);
+    let message = body["message"].as_str().unwrap();
+    assert!(
+        message.starts_with("Unknown field 'foo', expected one of 'name', 'address',"),
+        "{message}"
+    );
+}
+
+#[tokio::test]
+async fn create_farm_reports_a_wrong_typed_field_as_an_invalid_value() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    let user = TestUser::generate_user();
+    log_in_test_user(&app, &user).await;
+
+    let response = app
+        .post_farm(&serde_json::json!({
+            "name": "Farmy",
+            "address": "Bahnhofstrasse, 5401 Baden",
+            "canton": "ZH",
+            "coordinates": 47.3769,
+            "idempotency_key": Uuid::new_v4(),
+        }))
+        .await;
+
+    assert_eq!(StatusCode::BAD_REQUEST.as_u16(), response.status().as_u16());
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!("VALIDATION_ERROR", body["error_code"]);
+    assert_eq!("coordinates", body["field"]);
+    let message = body["message"].as_str().unwrap();
+    assert!(
+        message.starts_with("Invalid value for field 'coordinates': "),
+        "{message}"
+    );
+    assert!(
+        message.contains("invalid type: floating point"),
+        "{message}"
+    );
+    assert!(message.contains("expected a string"), "{message}");
+}
+
+#[tokio::test]
+async fn create_farm_reports_malformed_json() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    let user = TestUser::generate_user();
+    log_in_test_user(&app, &user).await;
+
+    let response = app
+        .api_client
+        .post(format!("{}/farms", &app.address))
+        .header("Content-Type", "application/json")
+        .body(r#"{"name": "Farmy","#)
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(StatusCode::BAD_REQUEST.as_u16(), response.status().as_u16());
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!("VALIDATION_ERROR", body["error_code"]);
+    let message = body["message"].as_str().unwrap();
+    assert!(
+        message.starts_with("Malformed JSON at line 1, column "),
+        "{message}"
+    );
+}
+
+#[tokio::test]
+async fn create_farm_reports_an_unreadable_body_as_a_farm_error() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    app.log_in_active_user().await;
+
+    let cases = [
+        ("text/plain", "{}".to_string(), StatusCode::BAD_REQUEST),
+        (
+            "application/json",
+            " ".repeat(farms::startup::MAX_BODY_BYTES + 1),
+            StatusCode::PAYLOAD_TOO_LARGE,
+        ),
+    ];
+    for (content_type, body, status) in cases {
+        let response = app
+            .api_client
+            .post(format!("{}/farms", &app.address))
+            .header("Content-Type", content_type)
+            .body(body)
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(
+            status.as_u16(),
+            response.status().as_u16(),
+            "{content_type}"
+        );
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!("VALIDATION_ERROR", body["error_code"], "{content_type}");
+        assert!(body["message"].is_string(), "{content_type}");
+    }
+}
+
+#[tokio::test]
+async fn an_oversized_body_is_rejected_before_it_is_sent() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    let user = TestUser::generate_user();
+    user.store(&app.db_pool).await;
+    let login = app
+        .post_login(&serde_json::json!({"email": user.email, "password": user.password}))
+        .await;
+    let cookies = login
+        .headers()
+        .get_all("set-cookie")
+        .iter()
+        .filter_map(|value| value.to_str().ok()?.split(';').next())
+        .collect::<Vec<_>>()
+        .join("; ");
+    let host = app.address.trim_start_matches("http://").to_owned();
+
+    // Raw HTTP/1.1, so the declared body is never actually sent.
+    let response = tokio::task::spawn_blocking(move || {
+        use std::io::{Read, Write};
+        let mut stream = std::net::TcpStream::connect(&host).unwrap();
+        stream
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        write!(
+            stream,
+            "POST /farms HTTP/1.1\r\nHost: {host}\r\nCookie: {cookies}\r\n\
+             Content-Type: application/json\r\nContent-Length: {}\r\n\
+             Expect: 100-continue\r\n\r\n",
+            10 * farms::startup::MAX_BODY_BYTES
+        )
+        .unwrap();
+
+        let mut received = String::new();
+        let mut buffer = [0; 1024];
+        while !received.contains("HTTP/1.1 4") {
+            match stream.read(&mut buffer) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => received.push_str(&String::from_utf8_lossy(&buffer[..n])),
+            }
+        }
+        received
+    })
+    .await
+    .unwrap();
+
+    assert!(
+        response.contains("HTTP/1.1 413"),
+        "expected a 413 without sending the body, got: {response}"
+    );
+}
+
+#[tokio::test]
+async fn create_farm_returns_422_for_invalid_coordinate_format() {
     let app = spawn_app(IdempotencyEngine::None).await;
 
     let test_cases = vec![
@@ -557,16 +1121,48 @@ async fn create_farm_returns_400_for_invalid_coordinate_format() {
         });
         let response = app.post_farm(&body).await;
         assert_eq!(
-            StatusCode::BAD_REQUEST.as_u16(),
+            StatusCode::UNPROCESSABLE_ENTITY.as_u16(),
             response.status().as_u16(),
-            "The API did not fail with 400 Bad Request for {}.",
+            "The API did not fail with 422 Unprocessable Entity for {}.",
             error_message
         );
     }
 }
 
 #[tokio::test]
-async fn create_farm_returns_400_for_coordinates_outside_switzerland() {
+async fn create_farm_names_the_field_that_failed_validation() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    app.log_in_active_user().await;
+
+    let test_cases = [
+        ("name", serde_json::json!("")),
+        ("address", serde_json::json!("")),
+        ("canton", serde_json::json!("XX")),
+        ("coordinates", serde_json::json!("52.5200,13.4050")),
+        ("products", serde_json::json!(["no-such-product"])),
+        ("categories", serde_json::json!(["no-such-group"])),
+        ("certifications", serde_json::json!(["no-such-label"])),
+    ];
+
+    for (field, value) in test_cases {
+        let mut body = generate_valid_farm().to_json(Uuid::new_v4());
+        body[field] = value;
+
+        let response = app.post_farm(&body).await;
+
+        assert_eq!(
+            StatusCode::UNPROCESSABLE_ENTITY.as_u16(),
+            response.status().as_u16(),
+            "{field}"
+        );
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!("UNPROCESSABLE", body["error_code"], "{field}");
+        assert_eq!(field, body["field"], "{field}");
+    }
+}
+
+#[tokio::test]
+async fn create_farm_returns_422_for_coordinates_outside_switzerland() {
     let app = spawn_app(IdempotencyEngine::None).await;
 
     let user = TestUser::generate_user();
@@ -583,11 +1179,38 @@ async fn create_farm_returns_400_for_coordinates_outside_switzerland() {
 
     let response = app.post_farm(&body).await;
 
-    assert_eq!(StatusCode::BAD_REQUEST.as_u16(), response.status().as_u16());
+    assert_eq!(
+        StatusCode::UNPROCESSABLE_ENTITY.as_u16(),
+        response.status().as_u16()
+    );
+}
+
+#[tokio::test]
+async fn create_farm_tells_a_missing_field_from_coordinates_outside_switzerland() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    app.log_in_active_user().await;
+    let mut missing = generate_valid_farm().to_json(Uuid::new_v4());
+    missing.as_object_mut().unwrap().remove("coordinates");
+    let mut abroad = generate_valid_farm().to_json(Uuid::new_v4());
+    abroad["coordinates"] = "52.5200,13.4050".into();
+
+    let missing_response = app.post_farm(&missing).await;
+    let abroad_response = app.post_farm(&abroad).await;
+
+    assert_eq!(
+        StatusCode::BAD_REQUEST.as_u16(),
+        missing_response.status().as_u16()
+    );
+    assert_eq!(
+        StatusCode::UNPROCESSABLE_ENTITY.as_u16(),
+        abroad_response.status().as_u16()
+    );
+    let body: serde_json::Value = abroad_response.json().await.unwrap();
+    assert_eq!("UNPROCESSABLE", body["error_code"]);
 }
 
 #[tokio::test]
-async fn create_farm_returns_400_for_invalid_latitude() {
+async fn create_farm_returns_422_for_invalid_latitude() {
     let app = spawn_app(IdempotencyEngine::None).await;
 
     let user = TestUser::generate_user();
@@ -604,11 +1227,14 @@ async fn create_farm_returns_400_for_invalid_latitude() {
 
     let response = app.post_farm(&body).await;
 
-    assert_eq!(StatusCode::BAD_REQUEST.as_u16(), response.status().as_u16());
+    assert_eq!(
+        StatusCode::UNPROCESSABLE_ENTITY.as_u16(),
+        response.status().as_u16()
+    );
 }
 
 #[tokio::test]
-async fn create_farm_returns_400_for_invalid_longitude() {
+async fn create_farm_returns_422_for_invalid_longitude() {
     let app = spawn_app(IdempotencyEngine::None).await;
 
     let user = TestUser::generate_user();
@@ -625,78 +1251,905 @@ async fn create_farm_returns_400_for_invalid_longitude() {
 
     let response = app.post_farm(&body).await;
 
-    assert_eq!(StatusCode::BAD_REQUEST.as_u16(), response.status().as_u16());
+    assert_eq!(
+        StatusCode::UNPROCESSABLE_ENTITY.as_u16(),
+        response.status().as_u16()
+    );
+}
+
+#[tokio::test]
+async fn create_farm_returns_201_for_all_valid_swiss_cantons() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    seed_test_taxonomy(&app.db_pool).await;
+
+    let cantons = vec![
+        "ZH", "BE", "LU", "UR", "SZ", "OW", "NW", "GL", "ZG", "FR", "SO", "BS", "BL", "SH", "AR",
+        "AI", "SG", "GR", "AG", "TG", "TI", "VD", "VS", "NE", "GE", "JU",
+    ];
+
+    let user = TestUser::generate_user();
+    log_in_test_user(&app, &user).await;
+
+    for canton in cantons {
+        let body = serde_json::json!({
+            "name": format!("{} Farm", canton),
+            "address": "Test Address",
+            "canton": canton,
+            "coordinates": "47.3769,8.5417",
+            "products": ["strawberries"],
+            "idempotency_key": Uuid::new_v4(),
+        });
+
+        let response = app.post_farm(&body).await;
+        assert_eq!(
+            StatusCode::CREATED.as_u16(),
+            response.status().as_u16(),
+            "Failed to create farm for canton {}",
+            canton
+        );
+    }
+}
+
+#[tokio::test]
+async fn create_farm_rejects_a_name_on_the_configured_blocklist() {
+    let app = spawn_app_with(IdempotencyEngine::None, |c| {
+        c.farms.name_blocklist = vec!["test".to_string()];
+    })
+    .await;
+    seed_test_taxonomy(&app.db_pool).await;
+    let user = TestUser::generate_user();
+    log_in_test_user(&app, &user).await;
+
+    let mut blocked = generate_valid_farm().to_json(Uuid::new_v4());
+    blocked["name"] = serde_json::json!("Test Hof");
+    let mut allowed = generate_valid_farm().to_json(Uuid::new_v4());
+    allowed["name"] = serde_json::json!("Bergmilch");
+
+    let blocked_response = app.post_farm(&blocked).await;
+    let allowed_response = app.post_farm(&allowed).await;
+
+    assert_eq!(
+        blocked_response.status().as_u16(),
+        StatusCode::UNPROCESSABLE_ENTITY.as_u16()
+    );
+    assert_eq!(
+        allowed_response.status().as_u16(),
+        StatusCode::CREATED.as_u16()
+    );
+}
+
+#[tokio::test]
+async fn create_farm_stores_certifications_and_returns_them() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    seed_test_taxonomy(&app.db_pool).await;
+    let user = TestUser::generate_user();
+    log_in_test_user(&app, &user).await;
+
+    let farm = generate_valid_farm();
+    let mut body = farm.to_json(Uuid::new_v4());
+    body["certifications"] = serde_json::json!(["demeter", " Bio_Suisse", "demeter"]);
+    let response = app.post_farm(&body).await;
+    assert_eq!(StatusCode::CREATED.as_u16(), response.status().as_u16());
+
+    let farms = farms_array(app.get_farms().await).await;
+    assert_eq!(
+        serde_json::json!(["bio_suisse", "demeter"]),
+        farms[0]["certifications"]
+    );
+    let stored = sqlx::query_scalar!("SELECT certifications FROM farms")
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap();
+    assert_eq!(
+        Some(vec!["bio_suisse".to_string(), "demeter".to_string()]),
+        stored
+    );
+}
+
+#[tokio::test]
+async fn create_farm_without_certifications_stores_null() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    seed_test_taxonomy(&app.db_pool).await;
+    let user = TestUser::generate_user();
+    log_in_test_user(&app, &user).await;
+
+    let response = app
+        .post_farm(&generate_valid_farm().to_json(Uuid::new_v4()))
+        .await;
+    assert_eq!(StatusCode::CREATED.as_u16(), response.status().as_u16());
+
+    let farms = farms_array(app.get_farms().await).await;
+    assert_eq!(serde_json::json!([]), farms[0]["certifications"]);
+    let stored = sqlx::query_scalar!("SELECT certifications FROM farms")
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap();
+    assert_eq!(None, stored);
+}
+
+#[tokio::test]
+async fn create_farm_rejects_an_unknown_certification() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    seed_test_taxonomy(&app.db_pool).await;
+    let user = TestUser::generate_user();
+    log_in_test_user(&app, &user).await;
+
+    for certifications in [
+        serde_json::json!(["organic"]),
+        serde_json::json!(["bio_suisse", ""]),
+    ] {
+        let mut body = generate_valid_farm().to_json(Uuid::new_v4());
+        body["certifications"] = certifications.clone();
+        let response = app.post_farm(&body).await;
+
+        assert_eq!(
+            StatusCode::UNPROCESSABLE_ENTITY.as_u16(),
+            response.status().as_u16(),
+            "The API did not reject certifications {certifications}."
+        );
+    }
+}
+
+#[tokio::test]
+async fn create_farm_rejects_an_invalid_value_in_any_field() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    seed_test_taxonomy(&app.db_pool).await;
+    let user = TestUser::generate_user();
+    log_in_test_user(&app, &user).await;
+    let test_cases = [
+        ("name", serde_json::json!("Hof <script>")),
+        ("address", serde_json::json!("   ")),
+        ("canton", serde_json::json!("XX")),
+        ("coordinates", serde_json::json!("48.8566,2.3522")),
+        ("products", serde_json::json!(["not-a-product"])),
+        ("certifications", serde_json::json!(["organic"])),
+    ];
+
+    for (field, value) in test_cases {
+        let mut body = generate_valid_farm().to_json(Uuid::new_v4());
+        body[field] = value.clone();
+        let response = app.post_farm(&body).await;
+
+        assert_eq!(
+            StatusCode::UNPROCESSABLE_ENTITY.as_u16(),
+            response.status().as_u16(),
+            "The API did not reject {field} = {value}."
+        );
+    }
+    let stored = sqlx::query_scalar!("SELECT COUNT(*) FROM farms")
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap();
+    assert_eq!(Some(0), stored);
+}
+
+#[tokio::test]
+async fn create_farm_records_exactly_one_audit_entry() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    seed_test_taxonomy(&app.db_pool).await;
+    let user = TestUser::generate_user();
+    log_in_test_user(&app, &user).await;
+
+    let farm = generate_valid_farm();
+    let response = app.post_farm(&farm.to_json(Uuid::new_v4())).await;
+    assert_eq!(StatusCode::CREATED.as_u16(), response.status().as_u16());
+
+    let farm_id = sqlx::query_scalar!("SELECT id FROM farms")
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap();
+    let entries =
+        sqlx::query!(r#"SELECT action, farm_id, user_id, payload, request_id FROM audit_log"#)
+            .fetch_all(&app.db_pool)
+            .await
+            .unwrap();
+
+    assert_eq!(1, entries.len());
+    let entry = &entries[0];
+    assert_eq!("create", entry.action);
+    assert_eq!(farm_id, entry.farm_id);
+    assert_eq!(user.id, entry.user_id);
+    assert!(entry.request_id.is_some());
+    assert_eq!(entry.payload["name"], farm.name.as_ref());
+}
+
+#[tokio::test]
+async fn create_farm_trims_every_string_field() {
+    let app = spawn_app(IdempotencyEngine::Postgres).await;
+    seed_test_taxonomy(&app.db_pool).await;
+    let user = TestUser::generate_user();
+    log_in_test_user(&app, &user).await;
+    let idempotency_key = Uuid::new_v4();
+
+    let body = serde_json::json!({
+        "name": "  Hof Sonnenberg ",
+        "address": " Road 1, 8000 Zürich\t",
+        "canton": " zh ",
+        "coordinates": " 47.3769,8.5417 ",
+        "products": [" strawberries "],
+        "idempotency_key": format!(" {idempotency_key} "),
+    });
+    let response = app.post_farm(&body).await;
+    assert_eq!(StatusCode::CREATED.as_u16(), response.status().as_u16());
+
+    let saved = sqlx::query!("SELECT name, address, canton FROM farms")
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap();
+    assert_eq!("Hof Sonnenberg", saved.name);
+    assert_eq!("Road 1, 8000 Zürich", saved.address);
+    assert_eq!("ZH", saved.canton);
+
+    let payload = sqlx::query_scalar!("SELECT payload FROM audit_log")
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap();
+    assert_eq!("Hof Sonnenberg", payload["name"]);
+    assert_eq!("Road 1, 8000 Zürich", payload["address"]);
+    assert_eq!("zh", payload["canton"]);
+    assert_eq!("47.3769,8.5417", payload["coordinates"]);
+    assert_eq!(serde_json::json!(["strawberries"]), payload["products"]);
+    assert_eq!(idempotency_key.to_string(), payload["idempotency_key"]);
+}
+
+#[tokio::test]
+async fn a_replayed_create_is_not_audited_twice() {
+    let app = spawn_app(IdempotencyEngine::Postgres).await;
+    seed_test_taxonomy(&app.db_pool).await;
+    let user = TestUser::generate_user();
+    log_in_test_user(&app, &user).await;
+
+    let body = generate_valid_farm().to_json(Uuid::new_v4());
+    for _ in 0..2 {
+        let response = app.post_farm(&body).await;
+        assert_eq!(StatusCode::CREATED.as_u16(), response.status().as_u16());
+    }
+
+    let entries = sqlx::query_scalar!(r#"SELECT COUNT(*) AS "count!" FROM audit_log"#)
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap();
+    assert_eq!(1, entries);
+}
+
+#[tokio::test]
+async fn audit_entries_cannot_be_changed_or_deleted() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    seed_test_taxonomy(&app.db_pool).await;
+    let user = TestUser::generate_user();
+    log_in_test_user(&app, &user).await;
+    app.post_farm(&generate_valid_farm().to_json(Uuid::new_v4()))
+        .await;
+
+    let update = sqlx::query!("UPDATE audit_log SET action = 'tampered'")
+        .execute(&app.db_pool)
+        .await;
+    let delete = sqlx::query!("DELETE FROM audit_log")
+        .execute(&app.db_pool)
+        .await;
+
+    assert!(update.is_err());
+    assert!(delete.is_err());
+}
+
+#[tokio::test]
+async fn a_farm_cannot_be_updated_before_it_was_created() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    seed_test_taxonomy(&app.db_pool).await;
+    let user = TestUser::generate_user();
+    log_in_test_user(&app, &user).await;
+    app.post_farm(&generate_valid_farm().to_json(Uuid::new_v4()))
+        .await;
+
+    let backdated = sqlx::query!("UPDATE farms SET updated_at = created_at - interval '1 second'")
+        .execute(&app.db_pool)
+        .await;
+    let updated = sqlx::query!(
+        r#"UPDATE farms SET updated_at = now() RETURNING created_at, updated_at AS "updated_at!""#
+    )
+    .fetch_one(&app.db_pool)
+    .await
+    .expect("Failed to update the farm.");
+
+    let Err(sqlx::Error::Database(e)) = backdated else {
+        panic!("a backdated updated_at was accepted");
+    };
+    assert_eq!(Some("farms_updated_at_after_created_at"), e.constraint());
+    assert!(updated.updated_at >= updated.created_at);
+}
+
+#[tokio::test]
+async fn a_farm_never_updated_reports_updated_at_as_null() {
+    // Arrange
+    let app = spawn_app(IdempotencyEngine::None).await;
+    let farm_id = insert_test_farm(&app.db_pool, "Hof ohne Änderungen").await;
+    let export_url = format!("{}/farms/export.json", app.address);
+
+    // Act
+    let detail = app.get_farm(farm_id).await.text().await.unwrap();
+    let list = app.get_farms().await.text().await.unwrap();
+    let export = app
+        .api_client
+        .get(&export_url)
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .text()
+        .await
+        .unwrap();
+
+    // Assert
+    for body in [detail, list, export] {
+        assert!(body.contains(r#""updated_at":null"#), "{body}");
+    }
+}
+
+#[tokio::test]
+async fn the_database_rejects_an_invalid_canton() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+
+    for canton in ["XX", "zh", ""] {
+        let insert = sqlx::query!(
+            r#"
+            INSERT INTO farms (id, name, address, canton, coordinates, created_at)
+            VALUES ($1, 'Farm', 'Road 1, 8000 Zürich', $2, POINT(8.5, 47.4), now())
+            "#,
+            Uuid::new_v4(),
+            canton,
+        )
+        .execute(&app.db_pool)
+        .await;
+
+        let Err(sqlx::Error::Database(e)) = &insert else {
+            panic!("canton '{canton}' was accepted");
+        };
+        assert_eq!(Some("farms_canton_valid"), e.constraint());
+
+        // Surfaces as an unprocessable farm rather than a 500.
+        let error = FarmError::from(anyhow::Error::from(insert.unwrap_err()));
+        assert_eq!(
+            StatusCode::UNPROCESSABLE_ENTITY.as_u16(),
+            error.status_code().as_u16()
+        );
+    }
+}
+
+#[tokio::test]
+async fn a_farm_joins_its_canton_for_a_localized_name() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    let farm_id = insert_test_farm(&app.db_pool, "Ferme du Lac").await;
+    sqlx::query!("UPDATE farms SET canton = 'GE' WHERE id = $1", farm_id)
+        .execute(&app.db_pool)
+        .await
+        .unwrap();
+
+    let name_fr = sqlx::query_scalar!(
+        r#"
+        SELECT c.name_fr
+        FROM farms f
+        JOIN cantons c ON c.code = f.canton
+        WHERE f.id = $1
+        "#,
+        farm_id,
+    )
+    .fetch_one(&app.db_pool)
+    .await
+    .unwrap();
+
+    assert_eq!("Genève", name_fr);
+}
+
+#[tokio::test]
+async fn the_cantons_table_has_the_built_in_canton_names() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+
+    let names = CantonNames::load(&app.db_pool).await.unwrap();
+
+    for code in [
+        "AG", "AI", "AR", "BE", "BL", "BS", "FR", "GE", "GL", "GR", "JU", "LU", "NE", "NW", "OW",
+        "SG", "SH", "SO", "SZ", "TG", "TI", "UR", "VD", "VS", "ZG", "ZH",
+    ] {
+        let canton = Canton::parse(code.to_string()).unwrap();
+        for language in Language::ALL {
+            assert_eq!(
+                canton.name(language),
+                canton.name_in(&names, language),
+                "{code} in {language:?}"
+            );
+        }
+    }
+}
+
+async fn post_farm_form(app: &TestApp, pairs: &[(&str, &str)]) -> reqwest::Response {
+    app.api_client
+        .post(format!("{}/farms", &app.address))
+        .form(pairs)
+        .send()
+        .await
+        .expect("Failed to execute request.")
+}
+
+/// Everything stored for the only farm in `app`, minus ids and timestamps.
+async fn stored_farm(app: &TestApp) -> serde_json::Value {
+    sqlx::query_scalar!(
+        r#"
+        SELECT json_build_object(
+            'name', f.name,
+            'address', f.address,
+            'postal_code', f.postal_code,
+            'canton', f.canton,
+            'coordinates', f.coordinates::text,
+            'certifications', f.certifications,
+            'categories', ARRAY(
+                SELECT c.slug FROM farm_categories fc
+                JOIN product_categories c ON c.id = fc.category_id
+                WHERE fc.farm_id = f.id ORDER BY c.slug
+            ),
+            'products', ARRAY(
+                SELECT p.slug FROM farm_products fp
+                JOIN products p ON p.id = fp.product_id
+                WHERE fp.farm_id = f.id ORDER BY p.slug
+            )
+        ) AS "farm!"
+        FROM farms f
+        "#
+    )
+    .fetch_one(&app.db_pool)
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn create_farm_accepts_the_same_farm_as_json_or_as_a_form() {
+    let json_app = spawn_app(IdempotencyEngine::None).await;
+    let form_app = spawn_app(IdempotencyEngine::None).await;
+    let user = TestUser::generate_user();
+    log_in_test_user(&json_app, &user).await;
+    log_in_test_user(&form_app, &user).await;
+
+    let json_response = json_app
+        .post_farm(&serde_json::json!({
+            "name": "Hof Sonnenberg",
+            "address": "Landhausweg 19, 5000 Aarau",
+            "canton": "AG",
+            "coordinates": "47.3925,8.0442",
+            "categories": ["vegetables"],
+            "products": ["strawberries", "cherries"],
+            "certifications": ["bio_suisse"],
+            "idempotency_key": Uuid::new_v4(),
+        }))
+        .await;
+    let idempotency_key = Uuid::new_v4().to_string();
+    let form_response = post_farm_form(
+        &form_app,
+        &[
+            ("name", "Hof Sonnenberg"),
+            ("address", "Landhausweg 19, 5000 Aarau"),
+            ("canton", "AG"),
+            ("coordinates", "47.3925,8.0442"),
+            ("categories[]", "vegetables"),
+            ("products[]", "strawberries"),
+            ("products[]", "cherries"),
+            ("certifications", "bio_suisse"),
+            ("idempotency_key", &idempotency_key),
+        ],
+    )
+    .await;
+
+    assert_eq!(
+        StatusCode::CREATED.as_u16(),
+        json_response.status().as_u16()
+    );
+    assert_eq!(
+        StatusCode::CREATED.as_u16(),
+        form_response.status().as_u16()
+    );
+    assert_eq!(stored_farm(&json_app).await, stored_farm(&form_app).await);
+}
+
+#[tokio::test]
+async fn create_farm_form_ignores_unknown_fields() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    seed_test_taxonomy(&app.db_pool).await;
+    app.log_in_active_user().await;
+
+    let response = post_farm_form(
+        &app,
+        &[
+            ("name", "Hof Sonnenberg"),
+            ("address", "Landhausweg 19, 5000 Aarau"),
+            ("canton", "AG"),
+            ("coordinates", "47.3925,8.0442"),
+            ("products[]", "strawberries"),
+            ("idempotency_key", &Uuid::new_v4().to_string()),
+            // What an HTML form's submit button adds.
+            ("submit", "Save"),
+        ],
+    )
+    .await;
+
+    assert_eq!(StatusCode::CREATED.as_u16(), response.status().as_u16());
+}
+
+#[tokio::test]
+async fn create_farm_form_names_the_missing_field() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    let user = TestUser::generate_user();
+    log_in_test_user(&app, &user).await;
+
+    let response = post_farm_form(
+        &app,
+        &[
+            ("name", "Hof Sonnenberg"),
+            ("address", "Landhausweg 19, 5000 Aarau"),
+            ("canton", "AG"),
+            ("products[]", "strawberries"),
+            ("idempotency_key", &Uuid::new_v4().to_string()),
+        ],
+    )
+    .await;
+
+    assert_eq!(StatusCode::BAD_REQUEST.as_u16(), response.status().as_u16());
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!("VALIDATION_ERROR", body["error_code"]);
+    assert_eq!("Missing field 'coordinates'.", body["message"]);
+    assert!(Uuid::parse_str(body["request_id"].as_str().unwrap()).is_ok());
+}
+
+#[tokio::test]
+async fn create_farm_warns_about_a_near_duplicate_name_in_the_same_canton() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    seed_test_taxonomy(&app.db_pool).await;
+    let user = TestUser::generate_user();
+    log_in_test_user(&app, &user).await;
+    let post = async |name: &str, canton: &str| {
+        let canton = Canton::parse(canton.to_string()).unwrap();
+        let mut body = generate_valid_farm()
+            .with_coordinates(generate_coordinates_in(&canton))
+            .with_canton(canton)
+            .to_json(Uuid::new_v4());
+        body["name"] = serde_json::json!(name);
+        let response = app.post_farm(&body).await;
+        assert_eq!(StatusCode::CREATED.as_u16(), response.status().as_u16());
+        response.json::<serde_json::Value>().await.unwrap()
+    };
+
+    let original = post("Hof Sonnenberg", "ZH").await;
+    let near_duplicate = post("Hof Sonneberg", "ZH").await;
+    let other_canton = post("Hof Sonnenberg", "BE").await;
+    let unrelated = post("Bergmilch Käserei", "ZH").await;
+
+    assert_eq!(serde_json::json!([]), original["warnings"]);
+    let warnings = near_duplicate["warnings"].as_array().unwrap();
+    assert_eq!(1, warnings.len());
+    assert_eq!("possible_duplicate", warnings[0]["code"]);
+    assert_eq!("Hof Sonnenberg", warnings[0]["name"]);
+    assert_eq!(original["id"], warnings[0]["farm_id"]);
+    assert_eq!(serde_json::json!([]), other_canton["warnings"]);
+    assert_eq!(serde_json::json!([]), unrelated["warnings"]);
+
+    // Warnings never block creation.
+    let farms = sqlx::query_scalar!(r#"SELECT COUNT(*) AS "count!" FROM farms"#)
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap();
+    assert_eq!(4, farms);
+}
+
+#[tokio::test]
+async fn create_farm_stores_the_canton_of_the_nearest_capital() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    seed_test_taxonomy(&app.db_pool).await;
+    app.log_in_active_user().await;
+    let mut body = generate_valid_farm().to_json(Uuid::new_v4());
+    body["canton"] = serde_json::json!("ZH");
+    body["coordinates"] = serde_json::json!("47.3769,8.5417");
+
+    let response = app.post_farm(&body).await;
+
+    assert_eq!(StatusCode::CREATED.as_u16(), response.status().as_u16());
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(serde_json::json!([]), body["warnings"]);
+    let stored = sqlx::query_scalar!("SELECT nearest_capital_canton FROM farms")
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap();
+    assert_eq!(Some("ZH".to_string()), stored);
+}
+
+#[tokio::test]
+async fn the_cantons_table_has_the_capitals_the_app_uses() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+
+    let rows = sqlx::query!("SELECT code, capital_lat, capital_lon FROM cantons")
+        .fetch_all(&app.db_pool)
+        .await
+        .unwrap();
+
+    assert_eq!(26, rows.len());
+    for row in rows {
+        let capital = Canton::parse(row.code).unwrap().capital_point();
+        assert_eq!(Point::new(row.capital_lat, row.capital_lon), capital);
+    }
+}
+
+#[tokio::test]
+async fn create_farm_warns_when_the_coordinates_point_to_another_canton() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    seed_test_taxonomy(&app.db_pool).await;
+    app.log_in_active_user().await;
+    let mut body = generate_valid_farm().to_json(Uuid::new_v4());
+    body["canton"] = serde_json::json!("GE");
+    body["coordinates"] = serde_json::json!("47.3769,8.5417");
+
+    let response = app.post_farm(&body).await;
+
+    assert_eq!(StatusCode::CREATED.as_u16(), response.status().as_u16());
+    let body: serde_json::Value = response.json().await.unwrap();
+    let warnings = body["warnings"].as_array().unwrap();
+    assert_eq!(1, warnings.len());
+    assert_eq!("canton_mismatch", warnings[0]["code"]);
+    assert_eq!("ZH", warnings[0]["nearest_capital_canton"]);
+}
+
+#[tokio::test]
+async fn create_farm_rejects_a_canton_mismatch_when_configured_to() {
+    let app = spawn_app_with(IdempotencyEngine::None, |c| {
+        c.farms.canton_check.on_mismatch = CantonMismatchAction::Reject;
+    })
+    .await;
+    seed_test_taxonomy(&app.db_pool).await;
+    app.log_in_active_user().await;
+    let mut body = generate_valid_farm().to_json(Uuid::new_v4());
+    body["canton"] = serde_json::json!("GE");
+    body["coordinates"] = serde_json::json!("47.3769,8.5417");
+
+    let response = app.post_farm(&body).await;
+
+    assert_eq!(
+        StatusCode::UNPROCESSABLE_ENTITY.as_u16(),
+        response.status().as_u16()
+    );
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!("UNPROCESSABLE", body["error_code"]);
+    assert_eq!(
+        "The coordinates point to canton ZH, not GE.",
+        body["message"]
+    );
+}
+
+#[tokio::test]
+async fn created_farm_categories_round_trip_through_the_join_table() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    let taxonomy = seed_test_taxonomy(&app.db_pool).await;
+    let user = TestUser::generate_user();
+    log_in_test_user(&app, &user).await;
+    let count_categories = async || {
+        sqlx::query_scalar!(r#"SELECT COUNT(*) AS "count!" FROM product_categories"#)
+            .fetch_one(&app.db_pool)
+            .await
+            .unwrap()
+    };
+    let categories_before = count_categories().await;
+    let category_sets = [
+        vec!["fruits"],
+        vec!["vegetables"],
+        vec!["vegetables", "fruits"],
+        vec!["fruits", "vegetables"],
+    ];
+
+    let mut posted = Vec::with_capacity(category_sets.len());
+    for categories in &category_sets {
+        let farm = generate_valid_farm();
+        let mut body = farm.to_json(Uuid::new_v4());
+        body["products"] = serde_json::json!([]);
+        body["categories"] = serde_json::json!(categories);
+        let response = app.post_farm(&body).await;
+        assert_eq!(StatusCode::CREATED.as_u16(), response.status().as_u16());
+
+        let categories = categories.iter().map(|c| c.to_string()).collect();
+        posted.push((farm.name, Categories::parse(categories).unwrap()));
+    }
+
+    let farms = farms_array(app.get_farms().await).await;
+    assert_eq!(farms.len(), posted.len());
+    for (name, categories) in &posted {
+        let farm = farms
+            .iter()
+            .find(|f| f["name"] == name.as_ref())
+            .expect("Created farm is missing from the list.");
+        let returned: Vec<String> = serde_json::from_value(farm["categories"].clone()).unwrap();
+        assert_eq!(&Categories::parse(returned).unwrap(), categories);
+    }
+
+    // Each category string is stored once; farms only reference it by id.
+    assert_eq!(categories_before, count_categories().await);
+    let join_rows = sqlx::query_scalar!("SELECT category_id FROM farm_categories")
+        .fetch_all(&app.db_pool)
+        .await
+        .unwrap();
+    assert_eq!(
+        join_rows.len(),
+        category_sets.iter().map(Vec::len).sum::<usize>()
+    );
+    assert_eq!(
+        HashSet::from([taxonomy.fruits_category_id, taxonomy.vegetables_category_id]),
+        join_rows.into_iter().collect::<HashSet<_>>()
+    );
+}
+
+#[tokio::test]
+async fn create_farm_called_multiple_times_sequentially_doesnt_create_duplicate_farms_in_db_redis()
+{
+    create_farm_called_multiple_times_sequentially_doesnt_create_duplicate_farms_in_db(
+        IdempotencyEngine::Redis,
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn create_farm_called_multiple_times_sequentially_doesnt_create_duplicate_farms_in_db_postgres()
+ {
+    create_farm_called_multiple_times_sequentially_doesnt_create_duplicate_farms_in_db(
+        IdempotencyEngine::Postgres,
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn create_farm_called_multiple_times_sequentially_doesnt_create_duplicate_farms_in_db_memory()
+{
+    create_farm_called_multiple_times_sequentially_doesnt_create_duplicate_farms_in_db(
+        IdempotencyEngine::Memory,
+    )
+    .await;
+}
+
+async fn create_farm_called_multiple_times_sequentially_doesnt_create_duplicate_farms_in_db(
+    idempotency_engine: IdempotencyEngine,
+) {
+    let app = spawn_app(idempotency_engine).await;
+    seed_test_taxonomy(&app.db_pool).await;
+    let farm = generate_valid_farm();
+    let idempotency_key = Uuid::new_v4();
+
+    let user = TestUser::generate_user();
+    log_in_test_user(&app, &user).await;
+
+    let body = farm.to_json(idempotency_key);
+
+    let response1 = app.post_farm(&body).await;
+    let response2 = app.post_farm(&body).await;
+
+    assert_eq!(response1.status(), StatusCode::CREATED.as_u16());
+    assert_eq!(response2.status(), StatusCode::CREATED.as_u16());
+
+    let saved = sqlx::query!("SELECT id FROM farms")
+        .fetch_all(&app.db_pool)
+        .await
+        .expect("Failed to fetch saved farms.");
+
+    assert_eq!(saved.len(), 1);
+}
+
+#[tokio::test]
+async fn an_expired_postgres_idempotency_key_is_processed_again() {
+    // Arrange
+    let app = spawn_app(IdempotencyEngine::Postgres).await;
+    seed_test_taxonomy(&app.db_pool).await;
+    let user = TestUser::generate_user();
+    log_in_test_user(&app, &user).await;
+    let body = generate_valid_farm().to_json(Uuid::new_v4());
+    let first = app.post_farm(&body).await;
+    sqlx::query!("UPDATE idempotency SET expire_at = now() - interval '1 second'")
+        .execute(&app.db_pool)
+        .await
+        .expect("Failed to expire the idempotency key.");
+
+    // Act
+    let second = app.post_farm(&body).await;
+
+    // Assert
+    assert_eq!(first.status(), StatusCode::CREATED.as_u16());
+    assert_eq!(second.status(), StatusCode::CREATED.as_u16());
+    assert_eq!("false", second.headers()["idempotency-replayed"]);
+    let saved = sqlx::query!("SELECT id FROM farms")
+        .fetch_all(&app.db_pool)
+        .await
+        .expect("Failed to fetch saved farms.");
+    assert_eq!(saved.len(), 2);
+    let rows = sqlx::query!(r#"SELECT expire_at > now() AS "live!" FROM idempotency"#)
+        .fetch_all(&app.db_pool)
+        .await
+        .expect("Failed to fetch idempotency rows.");
+    assert_eq!(rows.len(), 1);
+    assert!(rows[0].live);
+}
+
+#[tokio::test]
+async fn create_farm_flags_replayed_responses_redis() {
+    create_farm_flags_replayed_responses(IdempotencyEngine::Redis).await;
+}
+
+#[tokio::test]
+async fn create_farm_flags_replayed_responses_postgres() {
+    create_farm_flags_replayed_responses(IdempotencyEngine::Postgres).await;
 }
 
 #[tokio::test]
-async fn create_farm_returns_201_for_all_valid_swiss_cantons() {
-    let app = spawn_app(IdempotencyEngine::None).await;
-    seed_test_taxonomy(&app.db_pool).await;
-
-    let cantons = vec![
-        "ZH", "BE", "LU", "UR", "SZ", "OW", "NW", "GL", "ZG", "FR", "SO", "BS", "BL", "SH", "AR",
-        "AI", "SG", "GR", "AG", "TG", "TI", "VD", "VS", "NE", "GE", "JU",
-    ];
+async fn create_farm_flags_replayed_responses_memory() {
+    create_farm_flags_replayed_responses(IdempotencyEngine::Memory).await;
+}
 
+async fn create_farm_flags_replayed_responses(idempotency_engine: IdempotencyEngine) {
+    let app = spawn_app(idempotency_engine).await;
+    seed_test_taxonomy(&app.db_pool).await;
     let user = TestUser::generate_user();
     log_in_test_user(&app, &user).await;
+    let body = generate_valid_farm().to_json(Uuid::new_v4());
 
-    for canton in cantons {
-        let body = serde_json::json!({
-            "name": format!("{} Farm", canton),
-            "address": "Test Address",
-            "canton": canton,
-            "coordinates": "47.3769,8.5417",
-            "products": ["strawberries"],
-            "idempotency_key": Uuid::new_v4(),
-        });
+    let fresh = app.post_farm(&body).await;
+    let replay = app.post_farm(&body).await;
 
-        let response = app.post_farm(&body).await;
-        assert_eq!(
-            StatusCode::CREATED.as_u16(),
-            response.status().as_u16(),
-            "Failed to create farm for canton {}",
-            canton
-        );
-    }
+    assert_eq!(fresh.status(), StatusCode::CREATED.as_u16());
+    assert_eq!(replay.status(), StatusCode::CREATED.as_u16());
+    assert_eq!("false", fresh.headers()["idempotency-replayed"]);
+    assert_eq!("true", replay.headers()["idempotency-replayed"]);
+    assert_eq!(
+        1,
+        replay
+            .headers()
+            .get_all("idempotency-replayed")
+            .iter()
+            .count()
+    );
 }
 
 #[tokio::test]
-async fn create_farm_called_multiple_times_sequentially_doesnt_create_duplicate_farms_in_db_redis()
-{
-    create_farm_called_multiple_times_sequentially_doesnt_create_duplicate_farms_in_db(
-        IdempotencyEngine::Redis,
+async fn create_farm_reusing_idempotency_key_with_a_different_body_returns_422_redis() {
+    create_farm_reusing_idempotency_key_with_a_different_body_returns_422(IdempotencyEngine::Redis)
+        .await;
+}
+
+#[tokio::test]
+async fn create_farm_reusing_idempotency_key_with_a_different_body_returns_422_postgres() {
+    create_farm_reusing_idempotency_key_with_a_different_body_returns_422(
+        IdempotencyEngine::Postgres,
     )
     .await;
 }
 
 #[tokio::test]
-async fn create_farm_called_multiple_times_sequentially_doesnt_create_duplicate_farms_in_db_postgres()
- {
-    create_farm_called_multiple_times_sequentially_doesnt_create_duplicate_farms_in_db(
-        IdempotencyEngine::Postgres,
+async fn create_farm_reusing_idempotency_key_with_a_different_body_returns_422_memory() {
+    create_farm_reusing_idempotency_key_with_a_different_body_returns_422(
+        IdempotencyEngine::Memory,
     )
     .await;
 }
 
-async fn create_farm_called_multiple_times_sequentially_doesnt_create_duplicate_farms_in_db(
+async fn create_farm_reusing_idempotency_key_with_a_different_body_returns_422(
     idempotency_engine: IdempotencyEngine,
 ) {
     let app = spawn_app(idempotency_engine).await;
     seed_test_taxonomy(&app.db_pool).await;
-    let farm = generate_farm();
     let idempotency_key = Uuid::new_v4();
 
     let user = TestUser::generate_user();
     log_in_test_user(&app, &user).await;
 
-    let body = farm_to_json(&farm, idempotency_key);
+    let first_body = generate_valid_farm().to_json(idempotency_key);
+    let second_body = generate_valid_farm().to_json(idempotency_key);
 
-    let response1 = app.post_farm(&body).await;
-    let response2 = app.post_farm(&body).await;
+    let response1 = app.post_farm(&first_body).await;
+    let replay = app.post_farm(&first_body).await;
+    let response2 = app.post_farm(&second_body).await;
 
     assert_eq!(response1.status(), StatusCode::CREATED.as_u16());
-    assert_eq!(response2.status(), StatusCode::CREATED.as_u16());
+    assert_eq!(replay.status(), StatusCode::CREATED.as_u16());
+    assert_eq!(
+        response2.status(),
+        StatusCode::UNPROCESSABLE_ENTITY.as_u16()
+    );
 
     let saved = sqlx::query!("SELECT id FROM farms")
         .fetch_all(&app.db_pool)
@@ -706,6 +2159,61 @@ async fn create_farm_called_multiple_times_sequentially_doesnt_create_duplicate_
     assert_eq!(saved.len(), 1);
 }
 
+#[tokio::test]
+async fn idempotency_key_length_is_checked_on_the_stored_key_redis() {
+    idempotency_key_length_is_checked_on_the_stored_key(IdempotencyEngine::Redis).await;
+}
+
+#[tokio::test]
+async fn idempotency_key_length_is_checked_on_the_stored_key_postgres() {
+    idempotency_key_length_is_checked_on_the_stored_key(IdempotencyEngine::Postgres).await;
+}
+
+#[tokio::test]
+async fn idempotency_key_length_is_checked_on_the_stored_key_memory() {
+    idempotency_key_length_is_checked_on_the_stored_key(IdempotencyEngine::Memory).await;
+}
+
+#[tokio::test]
+async fn idempotency_key_length_is_checked_on_the_stored_key_none() {
+    idempotency_key_length_is_checked_on_the_stored_key(IdempotencyEngine::None).await;
+}
+
+/// With a long API version, a 60-character key composes to exactly
+/// `MAX_STORED_KEY_LENGTH` as Redis stores it: accepted by every engine, and
+/// one character more is rejected by every engine.
+async fn idempotency_key_length_is_checked_on_the_stored_key(
+    idempotency_engine: IdempotencyEngine,
+) {
+    let app = spawn_app_with(idempotency_engine, |c| {
+        // `{prefix}:{user_id}:{api_version}:` leaves 60 bytes for the key.
+        let namespace = c.idempotency.redis_key_prefix.len() + 36 + 3;
+        c.idempotency.api_version = "v".repeat(MAX_STORED_KEY_LENGTH - namespace - 60);
+    })
+    .await;
+    seed_test_taxonomy(&app.db_pool).await;
+    let user = TestUser::generate_user();
+    log_in_test_user(&app, &user).await;
+
+    let mut at_limit = generate_valid_farm().to_json(Uuid::new_v4());
+    at_limit["idempotency_key"] = "a".repeat(60).into();
+    let mut past_limit = generate_valid_farm().to_json(Uuid::new_v4());
+    past_limit["idempotency_key"] = "b".repeat(61).into();
+
+    let accepted = app.post_farm(&at_limit).await;
+    let rejected = app.post_farm(&past_limit).await;
+
+    assert_eq!(accepted.status(), StatusCode::CREATED.as_u16());
+    assert_eq!(rejected.status(), StatusCode::BAD_REQUEST.as_u16());
+    assert!(
+        rejected
+            .text()
+            .await
+            .unwrap()
+            .contains("The idempotency key is too long")
+    );
+}
+
 #[tokio::test]
 async fn create_farm_called_multiple_times_in_parallel_doesnt_create_duplicate_farms_in_db_redis() {
     create_farm_called_multiple_times_in_parallel_doesnt_create_duplicate_farms_in_db(
@@ -723,18 +2231,27 @@ async fn create_farm_called_multiple_times_in_parallel_doesnt_create_duplicate_f
     .await;
 }
 
+#[tokio::test]
+async fn create_farm_called_multiple_times_in_parallel_doesnt_create_duplicate_farms_in_db_memory()
+{
+    create_farm_called_multiple_times_in_parallel_doesnt_create_duplicate_farms_in_db(
+        IdempotencyEngine::Memory,
+    )
+    .await;
+}
+
 async fn create_farm_called_multiple_times_in_parallel_doesnt_create_duplicate_farms_in_db(
     idempotency_engine: IdempotencyEngine,
 ) {
     let app = spawn_app(idempotency_engine).await;
     seed_test_taxonomy(&app.db_pool).await;
-    let farm = generate_farm();
+    let farm = generate_valid_farm();
     let idempotency_key = Uuid::new_v4();
 
     let user = TestUser::generate_user();
     log_in_test_user(&app, &user).await;
 
-    let body = farm_to_json(&farm, idempotency_key);
+    let body = farm.to_json(idempotency_key);
 
     let response1 = app.post_farm(&body);
     let response2 = app.post_farm(&body);
@@ -762,36 +2279,37 @@ async fn create_farm_called_multiple_times_in_parallel_doesnt_create_duplicate_f
 async fn create_farm_creates_redis_key_with_response() {
     let app = spawn_app(IdempotencyEngine::Redis).await;
     seed_test_taxonomy(&app.db_pool).await;
-    let farm = generate_farm();
+    let farm = generate_valid_farm();
     let idempotency_key = Uuid::new_v4();
 
     let user = TestUser::generate_user();
     log_in_test_user(&app, &user).await;
 
-    let body = farm_to_json(&farm, idempotency_key);
+    let body = farm.to_json(idempotency_key);
 
     let response = app.post_farm(&body).await;
 
     assert_eq!(StatusCode::CREATED.as_u16(), response.status().as_u16());
 
-    let idempotency_key = IdempotencyKey::try_from(format!(
-        "{}:{}:{}",
-        app.configuration.idempotency.redis_key_prefix, user.id, idempotency_key
-    ))
-    .expect("Failed to parse idempotency key");
+    let redis_key = format!(
+        "{}:{}:{}:{}",
+        app.configuration.idempotency.redis_key_prefix,
+        user.id,
+        app.configuration.idempotency.api_version,
+        idempotency_key
+    );
     let mut redis_connection = app
         .redis_pool
         .get()
         .await
         .expect("Failed to get redis connection");
 
-    let key_exists =
-        redis_exists_with_retry(&mut redis_connection, idempotency_key.as_ref(), 10, 100)
-            .await
-            .expect("Failed to check if key exists");
+    let key_exists = redis_exists_with_retry(&mut redis_connection, &redis_key, 10, 100)
+        .await
+        .expect("Failed to check if key exists");
     assert!(key_exists);
 
-    let bytes: Vec<u8> = AsyncCommands::get(&mut redis_connection, idempotency_key.as_ref())
+    let bytes: Vec<u8> = AsyncCommands::get(&mut redis_connection, &redis_key)
         .await
         .expect("Failed to retrieve idempotency saved response");
 
@@ -819,6 +2337,45 @@ async fn create_farm_creates_redis_key_with_response() {
     assert_eq!(data.response_body, response.bytes().await.unwrap().to_vec());
 }
 
+#[tokio::test]
+async fn create_farm_does_not_replay_a_response_saved_under_another_api_version() {
+    // Both apps share the same Redis, so only the version namespace keeps
+    // their saved responses apart.
+    let v1 = spawn_app_with(IdempotencyEngine::Redis, |c| {
+        c.idempotency.api_version = "v1".to_string();
+    })
+    .await;
+    let v2 = spawn_app_with(IdempotencyEngine::Redis, |c| {
+        c.idempotency.api_version = "v2".to_string();
+    })
+    .await;
+    seed_test_taxonomy(&v1.db_pool).await;
+    seed_test_taxonomy(&v2.db_pool).await;
+    let idempotency_key = Uuid::new_v4();
+
+    let user = TestUser::generate_user();
+    log_in_test_user(&v1, &user).await;
+    log_in_test_user(&v2, &user).await;
+
+    let v1_response = v1
+        .post_farm(&generate_valid_farm().to_json(idempotency_key))
+        .await;
+    // A different payload under the same key would be a 422 if replayed.
+    let v2_response = v2
+        .post_farm(&generate_valid_farm().to_json(idempotency_key))
+        .await;
+
+    assert_eq!(v1_response.status().as_u16(), StatusCode::CREATED.as_u16());
+    assert_eq!(v2_response.status().as_u16(), StatusCode::CREATED.as_u16());
+
+    let saved = sqlx::query!("SELECT id FROM farms")
+        .fetch_all(&v2.db_pool)
+        .await
+        .expect("Failed to fetch saved farms.");
+
+    assert_eq!(saved.len(), 1);
+}
+
 #[tokio::test]
 async fn idempotency_worker_will_not_delete_non_expired_keys() {
     let app = spawn_app(IdempotencyEngine::Postgres).await;
@@ -878,3 +2435,330 @@ async fn idempotency_worker_will_only_delete_expired_keys() {
     assert_eq!(idempotency_rows, non_expired_rows_to_create);
     assert_eq!(ExpiryOutcome::RowsDeleted(expired_rows_to_create), outcome);
 }
+
+#[tokio::test]
+async fn get_farms_returns_503_with_retry_after_when_the_db_pool_is_saturated() {
+    // A single connection that times out after a second.
+    let app = spawn_app_with(IdempotencyEngine::None, |c| {
+        c.database.max_connections = Some(1);
+        c.database.timeout_seconds = Some(1);
+        c.application.retry_after_seconds = Some(3);
+    })
+    .await;
+
+    // Block readers of `farms` so the first request pins the app's only connection.
+    let mut lock = app.db_pool.begin().await.unwrap();
+    sqlx::query!("LOCK TABLE farms IN ACCESS EXCLUSIVE MODE")
+        .execute(&mut *lock)
+        .await
+        .unwrap();
+
+    let pinned = tokio::spawn({
+        let client = app.api_client.clone();
+        let url = format!("{}/farms", app.address);
+        async move { client.get(url).send().await.unwrap().status() }
+    });
+    tokio::time::sleep(Duration::from_millis(300)).await;
+
+    let response = app.get_farms().await;
+
+    assert_eq!(
+        response.status().as_u16(),
+        StatusCode::SERVICE_UNAVAILABLE.as_u16()
+    );
+    let retry_after: u64 = response.headers()["Retry-After"]
+        .to_str()
+        .unwrap()
+        .parse()
+        .expect("Retry-After is not a number of seconds.");
+    assert!((3..=6).contains(&retry_after), "{retry_after}");
+
+    lock.commit().await.unwrap();
+    assert_eq!(pinned.await.unwrap().as_u16(), StatusCode::OK.as_u16());
+}
+
+#[tokio::test]
+async fn a_key_still_in_flight_is_answered_with_409_and_retry_after() {
+    let prefix = format!("idemtest:{}", Uuid::new_v4());
+    let app = spawn_app_with(IdempotencyEngine::Redis, |c| {
+        c.idempotency.redis_key_prefix = prefix.clone();
+        c.idempotency.api_version = "v1".to_string();
+    })
+    .await;
+    let user_id = app.log_in_active_user().await;
+    let idempotency_key = Uuid::new_v4();
+    // Claim the key the way a request still being processed holds it.
+    let mut connection = app.redis_pool.get().await.unwrap();
+    let _: () = connection
+        .set_ex(
+            format!("{prefix}:{user_id}:v1:{idempotency_key}"),
+            Vec::<u8>::new(),
+            600,
+        )
+        .await
+        .unwrap();
+
+    let response = app
+        .post_farm(&generate_valid_farm().to_json(idempotency_key))
+        .await;
+
+    assert_eq!(StatusCode::CONFLICT.as_u16(), response.status().as_u16());
+    let retry_after: u64 = response.headers()["Retry-After"]
+        .to_str()
+        .unwrap()
+        .parse()
+        .expect("Retry-After is not a number of seconds.");
+    // The claim has 600 seconds left; the request holding it is expected
+    // back well before that.
+    assert_eq!(IN_FLIGHT_RETRY_AFTER_SECONDS, retry_after);
+}
+
+#[tokio::test]
+async fn a_key_still_in_flight_in_postgres_is_answered_with_409_and_retry_after() {
+    let app = spawn_app_with(IdempotencyEngine::Postgres, |c| {
+        c.idempotency.api_version = "v1".to_string();
+        c.idempotency.in_flight_retry_after_seconds = Some(30);
+    })
+    .await;
+    let user_id = app.log_in_active_user().await;
+    let idempotency_key = Uuid::new_v4();
+    // A claim without a response, as a request still being processed holds it.
+    app.create_idempotency_row(
+        user_id,
+        format!("v1:{idempotency_key}"),
+        Utc::now().add(Duration::from_secs(600)),
+    )
+    .await;
+
+    let response = app
+        .post_farm(&generate_valid_farm().to_json(idempotency_key))
+        .await;
+
+    assert_eq!(StatusCode::CONFLICT.as_u16(), response.status().as_u16());
+    let retry_after: u64 = response.headers()["Retry-After"]
+        .to_str()
+        .unwrap()
+        .parse()
+        .expect("Retry-After is not a number of seconds.");
+    assert_eq!(30, retry_after);
+}
+
+#[tokio::test]
+async fn a_claim_about_to_expire_asks_for_no_more_than_it_has_left() {
+    let app = spawn_app_with(IdempotencyEngine::Postgres, |c| {
+        c.idempotency.api_version = "v1".to_string();
+        c.idempotency.in_flight_retry_after_seconds = Some(30);
+    })
+    .await;
+    let user_id = app.log_in_active_user().await;
+    let idempotency_key = Uuid::new_v4();
+    app.create_idempotency_row(
+        user_id,
+        format!("v1:{idempotency_key}"),
+        Utc::now().add(Duration::from_secs(5)),
+    )
+    .await;
+
+    let response = app
+        .post_farm(&generate_valid_farm().to_json(idempotency_key))
+        .await;
+
+    assert_eq!(StatusCode::CONFLICT.as_u16(), response.status().as_u16());
+    let retry_after: u64 = response.headers()["Retry-After"]
+        .to_str()
+        .unwrap()
+        .parse()
+        .expect("Retry-After is not a number of seconds.");
+    assert!((1..=5).contains(&retry_after), "{retry_after}");
+}
+
+#[tokio::test]
+async fn the_categories_schema_reflects_the_server_limits() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+
+    let response = app
+        .api_client
+        .get(format!("{}/farms/schema/categories", app.address))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert_eq!(StatusCode::OK.as_u16(), response.status().as_u16());
+    let schema: CategoriesSchema = response.json().await.unwrap();
+    assert_eq!(Categories::MAX_CATEGORIES, schema.max_items);
+    assert_eq!(
+        Categories::MAX_CATEGORY_NAME_LENGTH,
+        schema.items.max_name_bytes
+    );
+    assert_eq!(0, schema.min_items);
+    assert_eq!("array", schema.r#type);
+    assert_eq!("^[a-z0-9-]+$", schema.items.pattern);
+    assert!(schema.unique_items_case_insensitive);
+}
+
+#[tokio::test]
+async fn create_farm_holds_categories_to_the_published_schema() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    app.log_in_active_user().await;
+    let too_many: Vec<String> = (0..=Categories::MAX_CATEGORIES)
+        .map(|i| format!("category-{i}"))
+        .collect();
+    let mut body = generate_valid_farm().to_json(Uuid::new_v4());
+    body["categories"] = serde_json::json!(too_many);
+
+    let response = app.post_farm(&body).await;
+
+    assert_eq!(
+        StatusCode::UNPROCESSABLE_ENTITY.as_u16(),
+        response.status().as_u16()
+    );
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!("categories", body["field"], "{body}");
+}
+
+#[tokio::test]
+async fn the_categories_schema_reports_case_sensitive_uniqueness_when_configured() {
+    let app = spawn_app_with(IdempotencyEngine::None, |c| {
+        c.farms.categories_case_sensitive = true;
+    })
+    .await;
+
+    let response = app
+        .api_client
+        .get(format!("{}/farms/schema/categories", app.address))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    let schema: CategoriesSchema = response.json().await.unwrap();
+    assert!(!schema.unique_items_case_insensitive);
+}
+
+/// The status of `POST /farms` listing "Vegetables" and "vegetables", with
+/// `farms.categories_case_sensitive` set to `case_sensitive`.
+async fn create_with_case_variant_categories(case_sensitive: bool) -> u16 {
+    let app = spawn_app_with(IdempotencyEngine::None, |c| {
+        c.farms.categories_case_sensitive = case_sensitive;
+    })
+    .await;
+    app.log_in_active_user().await;
+    let mut body = generate_valid_farm().to_json(Uuid::new_v4());
+    body["categories"] = serde_json::json!(["Vegetables", "vegetables"]);
+
+    app.post_farm(&body).await.status().as_u16()
+}
+
+#[tokio::test]
+async fn case_variant_categories_are_duplicates_by_default() {
+    assert_eq!(
+        StatusCode::UNPROCESSABLE_ENTITY.as_u16(),
+        create_with_case_variant_categories(false).await
+    );
+}
+
+#[tokio::test]
+async fn case_variant_categories_are_accepted_when_case_sensitive() {
+    assert_eq!(
+        StatusCode::CREATED.as_u16(),
+        create_with_case_variant_categories(true).await
+    );
+}
+
+/// Appends an `update` audit entry for `farm_id`, `seconds` after now. There
+/// is no update endpoint yet, so history tests write these directly.
+async fn record_update(app: &TestApp, farm_id: Uuid, user_id: Uuid, name: &str, seconds: f64) {
+    sqlx::query!(
+        r#"
+        INSERT INTO audit_log (id, action, farm_id, user_id, payload, created_at)
+        VALUES ($1, 'update', $2, $3, $4, now() + make_interval(secs => $5))
+        "#,
+        Uuid::new_v4(),
+        farm_id,
+        user_id,
+        serde_json::json!({ "name": name }),
+        seconds,
+    )
+    .execute(&app.db_pool)
+    .await
+    .expect("Failed to record an update.");
+}
+
+async fn get_history(app: &TestApp, farm_id: Uuid, query: &str) -> reqwest::Response {
+    app.api_client
+        .get(format!("{}/farms/{farm_id}/history{query}", app.address))
+        .send()
+        .await
+        .expect("Failed to execute request.")
+}
+
+#[tokio::test]
+async fn farm_history_lists_every_change_in_order() {
+    // Arrange
+    let app = spawn_app(IdempotencyEngine::None).await;
+    let user_id = app.log_in_active_user().await;
+    let farm = generate_valid_farm();
+    let response = app.post_farm(&farm.to_json(Uuid::new_v4())).await;
+    let created: serde_json::Value = response.json().await.unwrap();
+    let farm_id: Uuid = created["id"].as_str().unwrap().parse().unwrap();
+    record_update(&app, farm_id, user_id, "Renamed once", 1.0).await;
+    record_update(&app, farm_id, user_id, "Renamed twice", 2.0).await;
+
+    // Act
+    let response = get_history(&app, farm_id, "").await;
+
+    // Assert
+    assert_eq!(StatusCode::OK.as_u16(), response.status().as_u16());
+    let history: serde_json::Value = response.json().await.unwrap();
+    let entries = history["entries"].as_array().unwrap();
+    let actions: Vec<&str> = entries
+        .iter()
+        .map(|entry| entry["action"].as_str().unwrap())
+        .collect();
+    assert_eq!(vec!["create", "update", "update"], actions);
+    assert_eq!(farm.name.as_ref(), entries[0]["payload"]["name"]);
+    assert!(entries[0]["payload"].get("idempotency_key").is_none());
+    assert_eq!("Renamed twice", entries[2]["payload"]["name"]);
+    assert!(history["next_cursor"].is_null());
+
+    let page: serde_json::Value = get_history(&app, farm_id, "?limit=2")
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(2, page["entries"].as_array().unwrap().len());
+    assert_eq!("2", page["next_cursor"]);
+    let last: serde_json::Value = get_history(&app, farm_id, "?limit=2&offset=2")
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!("Renamed twice", last["entries"][0]["payload"]["name"]);
+}
+
+#[tokio::test]
+async fn farm_history_is_404_for_a_farm_that_never_existed() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    app.log_in_active_user().await;
+    let written_directly = insert_test_farm(&app.db_pool, "Farm A").await;
+
+    let unknown = get_history(&app, Uuid::new_v4(), "").await;
+    let without_audit = get_history(&app, written_directly, "").await;
+
+    assert_eq!(StatusCode::NOT_FOUND.as_u16(), unknown.status().as_u16());
+    assert_eq!(StatusCode::OK.as_u16(), without_audit.status().as_u16());
+    let history: serde_json::Value = without_audit.json().await.unwrap();
+    assert_eq!(serde_json::json!([]), history["entries"]);
+}
+
+#[tokio::test]
+async fn farm_history_requires_a_login() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    let farm_id = insert_test_farm(&app.db_pool, "Farm A").await;
+
+    let response = get_history(&app, farm_id, "").await;
+
+    assert_eq!(
+        StatusCode::UNAUTHORIZED.as_u16(),
+        response.status().as_u16()
+    );
+}
@@ -0,0 +1,92 @@
+use crate::helpers::{spawn_app, spawn_app_with};
+use farms::{configuration::IdempotencyEngine, domain::farm::SwissBoundary};
+use serde_json::{Value, json};
+
+#[tokio::test]
+async fn validate_accepts_a_point_in_switzerland() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+
+    let response = app
+        .api_client
+        .post(format!("{}/geo/validate", &app.address))
+        .json(&json!({"coordinates": "47.3769, 8.5417"}))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert_eq!(200, response.status().as_u16());
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body, json!({"valid": true, "nearest_capital_canton": "ZH"}));
+}
+
+#[tokio::test]
+async fn validate_rejects_a_point_outside_switzerland() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+
+    // Paris
+    let response = app
+        .api_client
+        .post(format!("{}/geo/validate", &app.address))
+        .json(&json!({"coordinates": "48.8566,2.3522"}))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert_eq!(400, response.status().as_u16());
+    assert_eq!(
+        "Coordinates not within Switzerland boundaries.",
+        response.text().await.unwrap()
+    );
+}
+
+#[tokio::test]
+async fn validate_rejects_malformed_coordinates() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    let test_cases = vec![
+        ("47.3769", "Invalid coordinate format"),
+        ("north,east", "Invalid coordinate format"),
+        ("95.0,8.5", "Invalid latitude"),
+    ];
+
+    for (coordinates, reason) in test_cases {
+        let response = app
+            .api_client
+            .post(format!("{}/geo/validate", &app.address))
+            .json(&json!({ "coordinates": coordinates }))
+            .send()
+            .await
+            .expect("Failed to execute request.");
+
+        assert_eq!(
+            400,
+            response.status().as_u16(),
+            "The API did not reject {coordinates}."
+        );
+        let body = response.text().await.unwrap();
+        assert!(body.starts_with(reason), "{coordinates}: {body}");
+    }
+}
+
+#[tokio::test]
+async fn validate_rejects_a_point_outside_the_border_polygon_only_when_configured() {
+    let bounding_box = spawn_app(IdempotencyEngine::None).await;
+    let polygon = spawn_app_with(IdempotencyEngine::None, |c| {
+        c.farms.swiss_boundary = SwissBoundary::Polygon
+    })
+    .await;
+
+    // Friedrichshafen: inside the bounding box, across Lake Constance.
+    let mut statuses = Vec::new();
+    for app in [&bounding_box, &polygon] {
+        let response = app
+            .api_client
+            .post(format!("{}/geo/validate", &app.address))
+            .json(&json!({"coordinates": "47.65,9.48"}))
+            .send()
+            .await
+            .expect("Failed to execute request.");
+        statuses.push(response.status().as_u16());
+    }
+
+    assert_eq!(vec![200, 400], statuses);
+}
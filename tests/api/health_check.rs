@@ -18,3 +18,61 @@ async fn health_check() {
     assert!(response.status().is_success());
     assert_eq!(Some(0), response.content_length());
 }
+
+#[tokio::test]
+async fn metrics_exposes_pool_gauges() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+
+    let response = app
+        .api_client
+        .get(format!("{}/metrics", &app.address))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert!(response.status().is_success());
+    assert!(
+        response.headers()["content-type"]
+            .to_str()
+            .unwrap()
+            .starts_with("text/plain")
+    );
+    let body = response.text().await.unwrap();
+    let gauge = |sample: &str| -> u64 {
+        body.lines()
+            .find_map(|line| line.strip_prefix(sample)?.trim().parse().ok())
+            .unwrap_or_else(|| panic!("Missing metric sample {sample}"))
+    };
+
+    // spawn_app caps the Postgres pool at 2 connections.
+    let db_max = gauge("farms_db_pool_max_connections ");
+    assert_eq!(db_max, 2);
+    let db_in_use = gauge("farms_db_pool_connections{state=\"idle\"} ")
+        + gauge("farms_db_pool_connections{state=\"active\"} ");
+    assert!(db_in_use <= db_max);
+
+    let redis_max = gauge("farms_redis_pool_max_connections ");
+    assert!(redis_max > 0);
+    let redis_in_use = gauge("farms_redis_pool_connections{state=\"idle\"} ")
+        + gauge("farms_redis_pool_connections{state=\"active\"} ");
+    assert!(redis_in_use <= redis_max);
+    gauge("farms_redis_pool_waiting ");
+}
+
+#[tokio::test]
+async fn version_reports_the_running_build() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+
+    let response = app
+        .api_client
+        .get(format!("{}/version", &app.address))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert_eq!(200, response.status().as_u16());
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(env!("CARGO_PKG_VERSION"), body["version"]);
+    assert!(!body["commit"].as_str().unwrap().is_empty());
+    assert!(body["uptime_seconds"].is_u64());
+}
@@ -0,0 +1,219 @@
+use crate::helpers::{spawn_app, spawn_app_with};
+use actix_web::HttpResponse;
+use farms::{
+    configuration::{IdempotencyEngine, IdempotencySettings, IdempotentEndpoint},
+    db::StatementCounter,
+    idempotency::{IdempotencyNextAction, MemoryIdempotencyStore, save_response, try_processing},
+    test_util::generate_valid_farm,
+};
+use std::{
+    fmt::Debug,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tracing::{
+    Subscriber,
+    field::{Field, Visit},
+    span::{Attributes, Id},
+};
+use tracing_subscriber::{Layer, layer::Context, layer::SubscriberExt, registry::Registry};
+use uuid::Uuid;
+
+/// A span as it was opened: its name and its fields rendered as text.
+#[derive(Debug)]
+struct RecordedSpan {
+    name: &'static str,
+    fields: Vec<(String, String)>,
+}
+
+/// Records every span opened while it is the default subscriber.
+#[derive(Clone, Default)]
+struct SpanRecorder(Arc<Mutex<Vec<RecordedSpan>>>);
+
+impl<S: Subscriber> Layer<S> for SpanRecorder {
+    fn on_new_span(&self, attributes: &Attributes<'_>, _id: &Id, _context: Context<'_, S>) {
+        let mut fields = FieldRecorder::default();
+        attributes.record(&mut fields);
+        self.0.lock().unwrap().push(RecordedSpan {
+            name: attributes.metadata().name(),
+            fields: fields.0,
+        });
+    }
+}
+
+#[derive(Default)]
+struct FieldRecorder(Vec<(String, String)>);
+
+impl Visit for FieldRecorder {
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        self.0.push((
+            field.name().to_string(),
+            format!("{value:?}").replace('"', ""),
+        ));
+    }
+}
+
+impl SpanRecorder {
+    fn field(&self, span: &str, field: &str) -> Option<String> {
+        self.0
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|recorded| recorded.name == span)
+            .flat_map(|recorded| recorded.fields.iter())
+            .find(|(name, _)| name == field)
+            .map(|(_, value)| value.clone())
+    }
+}
+
+/// Claims a fresh key and saves a response under it with `settings`, the way
+/// `POST /farms` does, while `recorder` collects the spans.
+async fn claim_and_save(
+    app: &crate::helpers::TestApp,
+    settings: &IdempotencySettings,
+    recorder: &SpanRecorder,
+) -> String {
+    let user_id = app.log_in_active_user().await;
+    let key = Uuid::new_v4().to_string();
+    let memory_store = MemoryIdempotencyStore::new(Duration::from_secs(60));
+    let _guard = tracing::subscriber::set_default(Registry::default().with(recorder.clone()));
+
+    let statements = StatementCounter::default();
+    let mut transaction = app.db_pool.begin().await.unwrap();
+    let IdempotencyNextAction::StartProcessing = try_processing(
+        &app.redis_pool,
+        &memory_store,
+        &statements,
+        &mut transaction,
+        &key,
+        user_id,
+        "hash",
+        settings,
+    )
+    .await
+    .unwrap() else {
+        panic!("A fresh key was not claimed.");
+    };
+    save_response(
+        &app.redis_pool,
+        &memory_store,
+        &statements,
+        &mut transaction,
+        &key,
+        user_id,
+        "hash",
+        settings,
+        HttpResponse::Created().body("created"),
+    )
+    .await
+    .unwrap();
+    transaction.commit().await.unwrap();
+
+    key
+}
+
+#[tokio::test]
+async fn redis_idempotency_operations_are_traced_without_the_key() {
+    let mut settings = None;
+    let app = spawn_app_with(IdempotencyEngine::Redis, |c| {
+        c.idempotency.redis_key_prefix = format!("idemtest:{}", Uuid::new_v4());
+        settings = Some(c.idempotency.clone());
+    })
+    .await;
+    let settings = settings.unwrap();
+    let recorder = SpanRecorder::default();
+
+    let key = claim_and_save(&app, &settings, &recorder).await;
+
+    assert_eq!(
+        Some("redis".to_string()),
+        recorder.field("Claim idempotency key", "engine")
+    );
+    assert_eq!(
+        Some("redis".to_string()),
+        recorder.field("Save idempotency response", "engine")
+    );
+    for span in [
+        "Claim idempotency key in Redis",
+        "Save idempotency response in Redis",
+    ] {
+        assert_eq!(
+            Some(settings.redis_key_prefix.clone()),
+            recorder.field(span, "key_prefix"),
+            "{span}"
+        );
+    }
+    let spans = recorder.0.lock().unwrap();
+    assert!(
+        spans
+            .iter()
+            .flat_map(|span| span.fields.iter())
+            .all(|(_, value)| !value.contains(&key)),
+        "{spans:?}"
+    );
+}
+
+#[tokio::test]
+async fn postgres_idempotency_operations_are_traced() {
+    let mut settings = None;
+    let app = spawn_app_with(IdempotencyEngine::Postgres, |c| {
+        settings = Some(c.idempotency.clone());
+    })
+    .await;
+    let settings = settings.unwrap();
+    let recorder = SpanRecorder::default();
+
+    claim_and_save(&app, &settings, &recorder).await;
+
+    for span in [
+        "Claim idempotency key",
+        "Claim idempotency key in Postgres",
+        "Save idempotency response",
+        "Save idempotency response in Postgres",
+    ] {
+        assert_eq!(
+            Some("postgres".to_string()),
+            recorder.field(span, "engine"),
+            "{span}"
+        );
+    }
+}
+
+/// A valid `POST /farms` body without an `idempotency_key`.
+fn farm_without_key() -> serde_json::Value {
+    let mut body = generate_valid_farm().to_json(Uuid::new_v4());
+    body.as_object_mut().unwrap().remove("idempotency_key");
+    body
+}
+
+#[tokio::test]
+async fn an_exempt_endpoint_processes_requests_without_a_key() {
+    let app = spawn_app_with(IdempotencyEngine::Memory, |c| {
+        c.idempotency.exempt_endpoints = vec![IdempotentEndpoint::CreateFarm];
+    })
+    .await;
+    app.log_in_active_user().await;
+    let body = farm_without_key();
+
+    let first = app.post_farm(&body).await;
+    let second = app.post_farm(&body).await;
+
+    assert_eq!(201, first.status().as_u16());
+    assert_eq!(201, second.status().as_u16());
+    assert!(!first.headers().contains_key("idempotency-replayed"));
+    let first: serde_json::Value = first.json().await.unwrap();
+    let second: serde_json::Value = second.json().await.unwrap();
+    assert_ne!(first["id"], second["id"]);
+}
+
+#[tokio::test]
+async fn an_enforcing_endpoint_requires_a_key() {
+    let app = spawn_app(IdempotencyEngine::Memory).await;
+    app.log_in_active_user().await;
+
+    let response = app.post_farm(&farm_without_key()).await;
+
+    assert_eq!(400, response.status().as_u16());
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!("Missing field 'idempotency_key'.", body["message"]);
+}
@@ -0,0 +1,190 @@
+use crate::helpers::{TestApp, spawn_app};
+use actix_web::http::StatusCode;
+use farms::configuration::IdempotencyEngine;
+
+const HEADER: &str = "name,address,canton,coordinates,categories,products,certifications";
+
+async fn post_import(app: &TestApp, csv: &str, query: &str) -> reqwest::Response {
+    app.api_client
+        .post(format!("{}/farms/import{}", app.address, query))
+        .header("Content-Type", "text/csv")
+        .body(csv.to_string())
+        .send()
+        .await
+        .expect("Failed to execute request.")
+}
+
+async fn farm_count(app: &TestApp) -> i64 {
+    sqlx::query_scalar!(r#"SELECT COUNT(*) AS "count!" FROM farms"#)
+        .fetch_one(&app.db_pool)
+        .await
+        .expect("Failed to count farms.")
+}
+
+#[tokio::test]
+async fn a_clean_csv_imports_every_row() {
+    // Arrange
+    let app = spawn_app(IdempotencyEngine::None).await;
+    app.log_in_active_user().await;
+    let csv = format!(
+        "{HEADER}\n\
+        Hofladen Binzenhof,\"Landhausweg 19, 5000 Aarau\",AG,\"47.3925,8.0457\",fruits,strawberries;cherries,bio_suisse\n\
+        Bergmilch,Dorfstrasse 12,ZH,\"47.3769,8.5417\",,broccoli,\n"
+    );
+
+    // Act
+    let response = post_import(&app, &csv, "").await;
+
+    // Assert
+    assert_eq!(response.status().as_u16(), StatusCode::OK.as_u16());
+    let report: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(report["imported"], 2);
+    assert_eq!(report["rejected"], 0);
+    assert_eq!(report["rows"][0]["row"], 2);
+    assert_eq!(report["rows"][1]["row"], 3);
+    assert_eq!(report["rows"][1]["status"], "imported");
+    assert_eq!(farm_count(&app).await, 2);
+
+    let id = report["rows"][0]["id"].as_str().unwrap().parse().unwrap();
+    let farm: serde_json::Value = app.get_farm(id).await.json().await.unwrap();
+    assert_eq!(farm["name"], "Hofladen Binzenhof");
+    assert_eq!(farm["postal_code"], 5000);
+    assert_eq!(farm["certifications"], serde_json::json!(["bio_suisse"]));
+}
+
+#[tokio::test]
+async fn the_csv_content_type_is_matched_case_insensitively() {
+    // Arrange
+    let app = spawn_app(IdempotencyEngine::None).await;
+    app.log_in_active_user().await;
+    let csv = format!(
+        "{HEADER}\n\
+        Bergmilch,Dorfstrasse 12,ZH,\"47.3769,8.5417\",,broccoli,\n"
+    );
+
+    // Act
+    let response = app
+        .api_client
+        .post(format!("{}/farms/import", app.address))
+        .header("Content-Type", "Text/CSV; charset=utf-8")
+        .body(csv)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    // Assert
+    assert_eq!(response.status().as_u16(), StatusCode::OK.as_u16());
+    assert_eq!(farm_count(&app).await, 1);
+}
+
+#[tokio::test]
+async fn a_row_with_a_bad_coordinate_is_reported_and_the_rest_imported() {
+    // Arrange
+    let app = spawn_app(IdempotencyEngine::None).await;
+    app.log_in_active_user().await;
+    let csv = format!(
+        "{HEADER}\n\
+        Hofladen Binzenhof,Landhausweg 19,AG,\"47.3925,8.0457\",fruits,,\n\
+        Nirgendwohof,Dorfstrasse 1,ZH,\"91.0,8.5\",fruits,,\n\
+        Bergmilch,Dorfstrasse 12,ZH,\"47.3769,8.5417\",vegetables,,\n"
+    );
+
+    // Act
+    let response = post_import(&app, &csv, "").await;
+
+    // Assert
+    assert_eq!(response.status().as_u16(), StatusCode::OK.as_u16());
+    let report: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(report["imported"], 2);
+    assert_eq!(report["rejected"], 1);
+    let rejected = &report["rows"][1];
+    assert_eq!(rejected["status"], "rejected");
+    assert_eq!(rejected["row"], 3);
+    assert!(!rejected["reason"].as_str().unwrap().is_empty());
+    assert_eq!(farm_count(&app).await, 2);
+}
+
+#[tokio::test]
+async fn an_all_or_nothing_import_with_a_bad_row_imports_nothing() {
+    // Arrange
+    let app = spawn_app(IdempotencyEngine::None).await;
+    app.log_in_active_user().await;
+    let csv = format!(
+        "{HEADER}\n\
+        Hofladen Binzenhof,Landhausweg 19,AG,\"47.3925,8.0457\",fruits,,\n\
+        Nirgendwohof,Dorfstrasse 1,ZH,not-a-coordinate,fruits,,\n"
+    );
+
+    // Act
+    let response = post_import(&app, &csv, "?all_or_nothing=true").await;
+
+    // Assert
+    assert_eq!(response.status().as_u16(), StatusCode::BAD_REQUEST.as_u16());
+    let report: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(report["error_code"], "VALIDATION_ERROR");
+    assert!(report["request_id"].is_string());
+    assert_eq!(report["imported"], 0);
+    assert_eq!(report["rejected"], 1);
+    assert_eq!(report["rows"][0]["status"], "skipped");
+    assert_eq!(report["rows"][1]["status"], "rejected");
+    assert_eq!(farm_count(&app).await, 0);
+}
+
+#[tokio::test]
+async fn a_row_missing_a_column_is_rejected() {
+    // Arrange
+    let app = spawn_app(IdempotencyEngine::None).await;
+    app.log_in_active_user().await;
+    let csv = format!(
+        "{HEADER}\n\
+        Kurzhof,Dorfstrasse 1\n\
+        Bergmilch,Dorfstrasse 12,ZH,\"47.3769,8.5417\",vegetables,,\n"
+    );
+
+    // Act
+    let response = post_import(&app, &csv, "").await;
+
+    // Assert
+    let report: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(report["rows"][0]["status"], "rejected");
+    assert_eq!(report["rows"][0]["row"], 2);
+    assert_eq!(report["rows"][1]["status"], "imported");
+}
+
+#[tokio::test]
+async fn an_import_without_rows_or_as_another_type_is_rejected() {
+    // Arrange
+    let app = spawn_app(IdempotencyEngine::None).await;
+    app.log_in_active_user().await;
+
+    // Act
+    let empty = post_import(&app, HEADER, "").await;
+    let json = app
+        .api_client
+        .post(format!("{}/farms/import", app.address))
+        .json(&serde_json::json!([]))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    // Assert
+    assert_eq!(empty.status().as_u16(), StatusCode::BAD_REQUEST.as_u16());
+    assert_eq!(json.status().as_u16(), StatusCode::BAD_REQUEST.as_u16());
+}
+
+#[tokio::test]
+async fn an_anonymous_import_is_rejected() {
+    // Arrange
+    let app = spawn_app(IdempotencyEngine::None).await;
+    let csv = format!("{HEADER}\nBergmilch,Dorfstrasse 12,ZH,\"47.3769,8.5417\",vegetables,,\n");
+
+    // Act
+    let response = post_import(&app, &csv, "").await;
+
+    // Assert
+    assert_eq!(
+        response.status().as_u16(),
+        StatusCode::UNAUTHORIZED.as_u16()
+    );
+    assert_eq!(farm_count(&app).await, 0);
+}
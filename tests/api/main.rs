@@ -2,11 +2,20 @@
 mod helpers;
 
 mod authentication;
+mod concurrency;
+mod configuration;
+mod coordinates;
 mod directory;
+mod export;
 mod farms;
+mod geo;
 mod health_check;
+mod idempotency;
+mod import;
 mod me;
 mod moderation;
 mod products;
 mod registration;
+mod stream;
 mod suggestions;
+mod transactions;
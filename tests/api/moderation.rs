@@ -1,4 +1,4 @@
-use crate::helpers::{insert_test_farm, seed_test_taxonomy, spawn_app};
+use crate::helpers::{insert_test_farm, seed_test_taxonomy, spawn_app, spawn_app_with};
 use actix_web::http::StatusCode;
 use farms::configuration::IdempotencyEngine;
 use uuid::Uuid;
@@ -172,3 +172,55 @@ async fn rejecting_leaves_no_link_and_conflicts_on_second_review() {
         .unwrap();
     assert_eq!(StatusCode::CONFLICT.as_u16(), second.status().as_u16());
 }
+
+#[tokio::test]
+async fn approving_a_suggestion_invalidates_the_listing_cache() {
+    let app = spawn_app_with(IdempotencyEngine::None, |c| {
+        c.farms.list_cache.enabled = true;
+        c.farms.list_cache.key_prefix = format!("farms-list-test:{}", Uuid::new_v4());
+    })
+    .await;
+    let taxonomy = seed_test_taxonomy(&app.db_pool).await;
+    let farm_id = insert_test_farm(&app.db_pool, "Farm").await;
+    let submitter = app.log_in_admin_user().await;
+    let suggestion_id = Uuid::new_v4();
+    sqlx::query!(
+        r#"
+        INSERT INTO farm_product_suggestions
+            (id, farm_id, product_id, action, note, submitted_by, status, created_at)
+        VALUES ($1, $2, $3, 'ADD', NULL, $4, 'PENDING', now())
+        "#,
+        suggestion_id,
+        farm_id,
+        taxonomy.strawberries_id,
+        submitter,
+    )
+    .execute(&app.db_pool)
+    .await
+    .unwrap();
+    let list = async || {
+        let response = app.get_farms().await;
+        let cache = response.headers()["x-cache"].to_str().unwrap().to_string();
+        let body: serde_json::Value = response.json().await.unwrap();
+        (cache, body["farms"][0]["products"].clone())
+    };
+    list().await;
+    let (cached, before) = list().await;
+
+    let response = app
+        .api_client
+        .post(format!(
+            "{}/admin/product-suggestions/{}/approve",
+            app.address, suggestion_id
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::OK.as_u16(), response.status().as_u16());
+    let (after_cache, after) = list().await;
+
+    assert_eq!("HIT", cached);
+    assert_eq!(serde_json::json!([]), before);
+    assert_eq!("MISS", after_cache);
+    assert_eq!("strawberries", after[0]["slug"], "{after}");
+}
@@ -1,4 +1,6 @@
-use crate::helpers::{insert_test_farm, link_farm_product, seed_test_taxonomy, spawn_app};
+use crate::helpers::{
+    insert_test_farm, link_farm_category, link_farm_product, seed_test_taxonomy, spawn_app,
+};
 use actix_web::http::StatusCode;
 use farms::configuration::IdempotencyEngine;
 
@@ -84,6 +86,43 @@ async fn match_all_requires_every_product() {
     assert_eq!(both.to_string(), farms[0]["id"].as_str().unwrap());
 }
 
+#[tokio::test]
+async fn match_all_requires_every_category() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    let taxonomy = seed_test_taxonomy(&app.db_pool).await;
+
+    // One group directly, the other via a product in it.
+    let both = insert_test_farm(&app.db_pool, "Both").await;
+    link_farm_category(&app.db_pool, both, taxonomy.fruits_category_id).await;
+    link_farm_product(&app.db_pool, both, taxonomy.broccoli_id).await;
+
+    let fruits_only = insert_test_farm(&app.db_pool, "Fruits").await;
+    link_farm_product(&app.db_pool, fruits_only, taxonomy.strawberries_id).await;
+    link_farm_product(&app.db_pool, fruits_only, taxonomy.cherries_id).await;
+
+    let url = |query: &str| format!("{}/farms?{query}", app.address);
+    let all = farms_array(
+        app.api_client
+            .get(url("category=fruits,vegetables&match=all"))
+            .send()
+            .await
+            .unwrap(),
+    )
+    .await;
+    let any = farms_array(
+        app.api_client
+            .get(url("category=fruits,vegetables"))
+            .send()
+            .await
+            .unwrap(),
+    )
+    .await;
+
+    assert_eq!(1, all.len());
+    assert_eq!(both.to_string(), all[0]["id"].as_str().unwrap());
+    assert_eq!(2, any.len());
+}
+
 #[tokio::test]
 async fn match_all_deduplicates_repeated_product_slugs() {
     // A repeated slug must not inflate the "all of" target: `?product=x,x`
@@ -0,0 +1,90 @@
+use crate::helpers::spawn_app;
+use actix_web::http::StatusCode;
+use farms::configuration::IdempotencyEngine;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// Read the stream until `received` satisfies `extract`, failing after a few
+/// seconds.
+async fn read_stream_until<T>(
+    response: &mut reqwest::Response,
+    extract: impl Fn(&str) -> Option<T>,
+) -> T {
+    let mut received = String::new();
+    let result = tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            if let Some(value) = extract(&received) {
+                return value;
+            }
+            let chunk = response
+                .chunk()
+                .await
+                .expect("Failed to read stream chunk.")
+                .expect("Stream ended unexpectedly.");
+            received.push_str(&String::from_utf8_lossy(&chunk));
+        }
+    })
+    .await;
+    result.expect("Timed out waiting for the stream.")
+}
+
+/// The JSON payload of the first complete `event: farm` frame, if any.
+fn farm_event_data(received: &str) -> Option<serde_json::Value> {
+    let start = received.find("event: farm\n")?;
+    let frame = &received[start..];
+    let frame = &frame[..frame.find("\n\n")?];
+    let data = frame.lines().find_map(|line| line.strip_prefix("data: "))?;
+    Some(serde_json::from_str(data).expect("Event data is not JSON."))
+}
+
+async fn open_stream(app: &crate::helpers::TestApp) -> reqwest::Response {
+    app.api_client
+        .get(format!("{}/farms/stream", app.address))
+        .send()
+        .await
+        .expect("Failed to execute request.")
+}
+
+#[tokio::test]
+async fn farm_stream_responds_with_an_event_stream() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+
+    let mut response = open_stream(&app).await;
+
+    assert_eq!(StatusCode::OK.as_u16(), response.status().as_u16());
+    assert_eq!(
+        "text/event-stream",
+        response.headers()["content-type"].to_str().unwrap()
+    );
+    read_stream_until(&mut response, |r| r.contains(": connected").then_some(())).await;
+}
+
+#[tokio::test]
+async fn farm_stream_receives_newly_created_farms() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    app.log_in_active_user().await;
+
+    let mut stream = open_stream(&app).await;
+    read_stream_until(&mut stream, |r| r.contains(": connected").then_some(())).await;
+
+    let response = app
+        .post_farm(&serde_json::json!({
+            "name": "Hof Sonnenberg",
+            "address": "Bahnhofstrasse 1, 8001 Zürich",
+            "canton": "ZH",
+            "coordinates": "47.37,8.54",
+            "products": ["strawberries"],
+            "idempotency_key": Uuid::new_v4().to_string(),
+        }))
+        .await;
+    assert_eq!(StatusCode::CREATED.as_u16(), response.status().as_u16());
+    let created: serde_json::Value = response.json().await.unwrap();
+
+    let farm = read_stream_until(&mut stream, farm_event_data).await;
+
+    assert!(created["id"].is_string());
+    assert_eq!(created["id"], farm["id"]);
+    assert_eq!("Hof Sonnenberg", farm["name"].as_str().unwrap());
+    assert_eq!("ZH", farm["canton"].as_str().unwrap());
+    assert_eq!(8001, farm["postal_code"].as_i64().unwrap());
+}
@@ -0,0 +1,105 @@
+use crate::helpers::spawn_app;
+use anyhow::Context;
+use farms::{
+    configuration::IdempotencyEngine, db::retry_serialization_failures, routes::farms::FarmError,
+};
+use sqlx::{Executor, PgPool};
+
+/// A one-row table both the transaction under test and its rival update.
+async fn create_counter(pool: &PgPool) {
+    pool.execute(
+        "CREATE TABLE retry_counter (id INT PRIMARY KEY, value INT NOT NULL);
+         INSERT INTO retry_counter VALUES (1, 0);",
+    )
+    .await
+    .unwrap();
+}
+
+async fn counter_value(pool: &PgPool) -> i32 {
+    sqlx::query_scalar("SELECT value FROM retry_counter WHERE id = 1")
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+/// Adds 1 to the counter in a `SERIALIZABLE` transaction. With `race`, a
+/// rival transaction adds 100 and commits after this one took its snapshot,
+/// so the update fails with a genuine serialization failure (40001).
+async fn increment_serializable(pool: &PgPool, race: bool) -> Result<(), FarmError> {
+    let mut transaction = pool.begin().await.context("Failed to begin.")?;
+    transaction
+        .execute("SET TRANSACTION ISOLATION LEVEL SERIALIZABLE")
+        .await
+        .context("Failed to set the isolation level.")?;
+    // The snapshot is taken by the first query.
+    transaction
+        .execute("SELECT value FROM retry_counter WHERE id = 1")
+        .await
+        .context("Failed to read the counter.")?;
+    if race {
+        pool.execute("UPDATE retry_counter SET value = value + 100 WHERE id = 1")
+            .await
+            .context("Failed to run the rival update.")?;
+    }
+    transaction
+        .execute("UPDATE retry_counter SET value = value + 1 WHERE id = 1")
+        .await
+        .context("Failed to increment the counter.")?;
+    transaction.commit().await.context("Failed to commit.")?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn a_serialization_failure_is_retried_as_a_new_transaction() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    create_counter(&app.db_pool).await;
+
+    let mut attempts = 0;
+    retry_serialization_failures(3, async || {
+        attempts += 1;
+        increment_serializable(&app.db_pool, attempts == 1).await
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(2, attempts);
+    // The rival's 100 and the retried increment; the failed attempt left nothing.
+    assert_eq!(101, counter_value(&app.db_pool).await);
+}
+
+#[tokio::test]
+async fn retries_stop_after_the_last_attempt() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    create_counter(&app.db_pool).await;
+
+    let mut attempts = 0;
+    let result = retry_serialization_failures(3, async || {
+        attempts += 1;
+        increment_serializable(&app.db_pool, true).await
+    })
+    .await;
+
+    assert!(result.is_err());
+    assert_eq!(3, attempts);
+    assert_eq!(300, counter_value(&app.db_pool).await);
+}
+
+#[tokio::test]
+async fn other_errors_are_not_retried() {
+    let app = spawn_app(IdempotencyEngine::None).await;
+    create_counter(&app.db_pool).await;
+
+    let mut attempts = 0;
+    let result = retry_serialization_failures(3, async || {
+        attempts += 1;
+        app.db_pool
+            .execute("INSERT INTO retry_counter VALUES (1, 0)")
+            .await
+            .context("Failed to insert a duplicate counter.")?;
+        Ok::<_, FarmError>(())
+    })
+    .await;
+
+    assert!(result.is_err());
+    assert_eq!(1, attempts);
+}
@@ -10,14 +10,19 @@ use farms::{
         DatabaseSettings, EmailClientEngine, IdempotencyEngine, LogFormat, LoggingLevel,
         LoggingSettings, Settings, TelemetrySettings, get_configuration,
     },
-    domain::user::Role,
+    domain::{
+        farm::{Address, Canton, Name, Point},
+        user::Role,
+    },
     startup::{Application, get_connection_pool, get_redis_connection_pool},
     telemetry::init_telemetry,
+    test_util::{TestFarm, generate_valid_farm},
 };
 use once_cell::sync::Lazy;
 use secrecy::SecretString;
 use sqlx::postgres::PgPoolOptions;
 use sqlx::{AssertSqlSafe, Connection, PgConnection, PgPool};
+use std::collections::HashSet;
 use std::time::Duration;
 use tokio::time::sleep;
 use uuid::Uuid;
@@ -31,10 +36,12 @@ pub struct TestUser {
 }
 
 impl TestUser {
+    #[allow(dead_code)]
     pub fn generate_user() -> Self {
         Self::generate_with_role(Role::User)
     }
 
+    #[allow(dead_code)]
     pub fn generate_admin() -> Self {
         Self::generate_with_role(Role::Admin)
     }
@@ -86,6 +93,10 @@ impl TestUser {
 
 // Ensure that the `tracing` stack is only initialised once using `once_cell`
 static TRACING: Lazy<()> = Lazy::new(|| {
+    // A test binary that captures logs installs its own subscriber first.
+    if tracing::dispatcher::has_been_set() {
+        return;
+    }
     let logging = LoggingSettings {
         level: LoggingLevel::Debug,
         format: LogFormat::Pretty,
@@ -120,6 +131,8 @@ pub struct TestApp {
     pub api_client: reqwest::Client,
     #[allow(dead_code)]
     pub email_server: wiremock::MockServer,
+    #[allow(dead_code)]
+    pub workers: usize,
 }
 impl TestApp {
     #[allow(dead_code)]
@@ -343,6 +356,15 @@ pub struct StoredUser {
 
 // Launch the application in the background
 pub async fn spawn_app(idempotency_engine: IdempotencyEngine) -> TestApp {
+    spawn_app_with(idempotency_engine, |_| {}).await
+}
+
+/// Like `spawn_app`, but lets the test adjust the configuration before the
+/// application is built.
+pub async fn spawn_app_with(
+    idempotency_engine: IdempotencyEngine,
+    customise: impl FnOnce(&mut Settings),
+) -> TestApp {
     // The first time `initialize` is invoked the code in `TRACING` is executed.
     // All other invocations will instead skip execution.
     Lazy::force(&TRACING);
@@ -372,6 +394,7 @@ pub async fn spawn_app(idempotency_engine: IdempotencyEngine) -> TestApp {
         // rides out contention spikes.
         c.database.max_connections = Some(2);
         c.database.timeout_seconds = Some(15);
+        customise(&mut c);
 
         c
     };
@@ -388,6 +411,7 @@ pub async fn spawn_app(idempotency_engine: IdempotencyEngine) -> TestApp {
         .await
         .expect("Failed to build application.");
     let application_port = application.port();
+    let workers = application.workers();
 
     // Launch the server as a background task
     // tokio::spawn returns a handle to the spawned future,
@@ -409,6 +433,7 @@ pub async fn spawn_app(idempotency_engine: IdempotencyEngine) -> TestApp {
         configuration,
         api_client,
         email_server,
+        workers,
     }
 }
 
@@ -637,4 +662,47 @@ impl TestApp {
         );
         user.id
     }
+
+    /// Store `farm` as is (no category or product links).
+    #[allow(dead_code)]
+    pub async fn insert_farm(&self, farm: &TestFarm) {
+        sqlx::query!(
+            r#"
+            INSERT INTO farms (id, name, address, canton, coordinates, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+            farm.id,
+            &farm.name as &Name,
+            &farm.address as &Address,
+            &farm.canton as &Canton,
+            &farm.coordinates as &Point,
+            farm.created_at,
+        )
+        .execute(&self.db_pool)
+        .await
+        .expect("Failed to insert test farm.");
+    }
+
+    /// Store `n` random valid farms, oldest first: each was created a second
+    /// after the one before it, and no two share coordinates (to 6 places).
+    #[allow(dead_code)]
+    pub async fn seed_farms(&self, n: usize) -> Vec<TestFarm> {
+        let now = Utc::now();
+        let mut taken = HashSet::with_capacity(n);
+        let mut farms = Vec::with_capacity(n);
+        while farms.len() < n {
+            let farm = generate_valid_farm();
+            if !taken.insert(farm.coordinates.to_fixed_key(6)) {
+                continue;
+            }
+            let age = chrono::Duration::seconds((n - farms.len()) as i64);
+            let farm = TestFarm {
+                created_at: now - age,
+                ..farm
+            };
+            self.insert_farm(&farm).await;
+            farms.push(farm);
+        }
+        farms
+    }
 }
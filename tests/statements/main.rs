@@ -0,0 +1,202 @@
+//! Reads the statement counts and spans off the request logs. The subscriber
+//! capturing them is process-wide, hence a test binary of its own.
+
+#[path = "../common/mod.rs"]
+mod helpers;
+
+use crate::helpers::{insert_test_farm, seed_test_taxonomy, spawn_app};
+use actix_web::http::StatusCode;
+use farms::{
+    configuration::{
+        IdempotencyEngine, LogFormat, LoggingLevel, LoggingSettings, TelemetrySettings,
+    },
+    telemetry::init_telemetry,
+    test_util::generate_valid_farm,
+};
+use std::{io::Write, sync::Mutex, time::Duration};
+use uuid::Uuid;
+
+/// Every Bunyan log line written so far.
+static LOGS: Mutex<Vec<u8>> = Mutex::new(Vec::new());
+
+/// Held by each test for its whole run, as they clear `LOGS` between requests.
+static ONE_AT_A_TIME: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+struct CapturedLogs;
+
+impl Write for CapturedLogs {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        LOGS.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+fn capture_logs() {
+    static INIT: std::sync::Once = std::sync::Once::new();
+    INIT.call_once(|| {
+        let logging = LoggingSettings {
+            level: LoggingLevel::Info,
+            format: LogFormat::Bunyan,
+        };
+        let telemetry = TelemetrySettings {
+            enabled: false,
+            service_name: "farms-tests".to_string(),
+            endpoint: "".to_string(),
+            environment: "test".to_string(),
+        };
+        init_telemetry(logging, telemetry, || CapturedLogs).expect("Failed to init logging");
+    });
+}
+
+/// `db_statements` of the completed `method` request to `target`, waiting a
+/// little for its span to close after the response went out.
+async fn logged_statements(method: &str, target: &str) -> u64 {
+    for _ in 0..50 {
+        let logs = String::from_utf8(LOGS.lock().unwrap().clone()).unwrap();
+        let statements = logs
+            .lines()
+            .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+            .find(|entry| {
+                entry["msg"] == "[HTTP REQUEST - END]"
+                    && entry["http.method"] == method
+                    && entry["http.target"] == target
+            })
+            .and_then(|entry| entry["db_statements"].as_u64());
+        if let Some(statements) = statements {
+            return statements;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    panic!("No completed {method} {target} request was logged.");
+}
+
+/// The log entry of a span named `span` closing while serving a `method`
+/// request to `target`, with the fields recorded on it.
+async fn logged_span_end(span: &str, method: &str, target: &str) -> Option<serde_json::Value> {
+    logged_statements(method, target).await;
+    let end = format!("[{} - END]", span.to_uppercase());
+    let logs = String::from_utf8(LOGS.lock().unwrap().clone()).unwrap();
+    logs.lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .find(|entry| {
+            entry["msg"] == end.as_str()
+                && entry["http.method"] == method
+                && entry["http.target"] == target
+        })
+}
+
+/// Whether a span named `span` closed while serving a `GET` of `target`.
+async fn logged_span(span: &str, target: &str) -> bool {
+    logged_span_end(span, "GET", target).await.is_some()
+}
+
+/// Posts a fresh farm to a new app on `engine`, returning the statement
+/// count of the request and of replaying it with the same idempotency key.
+async fn create_and_replay(engine: IdempotencyEngine) -> (u64, u64) {
+    LOGS.lock().unwrap().clear();
+    let app = spawn_app(engine).await;
+    seed_test_taxonomy(&app.db_pool).await;
+    app.log_in_active_user().await;
+    let farm = generate_valid_farm().to_json(Uuid::new_v4());
+
+    let response = app.post_farm(&farm).await;
+    assert_eq!(StatusCode::CREATED.as_u16(), response.status().as_u16());
+    let created = logged_statements("POST", "/farms").await;
+
+    LOGS.lock().unwrap().clear();
+    let response = app.post_farm(&farm).await;
+    assert_eq!(StatusCode::CREATED.as_u16(), response.status().as_u16());
+    let replayed = logged_statements("POST", "/farms").await;
+
+    (created, replayed)
+}
+
+#[tokio::test]
+async fn create_logs_how_many_statements_it_ran() {
+    capture_logs();
+    let _one_at_a_time = ONE_AT_A_TIME.lock().await;
+
+    // BEGIN, the duplicate lookup, the farm, its categories, its products,
+    // the audit entry and COMMIT. Without a key nothing is replayed, so the
+    // second request inserts again.
+    assert_eq!((7, 7), create_and_replay(IdempotencyEngine::None).await);
+    // The same; the key lives outside Postgres, so a replay only sends the
+    // BEGIN and COMMIT around looking it up.
+    assert_eq!((7, 2), create_and_replay(IdempotencyEngine::Redis).await);
+    assert_eq!((7, 2), create_and_replay(IdempotencyEngine::Memory).await);
+    // Claiming the key and saving the response add an INSERT and an UPDATE.
+    // A replay is BEGIN, the claim's INSERT, reading the saved response and
+    // COMMIT.
+    assert_eq!((9, 4), create_and_replay(IdempotencyEngine::Postgres).await);
+}
+
+#[tokio::test]
+async fn listing_only_summary_fields_skips_the_full_farm_query() {
+    capture_logs();
+    let _one_at_a_time = ONE_AT_A_TIME.lock().await;
+    LOGS.lock().unwrap().clear();
+    let app = spawn_app(IdempotencyEngine::None).await;
+    insert_test_farm(&app.db_pool, "Summary Farm").await;
+
+    let narrow = "/farms?fields=id,name";
+    let response = app
+        .api_client
+        .get(format!("{}{narrow}", app.address))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::OK.as_u16(), response.status().as_u16());
+    let body: serde_json::Value = response.json().await.unwrap();
+    let farm = body["farms"][0].as_object().unwrap();
+    assert_eq!(vec!["id", "name"], farm.keys().collect::<Vec<_>>());
+    assert!(logged_span("Query farm summaries page", narrow).await);
+    assert!(!logged_span("Query farms page", narrow).await);
+
+    // Coordinates are not a summary field, so the full query runs.
+    let full = "/farms?fields=id,coordinates";
+    let response = app
+        .api_client
+        .get(format!("{}{full}", app.address))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::OK.as_u16(), response.status().as_u16());
+    assert!(logged_span("Query farms page", full).await);
+    assert!(!logged_span("Query farm summaries page", full).await);
+}
+
+#[tokio::test]
+async fn create_records_the_trimmed_fields_on_its_span() {
+    capture_logs();
+    let _one_at_a_time = ONE_AT_A_TIME.lock().await;
+    LOGS.lock().unwrap().clear();
+    let app = spawn_app(IdempotencyEngine::None).await;
+    seed_test_taxonomy(&app.db_pool).await;
+    app.log_in_active_user().await;
+    let idempotency_key = Uuid::new_v4();
+
+    let response = app
+        .post_farm(&serde_json::json!({
+            "name": "  Hof Sonnenberg ",
+            "address": " Road 1, 8000 Zürich\t",
+            "canton": " zh ",
+            "coordinates": " 47.3769,8.5417 ",
+            "products": [" strawberries "],
+            "idempotency_key": format!(" {idempotency_key} "),
+        }))
+        .await;
+    assert_eq!(StatusCode::CREATED.as_u16(), response.status().as_u16());
+
+    let span = logged_span_end("Adding a new farm", "POST", "/farms")
+        .await
+        .expect("The create span was not logged.");
+    assert_eq!("Hof Sonnenberg", span["create_name"]);
+    assert_eq!("Road 1, 8000 Zürich", span["create_address"]);
+    assert_eq!("ZH", span["create_canton"]);
+    assert_eq!("47.3769,8.5417", span["create_coordinates"]);
+    assert_eq!(idempotency_key.to_string(), span["idempotency_key"]);
+}